@@ -0,0 +1,43 @@
+#[macro_use]
+extern crate criterion;
+extern crate ht16k33;
+extern crate led_bargraph;
+
+use criterion::{black_box, Criterion};
+
+use ht16k33::i2c_mock::I2cMock;
+
+use led_bargraph::{Bargraph, Frame};
+
+const ADDRESS: u8 = 0;
+
+fn update(c: &mut Criterion) {
+    let mut bargraph = Bargraph::new(I2cMock::new(None), ADDRESS, None);
+    bargraph.initialize().unwrap();
+
+    // Sweep the value like a VU-meter would, so every update changes the display.
+    let mut value = 0;
+    c.bench_function("update", |b| {
+        b.iter(|| {
+            value = (value + 1) % 25;
+            bargraph.update(black_box(value), 24, false).unwrap();
+        })
+    });
+}
+
+fn from_value(c: &mut Criterion) {
+    c.bench_function("Frame::from_value", |b| {
+        b.iter(|| Frame::from_value(black_box(12), black_box(24)))
+    });
+}
+
+fn show(c: &mut Criterion) {
+    let mut bargraph = Bargraph::new(I2cMock::new(None), ADDRESS, None);
+    bargraph.initialize().unwrap();
+    bargraph.update(12, 24, false).unwrap();
+
+    c.bench_function("show", |b| b.iter(|| bargraph.show().unwrap()));
+}
+
+criterion_group!(benches, update, from_value, show);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+#ifndef LED_BARGRAPH_H
+#define LED_BARGRAPH_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Returned by the `bargraph_*` functions when they succeed.
+#define BARGRAPH_OK 0
+
+// Returned by the `bargraph_*` functions when they fail.
+#define BARGRAPH_ERROR -1
+
+// An opaque handle to a Bargraph, created with `bargraph_new()` and destroyed
+// with `bargraph_free()`.
+typedef struct Bargraph Bargraph;
+
+// Create & initialize a Bargraph.
+//
+// Returns `NULL` if the I2C device can't be opened or initialized.
+//
+// # Arguments
+//
+// * `i2c_path` - Path to the I2C device (e.g. `/dev/i2c-1`), or `NULL` to use a mock
+//   I2C device, useful when no device is available.
+// * `i2c_address` - Address of the I2C device (e.g. `0x70`).
+//
+// # Safety
+//
+// `i2c_path` must be `NULL` or a valid, NUL-terminated string.
+Bargraph *bargraph_new(const char *i2c_path, uint8_t i2c_address);
+
+// Update the Bargraph display, see [Bargraph::try_update()](../struct.Bargraph.html#method.try_update).
+//
+// Returns `BARGRAPH_OK` on success, otherwise `BARGRAPH_ERROR`, including when `range`
+// is `0` or greater than the display's resolution, or `value` is greater than `range`.
+//
+// # Safety
+//
+// `bargraph` must be `NULL` or a handle returned by `bargraph_new()`.
+int bargraph_update(Bargraph *bargraph, uint8_t value, uint8_t range);
+
+// Clear the Bargraph display.
+//
+// Returns `BARGRAPH_OK` on success, otherwise `BARGRAPH_ERROR`.
+//
+// # Safety
+//
+// `bargraph` must be `NULL` or a handle returned by `bargraph_new()`.
+int bargraph_clear(Bargraph *bargraph);
+
+// Destroy a Bargraph, the display is left as-is.
+//
+// # Safety
+//
+// `bargraph` must be `NULL` or a handle returned by `bargraph_new()`, and must not
+// be used after it's freed.
+void bargraph_free(Bargraph *bargraph);
+
+#endif  /* LED_BARGRAPH_H */
@@ -0,0 +1,685 @@
+//! # AlphaNum
+//!
+//! A driver for the [Adafruit Quad Alphanumeric Display w/I2C Backpack](https://www.adafruit.com/product/1911).
+use hal::blocking::i2c::{Write, WriteRead};
+
+use ht16k33::{Dimming, Display, DisplayDataAddress, LedLocation, Oscillator, HT16K33};
+
+use slog::Drain;
+
+use super::{Error, Ht16k33Display, Operation, BRIGHTNESS_MAX};
+
+/// The number of digits on the display.
+pub const ALPHANUM_DIGITS: u8 = 4;
+
+// The first printable ASCII character in the font.
+const FONT_OFFSET: u8 = b' ';
+
+// The segments to enable for each printable ASCII character (`' '` to `'~'`), bits
+// 0-13 are segments A, B, C, D, E, F, G1, G2, H, J, K, L, M, N.
+const FONT: [u16; 95] = [
+    0b0000_0000_0000_0000, // ' '
+    0b0000_0000_0000_0110, // !
+    0b0000_0010_0010_0000, // "
+    0b0001_0010_1100_1110, // #
+    0b0001_0010_1110_1101, // $
+    0b0000_1100_0010_0100, // %
+    0b0010_0011_0101_1101, // &
+    0b0000_0100_0000_0000, // '
+    0b0010_0100_0000_0000, // (
+    0b0000_1001_0000_0000, // )
+    0b0011_1111_1100_0000, // *
+    0b0001_0010_1100_0000, // +
+    0b0000_1000_0000_0000, // ,
+    0b0000_0000_1100_0000, // -
+    0b0100_0000_0000_0000, // .
+    0b0000_1100_0000_0000, // /
+    0b0000_1100_0011_1111, // 0
+    0b0000_0000_0000_0110, // 1
+    0b0000_0000_1101_1011, // 2
+    0b0000_0000_1000_1111, // 3
+    0b0000_0000_1110_0110, // 4
+    0b0010_0000_0110_1001, // 5
+    0b0000_0000_1111_1101, // 6
+    0b0000_0000_0000_0111, // 7
+    0b0000_0000_1111_1111, // 8
+    0b0000_0000_1110_1111, // 9
+    0b0001_0010_0000_0000, // :
+    0b0000_1010_0000_0000, // ;
+    0b0010_0100_0000_0000, // <
+    0b0000_0000_1100_1000, // =
+    0b0000_1001_0000_0000, // >
+    0b0001_0000_1000_0011, // ?
+    0b0000_0010_1011_1011, // @
+    0b0000_0000_1111_0111, // A
+    0b0001_0010_1000_1111, // B
+    0b0000_0000_0011_1001, // C
+    0b0001_0010_0000_1111, // D
+    0b0000_0000_1111_1001, // E
+    0b0000_0000_0111_0001, // F
+    0b0000_0000_1011_1101, // G
+    0b0000_0000_1111_0110, // H
+    0b0001_0010_0000_1001, // I
+    0b0000_0000_0001_1110, // J
+    0b0010_0100_0111_0000, // K
+    0b0000_0000_0011_1000, // L
+    0b0000_0101_0011_0110, // M
+    0b0010_0001_0011_0110, // N
+    0b0000_0000_0011_1111, // O
+    0b0000_0000_1111_0011, // P
+    0b0010_0000_0011_1111, // Q
+    0b0010_0000_1111_0011, // R
+    0b0000_0000_1110_1101, // S
+    0b0001_0010_0000_0001, // T
+    0b0000_0000_0011_1110, // U
+    0b0000_1100_0011_0000, // V
+    0b0010_1000_0011_0110, // W
+    0b0010_1101_0000_0000, // X
+    0b0001_0101_0000_0000, // Y
+    0b0000_1100_0000_1001, // Z
+    0b0000_0000_0011_1001, // [
+    0b0010_0001_0000_0000, // \
+    0b0000_0000_0000_1111, // ]
+    0b0000_1100_0000_0011, // ^
+    0b0000_0000_0000_1000, // _
+    0b0000_0001_0000_0000, // `
+    0b0001_0000_0101_1000, // a
+    0b0010_0000_0111_1000, // b
+    0b0000_0000_1101_1000, // c
+    0b0000_1000_1000_1110, // d
+    0b0000_1000_0101_1000, // e
+    0b0000_0000_0111_0001, // f
+    0b0000_0100_1000_1110, // g
+    0b0001_0000_0111_0000, // h
+    0b0001_0000_0000_0000, // i
+    0b0000_0000_0000_1110, // j
+    0b0011_0110_0000_0000, // k
+    0b0000_0000_0011_0000, // l
+    0b0001_0000_1101_0100, // m
+    0b0001_0000_0101_0000, // n
+    0b0000_0000_1101_1100, // o
+    0b0000_0001_0111_0000, // p
+    0b0000_0100_1000_0110, // q
+    0b0000_0000_0101_0000, // r
+    0b0010_0000_1000_1000, // s
+    0b0000_0000_0111_1000, // t
+    0b0000_0000_0001_1100, // u
+    0b0010_0000_0000_0100, // v
+    0b0010_1000_0001_0100, // w
+    0b0010_1000_1100_0000, // x
+    0b0010_0000_0000_1100, // y
+    0b0000_1000_0100_1000, // z
+    0b0000_1001_0100_1001, // {
+    0b0001_0010_0000_0000, // |
+    0b0010_0100_1000_1001, // }
+    0b0000_0101_0010_0000, // ~
+];
+
+// Bit 14 is the decimal point of each digit.
+const DECIMAL_POINT_SEGMENT: u8 = 14;
+
+/// The alphanumeric display state.
+pub struct AlphaNum4<I2C> {
+    device: HT16K33<I2C>,
+    logger: slog::Logger,
+}
+
+impl<I2C, E> AlphaNum4<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Create an AlphaNum4 for display.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C device to communicate with the `HT16K33` chip.
+    /// * `i2c_address` - The I2C address of the `HT16K33` chip.
+    /// * `logger` - A logging instance.
+    ///
+    /// # Notes
+    ///
+    /// `logger = None` will log to the `slog-stdlog` drain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate ht16k33;
+    /// extern crate led_bargraph;
+    ///
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    ///
+    /// let mut i2c = I2cMock::new(None);
+    /// let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    ///
+    /// # }
+    /// ```
+    pub fn new<L>(i2c: I2C, i2c_address: u8, logger: L) -> Self
+    where
+        L: Into<Option<slog::Logger>>,
+    {
+        let logger = logger
+            .into()
+            .unwrap_or_else(|| slog::Logger::root(slog_stdlog::StdLog.fuse(), o!()));
+
+        trace!(logger, "Constructing AlphaNum4");
+
+        let ht16k33_logger = logger.new(o!("mod" => "HT16K33"));
+        let ht16k33 = HT16K33::new(i2c, i2c_address, ht16k33_logger);
+
+        AlphaNum4 {
+            device: ht16k33,
+            logger,
+        }
+    }
+
+    /// Initialize the display & the connected `HT16K33` device, leaving the display
+    /// cleared and turned on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.initialize().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn initialize(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "initialize");
+
+        self.device.initialize().map_err(Error::i2c(
+            Operation::Initialize,
+            Oscillator::COMMAND.bits(),
+        ))?;
+        self.device
+            .set_display(Display::ON)
+            .map_err(Error::i2c(Operation::Initialize, Display::COMMAND.bits()))
+    }
+
+    /// Clear the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.clear().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn clear(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "clear");
+
+        self.device.clear_display_buffer();
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Set a digit to an ASCII character, optionally with its decimal point.
+    ///
+    /// Characters without a glyph (anything other than printable ASCII) are shown
+    /// blank.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Which digit to set, from `0` (left) to `3` (right).
+    /// * `character` - The character to display.
+    /// * `decimal_point` - Whether to enable the decimal point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.set_char(0, 'A', false);
+    /// alphanum.set_char(1, '7', true);
+    /// alphanum.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_char(&mut self, position: u8, character: char, decimal_point: bool) {
+        trace!(self.logger, "set_char"; "position" => position,
+               "character" => format!("{:?}", character), "decimal_point" => decimal_point);
+
+        let mut segments = Self::glyph(character);
+        if decimal_point {
+            segments |= 1 << DECIMAL_POINT_SEGMENT;
+        }
+
+        self.set_segments(position, segments);
+    }
+
+    /// Set the digits to a string, left-aligned.
+    ///
+    /// A `.` following a character enables that character's decimal point instead of
+    /// using a digit of its own. Any digits not covered by the string are cleared, and
+    /// any characters beyond the last digit are ignored.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.write_str("3.14");
+    /// alphanum.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn write_str(&mut self, value: &str) {
+        trace!(self.logger, "write_str"; "value" => value);
+
+        let mut digits = [0u16; ALPHANUM_DIGITS as usize];
+        let mut position = 0;
+        let mut characters = value.chars().peekable();
+
+        while let Some(character) = characters.next() {
+            if position == digits.len() {
+                break;
+            }
+
+            digits[position] = Self::glyph(character);
+
+            if character != '.' && characters.peek() == Some(&'.') {
+                characters.next();
+                digits[position] |= 1 << DECIMAL_POINT_SEGMENT;
+            }
+
+            position += 1;
+        }
+
+        for (position, segments) in digits.iter().enumerate() {
+            self.set_segments(position as u8, *segments);
+        }
+    }
+
+    /// Set which segments of a digit are enabled, for displaying custom characters.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Which digit to set, from `0` (left) to `3` (right).
+    /// * `segments` - Bits `0` to `13` enable segments `A`, `B`, `C`, `D`, `E`, `F`,
+    ///   `G1`, `G2`, `H`, `J`, `K`, `L`, `M` & `N`, bit `14` enables the decimal point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    ///
+    /// // Display an asterisk.
+    /// alphanum.set_segments(0, 0b0011_1111_1100_0000);
+    /// alphanum.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_segments(&mut self, position: u8, segments: u16) {
+        trace!(self.logger, "set_segments"; "position" => position,
+               "segments" => format!("{:#018b}", segments));
+
+        assert!(
+            position < ALPHANUM_DIGITS,
+            "Digit position [{}] must be less than [{}]",
+            position,
+            ALPHANUM_DIGITS
+        );
+
+        // Each digit spans two rows, the low byte followed by the high byte.
+        let row = position * 2;
+
+        for segment in 0..16 {
+            let location = LedLocation::new(row + segment / 8, segment % 8).unwrap();
+            self.device
+                .update_display_buffer(location, segments & (1 << segment) != 0);
+        }
+    }
+
+    /// Write the digits to the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "flush");
+
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Enable/Disable continuous blinking of the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.set_blink(true).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_blink"; "enabled" => enabled);
+
+        let display = if enabled {
+            Display::ONE_HZ
+        } else {
+            Display::ON
+        };
+
+        self.device
+            .set_display(display)
+            .map_err(Error::i2c(Operation::SetBlink, Display::COMMAND.bits()))
+    }
+
+    /// Set the brightness of the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::AlphaNum4;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut alphanum = AlphaNum4::new(i2c, address, None);
+    /// alphanum.set_brightness(8).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_brightness"; "brightness" => brightness);
+
+        assert!(
+            brightness <= BRIGHTNESS_MAX,
+            "Brightness [{}] must be less than or equal to [{}]",
+            brightness,
+            BRIGHTNESS_MAX
+        );
+
+        self.device
+            .set_dimming(Dimming::from_bits_truncate(brightness))
+            .map_err(Error::i2c(
+                Operation::SetBrightness,
+                Dimming::COMMAND.bits(),
+            ))
+    }
+
+    // Lookup the segments for a character, blank if there's no glyph.
+    fn glyph(character: char) -> u16 {
+        if character.is_ascii() && !character.is_ascii_control() {
+            FONT[(character as u8 - FONT_OFFSET) as usize]
+        } else {
+            0
+        }
+    }
+}
+
+impl<I2C, E> Ht16k33Display for AlphaNum4<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn initialize(&mut self) -> Result<(), Error<E>> {
+        AlphaNum4::initialize(self)
+    }
+
+    fn clear(&mut self) -> Result<(), Error<E>> {
+        AlphaNum4::clear(self)
+    }
+
+    fn flush(&mut self) -> Result<(), Error<E>> {
+        AlphaNum4::flush(self)
+    }
+
+    fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        AlphaNum4::set_blink(self, enabled)
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        AlphaNum4::set_brightness(self, brightness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    // Retrieve the segments of a digit from the display buffer.
+    fn segments(alphanum: &AlphaNum4<I2cMock>, position: usize) -> u16 {
+        let buffer = alphanum.device.display_buffer();
+
+        u16::from(buffer[position * 2].bits()) | u16::from(buffer[position * 2 + 1].bits()) << 8
+    }
+
+    #[test]
+    fn new() {
+        let i2c = I2cMock::new(None);
+        let _alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+    }
+
+    #[test]
+    fn initialize() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+        alphanum.initialize().unwrap();
+
+        assert_eq!(Display::ON, *alphanum.device.display());
+    }
+
+    #[test]
+    fn clear() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+        alphanum.set_char(0, '8', true);
+
+        alphanum.clear().unwrap();
+
+        for row in alphanum.device.display_buffer().iter() {
+            assert_eq!(0, row.bits());
+        }
+    }
+
+    #[test]
+    fn set_char() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.set_char(0, 'A', false);
+        alphanum.set_char(1, 'T', true);
+        alphanum.set_char(2, ' ', false);
+        alphanum.set_char(3, '~', false);
+
+        assert_eq!(0b0000_0000_1111_0111, segments(&alphanum, 0));
+        assert_eq!(0b0101_0010_0000_0001, segments(&alphanum, 1));
+        assert_eq!(0, segments(&alphanum, 2));
+        assert_eq!(0b0000_0101_0010_0000, segments(&alphanum, 3));
+    }
+
+    #[test]
+    fn set_char_without_glyph() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.set_char(0, '\u{258A}', false);
+        alphanum.set_char(1, '\n', false);
+
+        assert_eq!(0, segments(&alphanum, 0));
+        assert_eq!(0, segments(&alphanum, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_char_position_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.set_char(4, 'A', false);
+    }
+
+    #[test]
+    fn write_str() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.write_str("3.14");
+
+        assert_eq!(0b0100_0000_1000_1111, segments(&alphanum, 0));
+        assert_eq!(FONT[(b'1' - FONT_OFFSET) as usize], segments(&alphanum, 1));
+        assert_eq!(FONT[(b'4' - FONT_OFFSET) as usize], segments(&alphanum, 2));
+        assert_eq!(0, segments(&alphanum, 3));
+    }
+
+    #[test]
+    fn write_str_truncates() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.write_str("ABCDE");
+
+        assert_eq!(FONT[(b'D' - FONT_OFFSET) as usize], segments(&alphanum, 3));
+    }
+
+    #[test]
+    fn write_str_clears_remaining_digits() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.write_str("ABCD");
+        alphanum.write_str("..");
+
+        assert_eq!(FONT[(b'.' - FONT_OFFSET) as usize], segments(&alphanum, 0));
+        assert_eq!(FONT[(b'.' - FONT_OFFSET) as usize], segments(&alphanum, 1));
+        assert_eq!(0, segments(&alphanum, 2));
+        assert_eq!(0, segments(&alphanum, 3));
+    }
+
+    #[test]
+    fn flush() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+        alphanum.write_str("RUST");
+
+        alphanum.flush().unwrap();
+    }
+
+    #[test]
+    fn set_blink() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.set_blink(true).unwrap();
+        assert_eq!(Display::ONE_HZ, *alphanum.device.display());
+
+        alphanum.set_blink(false).unwrap();
+        assert_eq!(Display::ON, *alphanum.device.display());
+    }
+
+    #[test]
+    fn set_brightness() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.set_brightness(0).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MIN, *alphanum.device.dimming());
+
+        alphanum.set_brightness(BRIGHTNESS_MAX).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MAX, *alphanum.device.dimming());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_brightness_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut alphanum = AlphaNum4::new(i2c, ADDRESS, None);
+
+        alphanum.set_brightness(BRIGHTNESS_MAX + 1).unwrap();
+    }
+}
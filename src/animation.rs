@@ -0,0 +1,445 @@
+//! # Animation
+//!
+//! Built-in animations for confirming a long-running mode started or stopped, e.g. a
+//! green sweep at startup & a slow red sweep at shutdown, or for an idle display.
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{Frame, LedColor};
+
+/// The shape of an [Animation](struct.Animation.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    /// A single bar moving from the bottom to the top of the display.
+    Sweep,
+    /// The bars filling from the bottom to the top of the display.
+    Fill,
+    /// A few bars lit at random across the display, changing every frame.
+    Sparkle,
+    /// Drops falling at random from the top to the bottom of the display.
+    Rain,
+    /// Conway's Game of Life on 2 rows of cells, the green & red LEDs of the bars,
+    /// starting again at random once it dies out or stops changing. The color isn't
+    /// used, the bars are green, red or yellow (both) by the cells alive.
+    Life,
+    /// The color wiping over the display from the bottom to the top, followed by
+    /// each other color in turn.
+    Wipe,
+}
+
+/// A built-in animation, played by [Bargraph::play()](struct.Bargraph.html#method.play).
+///
+/// An Animation can be parsed from `<pattern>[:<color>[:<interval>]]`, where the
+/// pattern is `sweep`, `fill`, `sparkle`, `rain`, `life` or `wipe`, the color is a [LedColor](enum.LedColor.html) &
+/// the interval is in milliseconds, e.g. `sweep:red:100`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{Animation, LedColor, Pattern};
+/// use std::time::Duration;
+/// # fn main() {
+///
+/// let animation: Animation = "sweep:red:100".parse().unwrap();
+///
+/// assert_eq!(Pattern::Sweep, animation.pattern);
+/// assert_eq!(LedColor::Red, animation.color);
+/// assert_eq!(Duration::from_millis(100), animation.interval);
+///
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Animation {
+    /// The shape of the animation.
+    pub pattern: Pattern,
+    /// The color of the lit bars, green by default.
+    pub color: LedColor,
+    /// How long each frame is displayed for, `40ms` by default.
+    pub interval: Duration,
+}
+
+impl Animation {
+    /// Create an Animation of the pattern in green, see [Animation](struct.Animation.html)
+    /// for the defaults.
+    pub fn new(pattern: Pattern) -> Self {
+        Animation {
+            pattern,
+            color: LedColor::Green,
+            interval: Duration::from_millis(40),
+        }
+    }
+
+    /// The frames of the animation on a display with `resolution` bars, each shown for
+    /// the interval, e.g. to render them to other outputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    pub fn frames(&self, resolution: u8) -> Vec<Frame> {
+        let size = usize::from(resolution);
+        let color = self.color;
+
+        // The same "random" frames are played every time.
+        let mut random = RANDOM_SEED;
+        let mut next_random = move || {
+            random = xorshift(random);
+            random as usize
+        };
+
+        match self.pattern {
+            Pattern::Sweep => (0..size)
+                .map(|step| frame(resolution, |bars| bars[step] = color))
+                .collect(),
+            Pattern::Fill => (0..size)
+                .map(|step| frame(resolution, |bars| fill(&mut bars[..=step], color)))
+                .collect(),
+            Pattern::Sparkle => (0..size)
+                .map(|_| {
+                    frame(resolution, |bars| {
+                        for _ in 0..SPARKLE_BARS {
+                            bars[next_random() % size] = color;
+                        }
+                    })
+                })
+                .collect(),
+            Pattern::Rain => {
+                let mut drops = vec![false; size];
+
+                (0..size * 2)
+                    .map(|_| {
+                        // Every drop falls a bar, & a new drop starts at the top a third
+                        // of the time.
+                        drops.remove(0);
+                        drops.push(next_random() % 3 == 0);
+
+                        frame(resolution, |bars| {
+                            for (bar, drop) in bars.iter_mut().zip(drops.iter()) {
+                                if *drop {
+                                    *bar = color;
+                                }
+                            }
+                        })
+                    })
+                    .collect()
+            }
+            Pattern::Life => {
+                let mut seed = || -> Vec<[bool; 2]> {
+                    (0..size)
+                        .map(|_| [next_random() % 3 == 0, next_random() % 3 == 0])
+                        .collect()
+                };
+                let mut cells = seed();
+
+                (0..size * 2)
+                    .map(|_| {
+                        let shown = frame(resolution, |bars| {
+                            for (bar, cell) in bars.iter_mut().zip(cells.iter()) {
+                                *bar = match *cell {
+                                    [false, false] => LedColor::Off,
+                                    [true, false] => LedColor::Green,
+                                    [false, true] => LedColor::Red,
+                                    [true, true] => LedColor::Yellow,
+                                };
+                            }
+                        });
+
+                        let next = life(&cells);
+                        cells = if next == cells || next.iter().all(|cell| *cell == [false; 2]) {
+                            seed()
+                        } else {
+                            next
+                        };
+
+                        shown
+                    })
+                    .collect()
+            }
+            Pattern::Wipe => {
+                let mut colors = vec![color];
+                colors.extend(WIPE_COLORS.iter().filter(|other| **other != color));
+
+                // The first color wipes over the last, so the animation repeats smoothly.
+                let previous = colors.iter().cycle().skip(colors.len() - 1);
+
+                colors
+                    .iter()
+                    .zip(previous)
+                    .flat_map(|(color, previous)| {
+                        (0..size).map(move |step| {
+                            frame(resolution, |bars| {
+                                let (wiped, rest) = bars.split_at_mut(step + 1);
+                                fill(wiped, *color);
+                                fill(rest, *previous);
+                            })
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+// A frame of the bars in use, which are off unless they're drawn.
+fn frame<F>(resolution: u8, draw: F) -> Frame
+where
+    F: FnOnce(&mut [LedColor]),
+{
+    Frame::within(resolution, |bars| {
+        draw(bars);
+        false
+    })
+}
+
+fn fill(bars: &mut [LedColor], color: LedColor) {
+    for bar in bars.iter_mut() {
+        *bar = color;
+    }
+}
+
+// The next generation of the Game of Life, with the 2 rows of cells side by side. The
+// ends of the rows wrap around, the rows don't.
+fn life(cells: &[[bool; 2]]) -> Vec<[bool; 2]> {
+    let size = cells.len();
+
+    (0..size)
+        .map(|index| {
+            let mut next = [false; 2];
+
+            for (row, alive) in next.iter_mut().enumerate() {
+                let neighbors = [size - 1, 0, 1]
+                    .iter()
+                    .map(|offset| &cells[(index + offset) % size])
+                    .flat_map(|column| column.iter().enumerate())
+                    .filter(|&(other, cell)| *cell && (other != row))
+                    .count()
+                    + [size - 1, 1]
+                        .iter()
+                        .filter(|offset| cells[(index + *offset) % size][row])
+                        .count();
+
+                *alive = neighbors == 3 || (neighbors == 2 && cells[index][row]);
+            }
+
+            next
+        })
+        .collect()
+}
+
+// How many bars are lit in each frame of a sparkle, fewer if they're the same bar.
+const SPARKLE_BARS: usize = 3;
+
+// The colors of a wipe, after the animation's color.
+const WIPE_COLORS: [LedColor; 3] = [LedColor::Green, LedColor::Yellow, LedColor::Red];
+
+// Any non-zero seed for the animations' random frames.
+const RANDOM_SEED: u32 = 0x2545_F491;
+
+// The next of a sequence of pseudo-random numbers, good enough to pick bars.
+pub(crate) fn xorshift(mut x: u32) -> u32 {
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    x
+}
+
+/// The error returned when an [Animation](struct.Animation.html) can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseAnimationError {
+    animation: String,
+}
+
+impl fmt::Display for ParseAnimationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid animation [{}], expected `<pattern>[:<color>[:<interval>]]`",
+            self.animation
+        )
+    }
+}
+
+impl error::Error for ParseAnimationError {}
+
+impl FromStr for Animation {
+    type Err = ParseAnimationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_animation(s.trim()).ok_or_else(|| ParseAnimationError {
+            animation: s.to_string(),
+        })
+    }
+}
+
+fn parse_animation(animation: &str) -> Option<Animation> {
+    let mut parts = animation.split(':').map(str::trim);
+
+    let pattern = match parts.next()?.to_lowercase().as_str() {
+        "sweep" => Pattern::Sweep,
+        "fill" => Pattern::Fill,
+        "sparkle" => Pattern::Sparkle,
+        "rain" => Pattern::Rain,
+        "life" => Pattern::Life,
+        "wipe" => Pattern::Wipe,
+        _ => return None,
+    };
+
+    let mut parsed = Animation::new(pattern);
+    if let Some(color) = parts.next() {
+        parsed.color = color.parse().ok()?;
+    }
+    if let Some(interval) = parts.next() {
+        parsed.interval = Duration::from_millis(interval.parse().ok()?);
+    }
+
+    match parts.next() {
+        Some(_) => None,
+        None => Some(parsed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::FromIterator;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Animation::new(Pattern::Sweep)), "sweep".parse());
+        assert_eq!(Ok(Animation::new(Pattern::Sparkle)), "sparkle".parse());
+        assert_eq!(Ok(Animation::new(Pattern::Life)), "life".parse());
+        assert_eq!(
+            Ok(Animation {
+                color: LedColor::Yellow,
+                ..Animation::new(Pattern::Fill)
+            }),
+            "Fill:y".parse()
+        );
+        assert_eq!(
+            Ok(Animation {
+                color: LedColor::Red,
+                interval: Duration::from_millis(100),
+                ..Animation::new(Pattern::Sweep)
+            }),
+            " sweep:red:100 ".parse()
+        );
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        for animation in ["", "spin", "sweep:blue", "sweep:red:fast", "sweep:red:1:2"].iter() {
+            assert_eq!(
+                Err(ParseAnimationError {
+                    animation: animation.to_string(),
+                }),
+                animation.parse::<Animation>()
+            );
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let sweep: Vec<String> = Animation::new(Pattern::Sweep)
+            .frames(3)
+            .iter()
+            .map(|frame| frame.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "G.......................",
+                ".G......................",
+                "..G.....................",
+            ],
+            sweep
+        );
+
+        let fill = Animation::new(Pattern::Fill).frames(24);
+        assert_eq!(24, fill.len());
+        assert_eq!(Frame::from_iter(vec![LedColor::Green; 24]), fill[23]);
+    }
+
+    #[test]
+    fn frames_sparkle() {
+        let sparkle = Animation::new(Pattern::Sparkle).frames(12);
+        assert_eq!(12, sparkle.len());
+
+        for frame in sparkle.iter() {
+            let lit = frame
+                .bars
+                .iter()
+                .filter(|bar| **bar != LedColor::Off)
+                .count();
+            assert!((1..=SPARKLE_BARS).contains(&lit), "{}", frame);
+
+            // Only the bars in use sparkle.
+            assert!(frame.bars[12..].iter().all(|bar| *bar == LedColor::Off));
+        }
+
+        // The sparkle changes, but the same way every time.
+        assert_ne!(sparkle[0], sparkle[1]);
+        assert_eq!(sparkle, Animation::new(Pattern::Sparkle).frames(12));
+    }
+
+    #[test]
+    fn frames_rain() {
+        let rain = Animation::new(Pattern::Rain).frames(12);
+        assert_eq!(24, rain.len());
+
+        // The drops fall a bar each frame.
+        for frames in rain.windows(2) {
+            assert_eq!(frames[0].bars[1..12], frames[1].bars[..11]);
+        }
+        assert!(rain.iter().any(|frame| frame.bars[0] == LedColor::Green));
+    }
+
+    #[test]
+    fn frames_life() {
+        let life = Animation::new(Pattern::Life).frames(24);
+        assert_eq!(48, life.len());
+        assert!(life
+            .iter()
+            .any(|frame| frame.bars.contains(&LedColor::Yellow)));
+        assert_eq!(life, Animation::new(Pattern::Life).frames(24));
+    }
+
+    #[test]
+    fn life_generation() {
+        let mut cells = vec![[false; 2]; 12];
+        for cell in cells[5..8].iter_mut() {
+            cell[0] = true;
+        }
+
+        let mut next = vec![[false; 2]; 12];
+        next[6] = [true, true];
+        assert_eq!(next, life(&cells));
+
+        // Each cell has one neighbor, so they both die.
+        assert_eq!(vec![[false; 2]; 12], life(&next));
+    }
+
+    #[test]
+    fn frames_wipe() {
+        let wipe: Vec<String> = Animation::new(Pattern::Wipe)
+            .frames(3)
+            .iter()
+            .map(|frame| frame.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "GRR.....................",
+                "GGR.....................",
+                "GGG.....................",
+                "YGG.....................",
+                "YYG.....................",
+                "YYY.....................",
+                "RYY.....................",
+                "RRY.....................",
+                "RRR.....................",
+            ],
+            wipe
+        );
+    }
+}
@@ -0,0 +1,722 @@
+//! The command line usage, its arguments & the parsing of their values.
+use led_bargraph::{Animation, LedColor, ParseAnimationError, Thresholds};
+
+use logging::LogTarget;
+
+use std::fmt::Debug;
+use std::result;
+use std::time::Duration;
+
+// Docopts: https://github.com/docopt/docopt.rs
+pub(crate) const USAGE: &str = "
+LED Bargraph.
+
+Usage:
+    led-bargraph [options] clear
+    led-bargraph [options] set <value> <range>
+    led-bargraph [options] set-multi <segment>...
+    led-bargraph [options] show
+    led-bargraph [options] listen <range>
+    led-bargraph [options] fifo <range>
+    led-bargraph [options] shm <range>
+    led-bargraph [options] identify [<mapping-file>]
+    led-bargraph [options] test-leds
+    led-bargraph [options] off
+    led-bargraph [options] rpc
+    led-bargraph [options] status
+    led-bargraph [options] progress <range>
+    led-bargraph [options] copy-progress --path=<path> --expected-size=<size>
+    led-bargraph [options] packages <range>
+    led-bargraph [options] ci <range> --ci-url=<url>
+    led-bargraph [options] mail <range> --imap-url=<url> [--mail-filter=<criteria>]
+    led-bargraph [options] countdown (--remaining=<duration> | --ics-url=<url>)
+    led-bargraph [options] daylight --latitude=<degrees> --longitude=<degrees>
+    led-bargraph [options] co2
+    led-bargraph [options] environment
+    led-bargraph [options] meter <max>
+    led-bargraph [options] bench
+    led-bargraph [options] animate <animation>
+    led-bargraph --help
+
+Commands:
+    clear      Clear the display.
+    set        Display the value against the range.
+    set-multi  Display several values against their ranges side-by-side, each
+               segment is a `<value>/<range>` pair (e.g. `3/6 5/6`).
+    show       Show on-screen the current bargraph display.
+    listen     Display values received as UDP datagrams, either plain-text
+               numbers (e.g. `7`) or OSC messages (e.g. `/bargraph/value 0.7`).
+    fifo       Display values written as lines to a named pipe, which is created
+               if it doesn't exist (e.g. `echo 7 > /run/led-bargraph.fifo`).
+    shm        Display the values written to a ring buffer in the shared memory file
+               `--shm`, which is created if it doesn't exist, `--fps` times a second.
+               For sources too fast for a system call per value, e.g. audio levels.
+               The file is a native-endian `u64` count of the values written, then a
+               ring of 1024 `f32` values: value `n` is written at index `n % 1024`,
+               then the count is incremented.
+    identify   Light each bar in turn to check the display's wiring against the
+               mapping in use. With `--wait`, enter where each lit bar actually
+               is to generate a corrected mapping file.
+    test-leds  Light every LED in turn, the red then the green LED of each bar, to
+               check a newly assembled display. With `--interactive`, ask whether
+               each LED lit correctly, then print a report of the dead & miswired LEDs.
+    status     Print the state of the display as JSON, e.g. for monitoring: whether the
+               device responds, the colors of the bars read back from it, the transport
+               & whether the `listen`, `fifo` & `shm` daemons are running. The display
+               isn't initialized. The HT16K33 can't be read back whether it's blinking or
+               its brightness, the blink is from the `--if-changed` state file, if any.
+               Exits with 1 if the device doesn't respond.
+    rpc        Drive the display with newline-delimited JSON-RPC 2.0 on stdin & stdout,
+               e.g. from another program. The methods are `set_value` (`value` &
+               `range`), `set_bars` (`bars`, a list of colors from the bottom),
+               `clear` & `get_state`.
+    progress   Display the progress of another process reading or writing `--total`
+               bytes, from its `/proc/<pid>/io` with `--pid`, or from the size of a
+               file with `--path`. Stops once complete or the process exits.
+    copy-progress
+               Display the percentage of a file being copied, from its size against
+               `--expected-size`. Once complete & the size stops changing, the display
+               blinks green & the command stops.
+    packages   Display the progress of an apt or dnf install/upgrade piped to stdin,
+               passing the output through to stdout, e.g.
+               `apt-get -o APT::Status-Fd=1 upgrade | led-bargraph packages 24`.
+    ci         Display the progress of a running CI pipeline, polling `--ci-url`: either
+               a GitLab pipeline's jobs
+               (`<gitlab>/api/v4/projects/<id>/pipelines/<id>/jobs`), shown as the
+               finished jobs, or a Jenkins build (`<jenkins>/job/<name>/<build>/api/json`),
+               shown against its estimated duration. A header for authentication, e.g.
+               `PRIVATE-TOKEN: <token>`, is read from `LED_BARGRAPH_CI_HEADER`. Stops
+               once the pipeline finishes, blinking red if it failed.
+    mail       Display the number of unread messages in an IMAP mailbox, polling
+               `--imap-url` (e.g. `imaps://imap.example.com/INBOX`), blinking while any
+               match `--mail-filter`. The `<user>:<password>` to log in with is read from
+               `LED_BARGRAPH_IMAP_USER`.
+    countdown  Drain the display as the next meeting approaches, over `--window` before it
+               starts, blinking red in the final five minutes. The meeting starts after
+               `--remaining`, then the command stops, or is the next event of the ICS
+               calendar at `--ics-url`. Recurring events aren't expanded & times in a
+               `TZID` are taken as local time.
+    daylight   Display how much of today's daylight is left, from the sunrise & sunset
+               at `--latitude` & `--longitude`, until interrupted. The display is empty
+               at night.
+    co2        Display the CO2 concentration measured by an SCD30 or SCD41 sensor on the
+               same I2C bus as the display, from 0 to `--co2-max` ppm, until interrupted.
+               The bars are green, then yellow & red from each of `--co2-thresholds`.
+    environment
+               Display the temperature or humidity measured by a BME280 or BME680
+               sensor on the same I2C bus as the display, on the scale of
+               `--temperature-scale` or `--humidity-scale`, until interrupted. With
+               `--alternate`, the humidity is shown in green between temperatures.
+    meter      Display the current in A, or power in W, measured by an INA219 or INA260
+               on the same I2C bus as the display, against the maximum, until
+               interrupted. The display blinks above the maximum.
+    bench      Measure how long each update of the display takes & the fastest frame
+               rate it sustains, writing `--frames` changing frames as fast as possible,
+               then print a summary. With `--i2c-mock`, measures the overhead without
+               the bus.
+    animate    Repeat an animation until interrupted, as `<pattern>[:<color>[:<interval>]]`
+               where the pattern is `sweep`, `fill`, `sparkle`, `rain`, `life` (the Game of
+               Life on the green & red LEDs of the bars) or `wipe` & the interval is in
+               milliseconds (e.g. `rain:green:80`).
+    off        Switch off the backpack's power for standby, requires `--power-gpio`.
+               Any other command with `--power-gpio` switches it back on.
+
+Arguments:
+    value         The value to display.
+    max           The reading of a full display.
+    range         The range of the bar graph to display.
+    segment       A value & range to display, as `<value>/<range>`.
+    mapping-file  Where `identify` writes the corrected mapping, otherwise it's printed.
+    animation     An animation to play, see `animate`.
+
+Options:
+    --no-init               Do not initialize the device.
+    --trace                 Enable verbose debug logging.
+    -d, --debug             Enable debug logging.
+    -v, --verbose           Enable verbose logging.
+    -s, --show              Show on-screen the current bargraph display.
+    --preview-glyphs=<set>  How the display is shown on-screen: auto, unicode or ascii, e.g. for
+                            serial consoles. `auto` is unicode for UTF-8 locales [default: auto].
+    --preview-colors=<set>  The colors the display is shown on-screen with: auto, none, basic or
+                            extended (256). `auto` is none when the output isn't a terminal or
+                            `NO_COLOR` is set, extended for `256color` terminals [default: auto].
+    --transport=<name>      How to connect to the I2C device: auto, linux, ftdi or mock. `auto`
+                            is `linux` on Linux, otherwise `mock` [default: auto].
+    --i2c-mock              Mock the I2C interface, same as `--transport=mock`.
+    --mock-state=<path>     With the mock I2C interface, keep the display's registers in the file
+                            between runs, e.g. so `show --no-init` shows the value of an earlier
+                            `set` (e.g. `~/.cache/led-bargraph/mock.bin`).
+    --i2c-address=<N>       Address of the I2C device, in decimal [default: 112].
+    --i2c-path=<path>       Path to the I2C device [default: /dev/i2c-1].
+    --power-gpio=<n>        The GPIO number (sysfs) that switches the backpack's power, the
+                            display is then power cycled instead of only initialized.
+    --trace-i2c             Log every I2C transaction, implies `--debug`.
+    --log-target=<target>   Where to log: terminal, syslog or journald, e.g. for daemons. Journald
+                            keeps the key-values of each record as fields, e.g. `VALUE`, with the
+                            display's address as `DEVICE` [default: terminal].
+    --log-file=<path>       Also write the log to the file, e.g. for daemons that don't run under
+                            journald (e.g. `/var/log/led-bargraph.log`).
+    --log-rotate=<when>     With `--log-file`, move the file aside to `<path>.1` once it's the size,
+                            with an optional `K`, `M`, `G` or `T` unit, or `daily` when the date
+                            changes [default: 10M].
+    --log-keep=<n>          With `--log-file`, how many rotated files to keep, the oldest are
+                            removed [default: 5].
+    --simulate-errors=<spec>
+                            Fail a fraction of the I2C transactions on purpose, as `rate=<fraction>`
+                            (e.g. `rate=0.05`), to check how supervision & alerting cope with a
+                            misbehaving bus.
+    --udp-bind=<addr>       Address to listen on for UDP datagrams [default: 0.0.0.0:9000].
+    --fifo=<path>           Path of the named pipe to read values from [default: /run/led-bargraph.fifo].
+    --shm=<path>            Path of the shared memory file to read values from
+                            [default: /dev/shm/led-bargraph].
+    --fps=<n>               With `listen`, `fifo` or `shm`, the most times a second the display
+                            is updated, values received in between are coalesced to the latest
+                            [default: 30].
+    --normalized            Treat received values as fractions (0.0 - 1.0) of the range.
+    --smoothing=<factor>    Smooth the values of `listen`, `fifo`, `shm` or a polled command,
+                            keeping the fraction (0.0 - below 1.0) of the average for each new
+                            value.
+    --alert-above=<percent>
+                            Blink while the values of `listen`, `fifo`, `shm` or a polled command
+                            are at or above the percentage of the range.
+    --mapping=<path>        Load the wiring of a hand-wired or clone display from a mapping
+                            file, e.g. one written by `identify`.
+    --flip                  The display is mounted upside down, bar 0 is at the top.
+    --reverse               Fill values from the top of the display down.
+    --style=<style>         With `set`, how the value is drawn: bar, filling every value up to
+                            it, or dot, lighting only the value like a meter's needle
+                            [default: bar].
+    --steps=<n>             How many bars are fitted, from the bottom of the display, for partial
+                            or custom-built displays [default: 24].
+    --watch                 With `show`, keep showing the display until interrupted, emulating
+                            blinking for terminals that don't support it.
+    --wait                  With `identify`, wait for Enter before lighting the next bar.
+    --interactive           With `test-leds`, ask whether each LED lit correctly.
+    --delay=<duration>      With `identify` or `test-leds`, how long to light each bar or LED for
+                            [default: 1s].
+    --frames=<n>            With `bench`, how many frames to write [default: 500].
+    --pid=<pid>             With `progress`, the process whose I/O to follow.
+    --io=<direction>        With `--pid`, follow the bytes the process has `read` or
+                            `written` [default: written].
+    --path=<path>           With `progress` or `copy-progress`, the file whose size to follow.
+    --total=<size>          With `progress`, the expected number of bytes, with an optional
+                            `K`, `M`, `G` or `T` unit (e.g. `4G`).
+    --expected-size=<size>  With `copy-progress`, the size of the complete file, with an optional
+                            `K`, `M`, `G` or `T` unit (e.g. `4G`).
+    --ci-url=<url>          With `ci`, the API URL of the pipeline's jobs or the build.
+    --imap-url=<url>        With `mail`, the URL of the mailbox to check.
+    --mail-filter=<criteria>
+                            With `mail`, blink while unread messages match the IMAP search
+                            criteria, e.g. `FROM pager@example.com`.
+    --remaining=<duration>  With `countdown`, the time until the meeting starts (e.g. `25m`).
+    --ics-url=<url>         With `countdown`, the URL of the calendar to count down to the next
+                            event of, e.g. a private ICS address.
+    --window=<duration>     With `countdown`, how long before the meeting the display starts
+                            draining from full [default: 1h].
+    --latitude=<degrees>    With `daylight`, the latitude to calculate the sunrise & sunset at,
+                            north is positive (e.g. `47.6`).
+    --longitude=<degrees>   With `daylight`, the longitude, east is positive (e.g. `-122.3`).
+    --sensor=<model>        With `co2`, the sensor on the bus: scd30 or scd41 [default: scd41].
+    --co2-max=<ppm>         With `co2`, the concentration filling the display [default: 2000].
+    --co2-thresholds=<ppm>  With `co2`, the concentrations where the bars turn yellow & red, as
+                            `<yellow>,<red>` [default: 1000,1400].
+    --bme-address=<N>       With `environment`, the address of the sensor, in decimal [default: 118].
+    --measure=<quantity>    With `environment`, display the temperature or humidity
+                            [default: temperature].
+    --alternate=<duration>  With `environment`, alternate between the temperature & humidity,
+                            displaying each for the duration (e.g. `5s`).
+    --temperature-scale=<range>
+                            With `environment`, the temperatures in °C from an empty to a full
+                            display, as `<min>,<max>` [default: 10,35].
+    --humidity-scale=<range>
+                            With `environment`, the relative humidity in % from an empty to a full
+                            display, as `<min>,<max>` [default: 0,100].
+    --ina=<model>           With `meter`, the power monitor on the bus: ina219 or ina260
+                            [default: ina219].
+    --ina-address=<N>       With `meter`, the address of the power monitor, in decimal [default: 64].
+    --shunt=<ohms>          With `meter`, the INA219's shunt resistor [default: 0.1].
+    --meter=<quantity>      With `meter`, display the current or power [default: current].
+    --poll=<duration>       With `progress`, `copy-progress`, `ci` or `mail`, how often to check
+                            the progress, e.g. `1m` for `mail` [default: 1s].
+    --expire=<duration>     Clear the display after a `set` or `set-multi` once the duration has
+                            passed (e.g. `30s`, `5m` or `1h`), from a background process.
+    --fade-in=<duration>    Fade in the first value after initializing the display, rather than
+                            starting at full brightness (e.g. `500ms` or `2s`).
+    --fade-out=<duration>   With `clear` or `--expire`, dim the display before clearing it.
+    --splash=<animation>    With `listen`, `fifo` or `shm`, play an animation once started to
+                            confirm the display works, see `animate` (e.g. `sweep:green:40`).
+    --shutdown=<animation>  With `listen`, `fifo` or `shm`, play an animation when stopped by
+                            SIGTERM or SIGINT, then clear the display (e.g. `sweep:red:120`).
+    --screensaver=<animation>
+                            With `listen`, `fifo` or `shm`, dim the display & repeat an animation
+                            once the values haven't changed for `--idle`, until they next change
+                            (e.g. `sparkle:green:500` or `sweep:green:200`).
+    --idle=<duration>       With `--screensaver`, how long the values are unchanged before it
+                            starts [default: 5m].
+    --daily-range           With `listen`, `fifo` or `shm`, draw the span of the values since the
+                            last `--daily-reset` as a green band, with the latest value as a red
+                            marker, e.g. the day's lowest & highest temperatures.
+    --daily-reset=<time>    With `--daily-range`, the local time each day that the span restarts
+                            from the latest value, as `HH:MM` [default: 00:00].
+    --if-changed            With `set` or `set-multi`, don't initialize or write the display when
+                            the last value set with `--if-changed` is still displayed, e.g. for
+                            cron jobs. Power cycling the display makes the state file stale.
+    --state-file=<path>     Where `--if-changed` keeps the last value displayed, other commands
+                            remove it [default: /tmp/led-bargraph.state].
+    --skip-unchanged        Don't write values that are already displayed, e.g. repeated values
+                            received by `listen`, `fifo` or `shm`.
+    --color=<color>         The color of filled values: off, green, red or yellow [default: yellow].
+    --head-color=<color>    The color of the top bar of filled values [default: red].
+    --colors=<map>          Color the filled values by how full the display is, as comma-separated
+                            `<percent>:<color>` breakpoints (e.g. `0:green,70:yellow,90:red`).
+    --warn=<percent>        Color the filled values green, then yellow from the percentage of the
+                            range, instead of with `--color` & `--head-color`. Without `--crit`
+                            they stay yellow up to the top.
+    --crit=<percent>        Color the filled values green, then red from the percentage of the
+                            range, after yellow from `--warn`. Without `--warn` they go straight
+                            from green to red.
+    -h, --help              Print this help.
+";
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub(crate) enum Transport {
+    Auto,
+    Linux,
+    Ftdi,
+    Mock,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum Co2Model {
+    Scd30,
+    Scd41,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum Quantity {
+    Temperature,
+    Humidity,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum PowerMonitor {
+    Ina219,
+    Ina260,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum MeterQuantity {
+    Current,
+    Power,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum PreviewGlyphs {
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum PreviewColors {
+    Auto,
+    None,
+    Basic,
+    Extended,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub(crate) enum ValueStyle {
+    Bar,
+    Dot,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub(crate) enum IoDirection {
+    Read,
+    Written,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Args {
+    pub(crate) cmd_clear: bool,
+    pub(crate) cmd_set: bool,
+    pub(crate) cmd_set_multi: bool,
+    pub(crate) cmd_show: bool,
+    pub(crate) cmd_listen: bool,
+    pub(crate) cmd_fifo: bool,
+    pub(crate) cmd_shm: bool,
+    pub(crate) cmd_identify: bool,
+    pub(crate) cmd_test_leds: bool,
+    pub(crate) cmd_off: bool,
+    pub(crate) cmd_rpc: bool,
+    pub(crate) cmd_status: bool,
+    pub(crate) cmd_progress: bool,
+    pub(crate) cmd_copy_progress: bool,
+    pub(crate) cmd_packages: bool,
+    pub(crate) cmd_ci: bool,
+    pub(crate) cmd_mail: bool,
+    pub(crate) cmd_countdown: bool,
+    pub(crate) cmd_daylight: bool,
+    pub(crate) cmd_co2: bool,
+    pub(crate) cmd_environment: bool,
+    pub(crate) cmd_meter: bool,
+    pub(crate) cmd_bench: bool,
+    pub(crate) cmd_animate: bool,
+    pub(crate) arg_value: u8,
+    pub(crate) arg_range: u8,
+    pub(crate) arg_max: Option<f64>,
+    pub(crate) arg_segment: Vec<String>,
+    pub(crate) arg_mapping_file: Option<String>,
+    pub(crate) arg_animation: Option<String>,
+    pub(crate) flag_debug: bool,
+    pub(crate) flag_trace: bool,
+    pub(crate) flag_verbose: bool,
+    pub(crate) flag_no_init: bool,
+    pub(crate) flag_show: bool,
+    pub(crate) flag_preview_glyphs: PreviewGlyphs,
+    pub(crate) flag_preview_colors: PreviewColors,
+    pub(crate) flag_transport: Transport,
+    pub(crate) flag_i2c_mock: bool,
+    pub(crate) flag_mock_state: Option<String>,
+    pub(crate) flag_i2c_path: String,
+    pub(crate) flag_i2c_address: u8,
+    pub(crate) flag_power_gpio: Option<u64>,
+    pub(crate) flag_trace_i2c: bool,
+    pub(crate) flag_log_target: LogTarget,
+    pub(crate) flag_log_file: Option<String>,
+    pub(crate) flag_log_rotate: String,
+    pub(crate) flag_log_keep: u32,
+    pub(crate) flag_simulate_errors: Option<String>,
+    pub(crate) flag_udp_bind: String,
+    pub(crate) flag_fifo: String,
+    pub(crate) flag_shm: String,
+    pub(crate) flag_fps: u32,
+    pub(crate) flag_normalized: bool,
+    pub(crate) flag_smoothing: Option<f32>,
+    pub(crate) flag_alert_above: Option<u8>,
+    pub(crate) flag_mapping: Option<String>,
+    pub(crate) flag_flip: bool,
+    pub(crate) flag_reverse: bool,
+    pub(crate) flag_steps: u8,
+    pub(crate) flag_watch: bool,
+    pub(crate) flag_wait: bool,
+    pub(crate) flag_interactive: bool,
+    pub(crate) flag_delay: String,
+    pub(crate) flag_frames: u32,
+    pub(crate) flag_pid: Option<u32>,
+    pub(crate) flag_io: IoDirection,
+    pub(crate) flag_path: Option<String>,
+    pub(crate) flag_total: Option<String>,
+    pub(crate) flag_expected_size: Option<String>,
+    pub(crate) flag_ci_url: Option<String>,
+    pub(crate) flag_imap_url: Option<String>,
+    pub(crate) flag_mail_filter: Option<String>,
+    pub(crate) flag_remaining: Option<String>,
+    pub(crate) flag_ics_url: Option<String>,
+    pub(crate) flag_window: String,
+    pub(crate) flag_latitude: Option<f64>,
+    pub(crate) flag_longitude: Option<f64>,
+    pub(crate) flag_sensor: Co2Model,
+    pub(crate) flag_co2_max: u16,
+    pub(crate) flag_co2_thresholds: String,
+    pub(crate) flag_bme_address: u8,
+    pub(crate) flag_measure: Quantity,
+    pub(crate) flag_alternate: Option<String>,
+    pub(crate) flag_temperature_scale: String,
+    pub(crate) flag_humidity_scale: String,
+    pub(crate) flag_ina: PowerMonitor,
+    pub(crate) flag_ina_address: u8,
+    pub(crate) flag_shunt: f64,
+    pub(crate) flag_meter: MeterQuantity,
+    pub(crate) flag_poll: String,
+    pub(crate) flag_expire: Option<String>,
+    pub(crate) flag_fade_in: Option<String>,
+    pub(crate) flag_fade_out: Option<String>,
+    pub(crate) flag_splash: Option<String>,
+    pub(crate) flag_shutdown: Option<String>,
+    pub(crate) flag_screensaver: Option<String>,
+    pub(crate) flag_idle: String,
+    pub(crate) flag_daily_range: bool,
+    pub(crate) flag_daily_reset: String,
+    pub(crate) flag_if_changed: bool,
+    pub(crate) flag_state_file: String,
+    pub(crate) flag_skip_unchanged: bool,
+    pub(crate) flag_color: String,
+    pub(crate) flag_head_color: String,
+    pub(crate) flag_colors: Option<String>,
+    pub(crate) flag_warn: Option<u8>,
+    pub(crate) flag_style: ValueStyle,
+    pub(crate) flag_crit: Option<u8>,
+}
+
+// The transport to connect with, resolving `auto` for the platform.
+pub(crate) fn transport(args: &Args) -> Transport {
+    match args.flag_transport {
+        _ if args.flag_i2c_mock => Transport::Mock,
+        Transport::Auto if cfg!(target_os = "linux") => Transport::Linux,
+        Transport::Auto => Transport::Mock,
+        transport => transport,
+    }
+}
+
+// Parse an animation option, exiting with the usage on error.
+pub(crate) fn parse_animation(animation: &str) -> Animation {
+    animation
+        .parse()
+        .unwrap_or_else(|e: ParseAnimationError| docopt::Error::Argv(e.to_string()).exit())
+}
+
+// Parse a color option, exiting with the usage on error.
+pub(crate) fn parse_color(color: &str) -> LedColor {
+    color
+        .parse()
+        .unwrap_or_else(|e: led_bargraph::ParseLedColorError| {
+            docopt::Error::Argv(e.to_string()).exit()
+        })
+}
+
+// The color zones of `--warn` & `--crit`, if either is given. Without `--warn` there's
+// no yellow zone, & without `--crit` no red zone.
+pub(crate) fn parse_thresholds(
+    warn: Option<u8>,
+    crit: Option<u8>,
+) -> result::Result<Option<Thresholds>, String> {
+    for &percent in warn.iter().chain(crit.iter()) {
+        if percent > 100 {
+            return Err(format!(
+                "Invalid threshold [{}], expected [0] to [100]",
+                percent
+            ));
+        }
+    }
+
+    match (warn, crit) {
+        (None, None) => Ok(None),
+        (Some(warn), Some(crit)) if warn > crit => Err(format!(
+            "Invalid thresholds, `--warn` [{}] is above `--crit` [{}]",
+            warn, crit
+        )),
+        (warn, crit) => Ok(Some(Thresholds { warn, crit })),
+    }
+}
+
+// Parse a `<value>/<range>` segment, e.g. `3/6`.
+fn parse_segment(segment: &str) -> result::Result<(u8, u8), String> {
+    let invalid = || format!("Invalid segment [{}], expected `<value>/<range>`", segment);
+
+    let (value, range) = segment.split_once('/').ok_or_else(invalid)?;
+    let value = value.trim().parse().map_err(|_| invalid())?;
+    let range = range.trim().parse().map_err(|_| invalid())?;
+
+    if range == 0 || range > led_bargraph::BARGRAPH_RESOLUTION {
+        return Err(format!(
+            "Invalid segment [{}], the range must be between [1] and [{}]",
+            segment,
+            led_bargraph::BARGRAPH_RESOLUTION
+        ));
+    }
+
+    Ok((value, range))
+}
+
+// Parse the `<value>/<range>` segments, checking that each range fits within its
+// segment's share of the resolution.
+pub(crate) fn parse_segments(
+    segments: &[String],
+    resolution: u8,
+) -> result::Result<Vec<(u8, u8)>, String> {
+    if segments.len() > usize::from(resolution) {
+        return Err(format!(
+            "Invalid segments, expected at most [{}] for [{}] bars, got [{}]",
+            resolution,
+            resolution,
+            segments.len()
+        ));
+    }
+
+    let segment_size = resolution / segments.len().max(1) as u8;
+    segments
+        .iter()
+        .map(|segment| {
+            let (value, range) = parse_segment(segment)?;
+            if range > segment_size {
+                return Err(format!(
+                    "Invalid segment [{}], the range must be at most the [{}] bars of each segment",
+                    segment, segment_size
+                ));
+            }
+
+            Ok((value, range))
+        })
+        .collect()
+}
+
+// The fraction of transactions to fail from `--simulate-errors`, e.g. `rate=0.05`.
+pub(crate) fn parse_simulate_errors(spec: &str) -> result::Result<f32, String> {
+    let invalid = || {
+        format!(
+            "Invalid simulated errors [{}], expected `rate=<fraction>` from [0.0] to [1.0]",
+            spec
+        )
+    };
+
+    let rate: f32 = spec
+        .trim()
+        .strip_prefix("rate=")
+        .ok_or_else(invalid)?
+        .parse()
+        .map_err(|_| invalid())?;
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(invalid());
+    }
+
+    Ok(rate)
+}
+
+// Parse a size in bytes, with an optional binary `K`, `M`, `G` or `T` unit, e.g. `4G`.
+pub(crate) fn parse_size(size: &str) -> result::Result<u64, String> {
+    let invalid = || format!("Invalid size [{}], expected e.g. `4G`", size);
+
+    let trimmed = size.trim();
+    let (number, shift) = match trimmed.char_indices().last() {
+        Some((i, 'K')) | Some((i, 'k')) => (&trimmed[..i], 10),
+        Some((i, 'M')) | Some((i, 'm')) => (&trimmed[..i], 20),
+        Some((i, 'G')) | Some((i, 'g')) => (&trimmed[..i], 30),
+        Some((i, 'T')) | Some((i, 't')) => (&trimmed[..i], 40),
+        _ => (trimmed, 0),
+    };
+
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+
+    number
+        .checked_mul(1 << shift)
+        .filter(|&bytes| bytes > 0)
+        .ok_or_else(invalid)
+}
+
+// Parse a duration in seconds, with an optional `ms`, `s`, `m` or `h` unit, e.g. `30s`.
+pub(crate) fn parse_duration(duration: &str) -> result::Result<Duration, String> {
+    let invalid = || format!("Invalid duration [{}], expected e.g. `30s`", duration);
+
+    let trimmed = duration.trim();
+    if let Some(millis) = trimmed.strip_suffix("ms") {
+        let millis: u64 = millis.parse().map_err(|_| invalid())?;
+        return Ok(Duration::from_millis(millis));
+    }
+
+    let (number, multiplier) = match trimmed.char_indices().last() {
+        Some((i, 's')) => (&trimmed[..i], 1),
+        Some((i, 'm')) => (&trimmed[..i], 60),
+        Some((i, 'h')) => (&trimmed[..i], 60 * 60),
+        _ => (trimmed, 1),
+    };
+
+    let seconds: u64 = number.parse().map_err(|_| invalid())?;
+
+    Ok(Duration::from_secs(seconds * multiplier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_thresholds_zones() {
+        assert_eq!(Ok(None), parse_thresholds(None, None));
+        assert_eq!(
+            Ok(Some(Thresholds {
+                warn: Some(70),
+                crit: Some(90)
+            })),
+            parse_thresholds(Some(70), Some(90))
+        );
+        assert_eq!(
+            Ok(Some(Thresholds {
+                warn: None,
+                crit: Some(90)
+            })),
+            parse_thresholds(None, Some(90))
+        );
+        assert_eq!(
+            Ok(Some(Thresholds {
+                warn: Some(70),
+                crit: None
+            })),
+            parse_thresholds(Some(70), None)
+        );
+
+        assert!(parse_thresholds(Some(90), Some(70)).is_err());
+        assert!(parse_thresholds(Some(50), Some(101)).is_err());
+    }
+
+    #[test]
+    fn parse_segment_valid() {
+        assert_eq!(Ok((3, 10)), parse_segment("3/10"));
+        assert_eq!(Ok((11, 10)), parse_segment("11/10"));
+    }
+
+    #[test]
+    fn parse_segment_invalid() {
+        assert!(parse_segment("3").is_err());
+        assert!(parse_segment("3/").is_err());
+        assert!(parse_segment("a/10").is_err());
+        assert!(parse_segment("3/0").is_err());
+        assert!(parse_segment("3/25").is_err());
+    }
+
+    #[test]
+    fn parse_segments_resolution() {
+        let segments = |segments: &[&str]| -> Vec<String> {
+            segments.iter().map(|segment| segment.to_string()).collect()
+        };
+
+        assert_eq!(
+            Ok(vec![(3, 12), (12, 12)]),
+            parse_segments(&segments(&["3/12", "12/12"]), 24)
+        );
+
+        // A range larger than the 12 bars of each of two segments.
+        assert!(parse_segments(&segments(&["3/12", "3/13"]), 24).is_err());
+        // 25 segments of a single bar.
+        assert!(parse_segments(&segments(&["0/1"; 25]), 24).is_err());
+        // A reduced resolution.
+        assert!(parse_segments(&segments(&["3/6", "3/6"]), 12).is_ok());
+        assert!(parse_segments(&segments(&["3/6", "3/6"]), 10).is_err());
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(Ok(512), parse_size("512"));
+        assert_eq!(Ok(2048), parse_size(" 2K "));
+        assert_eq!(Ok(3 << 20), parse_size("3m"));
+        assert_eq!(Ok(4 << 30), parse_size("4G"));
+        assert_eq!(Ok(1 << 40), parse_size("1T"));
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("G").is_err());
+        assert!(parse_size("4GB").is_err());
+        assert!(parse_size("99999999T").is_err());
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(Ok(Duration::from_secs(30)), parse_duration("30"));
+        assert_eq!(Ok(Duration::from_secs(30)), parse_duration(" 30s "));
+        assert_eq!(Ok(Duration::from_secs(300)), parse_duration("5m"));
+        assert_eq!(Ok(Duration::from_secs(7200)), parse_duration("2h"));
+        assert_eq!(Ok(Duration::from_millis(500)), parse_duration("500ms"));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("ms").is_err());
+        assert!(parse_duration("5d").is_err());
+    }
+
+    #[test]
+    fn parse_simulate_errors_rate() {
+        assert_eq!(Ok(0.05), parse_simulate_errors("rate=0.05"));
+        assert_eq!(Ok(1.0), parse_simulate_errors("rate=1"));
+        assert!(parse_simulate_errors("0.05").is_err());
+        assert!(parse_simulate_errors("rate=1.5").is_err());
+        assert!(parse_simulate_errors("rate=often").is_err());
+    }
+}
@@ -0,0 +1,1053 @@
+//! Executing the commands, & those that drive the display directly, e.g. `bench`
+//! & `identify`.
+use hal::blocking::i2c::{Read, Write, WriteRead};
+use led_bargraph::{
+    Animation, Bargraph, ColorMap, ColorScheme, Error, Frame, LedColor, Render, Style,
+    BARGRAPH_RESOLUTION, BRIGHTNESS_MAX,
+};
+use shared_bus::BusManagerStd;
+
+use args::{
+    parse_animation, parse_color, parse_duration, parse_segments, parse_thresholds, transport,
+    Args, Transport, ValueStyle,
+};
+use daemon::{daemon_status, handle_shutdown_signals, listen, read_fifo, read_shm, SHUTDOWN};
+use outputs::{command_outputs, terminal_support, update_value};
+use rpc::{device_status, rpc};
+use sources::{
+    ci, co2, copy_progress, countdown, daylight, environment, mail, meter, packages, progress,
+};
+
+use std::fmt::Debug;
+use std::fs;
+use std::io::{self, BufRead, Write as IoWrite};
+use std::path::Path;
+use std::result;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The display shares the I2C bus with any sensor being displayed.
+pub(crate) fn execute<I2C, E>(i2c_device: I2C, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E> + Read<Error = E>,
+    E: Debug,
+{
+    let bus = BusManagerStd::new(i2c_device);
+
+    let bargraph_logger = logger.new(o!("mod" => "bargraph"));
+    let mut bargraph = Bargraph::new(bus.acquire_i2c(), args.flag_i2c_address, bargraph_logger);
+    if let Some(ref path) = args.flag_mapping {
+        info!(logger, "Loading the mapping"; "path" => path);
+
+        let table = load_mapping(Path::new(path)).expect("Failed to load the mapping file");
+        bargraph
+            .set_mapping(&table)
+            .expect("Failed to apply the mapping file");
+    }
+    if args.flag_steps == 0 || args.flag_steps > BARGRAPH_RESOLUTION {
+        docopt::Error::Argv(format!(
+            "Invalid steps [{}], expected [1] to [{}]",
+            args.flag_steps, BARGRAPH_RESOLUTION
+        ))
+        .exit();
+    }
+    bargraph.set_resolution(args.flag_steps);
+    bargraph.set_flipped(args.flag_flip);
+    bargraph.set_color_scheme(ColorScheme {
+        fill: parse_color(&args.flag_color),
+        head: parse_color(&args.flag_head_color),
+        ..ColorScheme::default()
+    });
+    bargraph.set_thresholds(
+        parse_thresholds(args.flag_warn, args.flag_crit)
+            .unwrap_or_else(|e| docopt::Error::Argv(e).exit()),
+    );
+    bargraph.set_reversed(args.flag_reverse);
+    bargraph.set_style(match args.flag_style {
+        ValueStyle::Bar => Style::Bar,
+        ValueStyle::Dot => Style::Dot,
+    });
+    bargraph.set_terminal(terminal_support(args));
+    bargraph.set_skip_unchanged(args.flag_skip_unchanged);
+
+    let state_file = Path::new(&args.flag_state_file);
+    let state = if args.flag_if_changed && (args.cmd_set || args.cmd_set_multi) {
+        read_state(state_file)
+    } else {
+        None
+    };
+
+    if let Some(frame) = state {
+        debug!(logger, "Loaded the displayed frame"; "frame" => %frame, "blink" => frame.blink);
+
+        bargraph.set_skip_unchanged(true);
+        bargraph.assume_frame(frame);
+    }
+
+    if let Some(gpio) = args.flag_power_gpio {
+        set_power_pin(&mut bargraph, gpio);
+    } else if args.cmd_off {
+        docopt::Error::Argv("The off command requires `--power-gpio`".to_string()).exit();
+    }
+
+    if let Some(ref fade_in) = args.flag_fade_in {
+        let duration = parse_duration(fade_in).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+        bargraph.set_fade_in(duration, BRIGHTNESS_MAX);
+    }
+
+    if args.cmd_off {
+        info!(logger, "Switching off the display's power");
+        bargraph
+            .power_off()
+            .expect("Failed to switch off the display's power");
+        return;
+    }
+
+    // Before anything changes the display or the state file.
+    if args.cmd_status {
+        status(&mut bargraph, args, logger);
+        return;
+    }
+
+    if args.flag_no_init {
+        info!(logger, "Not initializing the display");
+    } else if state.is_some() {
+        info!(
+            logger,
+            "Not initializing the display, it's showing the last value"
+        );
+    } else if args.flag_power_gpio.is_some() {
+        info!(logger, "Power cycling the display");
+        bargraph
+            .power_cycle()
+            .expect("Failed to power cycle the display");
+    } else {
+        info!(logger, "Initializing the display");
+        bargraph
+            .initialize()
+            .expect("Failed to initialize the display");
+    }
+
+    let fade_out = args
+        .flag_fade_out
+        .as_ref()
+        .map(|fade_out| parse_duration(fade_out).unwrap_or_else(|e| docopt::Error::Argv(e).exit()));
+
+    if args.cmd_clear {
+        info!(logger, "Clearing the display");
+        clear(&mut bargraph, fade_out).expect("Failed to clear the display");
+    }
+
+    let expire = args
+        .flag_expire
+        .as_ref()
+        .map(|expire| parse_duration(expire).unwrap_or_else(|e| docopt::Error::Argv(e).exit()));
+
+    let colors = args.flag_colors.as_ref().map(|colors| {
+        colors
+            .parse::<ColorMap>()
+            .unwrap_or_else(|e| docopt::Error::Argv(e.to_string()).exit())
+    });
+
+    if args.cmd_set {
+        info!(logger, "Setting a value within a range on the display";
+              "value" => args.arg_value, "range" => args.arg_range);
+
+        update_value(
+            &mut bargraph,
+            args.arg_value,
+            args.arg_range,
+            colors.as_ref(),
+            args.flag_show,
+        )
+        .expect("Failed to set a value within a range on the display");
+    }
+
+    if args.cmd_set_multi {
+        let segments = parse_segments(&args.arg_segment, bargraph.resolution())
+            .unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+
+        info!(logger, "Setting several values within ranges on the display";
+              "segments" => ?segments);
+
+        bargraph
+            .update_segments(&segments, args.flag_show)
+            .expect("Failed to set several values within ranges on the display");
+    }
+
+    // Only the frame of a value that stays displayed is kept, anything else may have
+    // changed the display.
+    match bargraph.last_frame() {
+        Some(frame)
+            if args.flag_if_changed && (args.cmd_set || args.cmd_set_multi) && expire.is_none() =>
+        {
+            if !bargraph.changed_since_last_flush() {
+                info!(logger, "The value is already displayed");
+            }
+
+            write_state(state_file, &frame).expect("Failed to write the state file");
+        }
+        _ => remove_state(state_file).expect("Failed to remove the state file"),
+    }
+
+    if let Some(expire) = expire {
+        if args.cmd_set || args.cmd_set_multi {
+            clear_after(&mut bargraph, expire, fade_out, logger);
+        }
+    }
+
+    if args.cmd_show {
+        info!(logger, "Showing the current display on-screen";
+              "blink_rate" => ?bargraph.blink_rate(), "brightness" => bargraph.brightness());
+
+        if args.flag_watch {
+            watch(&mut bargraph);
+        } else {
+            bargraph
+                .show()
+                .expect("Failed to show the current display on-screen");
+        }
+    }
+
+    let splash = args
+        .flag_splash
+        .as_ref()
+        .map(|splash| parse_animation(splash));
+    let shutdown = args
+        .flag_shutdown
+        .as_ref()
+        .map(|shutdown| parse_animation(shutdown));
+
+    if shutdown.is_some() {
+        handle_shutdown_signals();
+    }
+
+    let daemon = args.cmd_listen || args.cmd_fifo || args.cmd_shm;
+
+    if let (true, Some(splash)) = (daemon, splash) {
+        info!(logger, "Playing the splash animation"; "animation" => ?splash);
+
+        bargraph
+            .play(&splash)
+            .and_then(|_| bargraph.clear())
+            .expect("Failed to play the splash animation");
+    }
+
+    if args.cmd_listen {
+        listen(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_fifo {
+        read_fifo(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_shm {
+        read_shm(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if let (true, Some(shutdown)) = (daemon, shutdown) {
+        info!(logger, "Playing the shutdown animation"; "animation" => ?shutdown);
+
+        bargraph
+            .play(&shutdown)
+            .and_then(|_| bargraph.clear())
+            .expect("Failed to play the shutdown animation");
+    }
+
+    if args.cmd_identify {
+        identify(&mut bargraph, args, logger);
+    }
+
+    if args.cmd_test_leds {
+        test_leds(&mut bargraph, args, logger);
+    }
+
+    if args.cmd_rpc {
+        rpc(&mut bargraph, logger);
+    }
+
+    if args.cmd_progress {
+        progress(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_copy_progress {
+        copy_progress(&mut bargraph, args, logger);
+    }
+
+    if args.cmd_packages {
+        packages(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_ci {
+        ci(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_mail {
+        mail(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_countdown {
+        countdown(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_daylight {
+        daylight(&mut bargraph, args, colors.as_ref(), logger);
+    }
+
+    if args.cmd_co2 {
+        co2(&mut bargraph, bus.acquire_i2c(), args, logger);
+    }
+
+    if args.cmd_environment {
+        environment(
+            &mut bargraph,
+            bus.acquire_i2c(),
+            args,
+            colors.as_ref(),
+            logger,
+        );
+    }
+
+    if args.cmd_meter {
+        meter(
+            &mut bargraph,
+            bus.acquire_i2c(),
+            args,
+            colors.as_ref(),
+            logger,
+        );
+    }
+
+    if args.cmd_bench {
+        bench(&mut bargraph, args, logger);
+    }
+
+    if let (true, Some(animation)) = (args.cmd_animate, args.arg_animation.as_ref()) {
+        animate(&mut bargraph, &parse_animation(animation), args, logger);
+    }
+
+    debug!(logger, "Success"; "metrics" => ?bargraph.metrics());
+}
+
+// Switch the backpack's power with the sysfs GPIO, powering it on.
+#[cfg(target_os = "linux")]
+fn set_power_pin<I2C, E>(bargraph: &mut Bargraph<I2C>, gpio: u64)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    use linux_embedded_hal::sysfs_gpio::Direction;
+    use linux_embedded_hal::Pin;
+
+    let pin = Pin::new(gpio);
+    pin.export().expect("Failed to export the power GPIO");
+    pin.set_direction(Direction::High)
+        .expect("Failed to configure the power GPIO");
+
+    bargraph.set_power_pin(pin);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_power_pin<I2C, E>(_bargraph: &mut Bargraph<I2C>, _gpio: u64)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    panic!("The power GPIO is only available on Linux");
+}
+
+// Keep showing the display, redrawing it in place, forever.
+fn watch<I2C, E>(bargraph: &mut Bargraph<I2C>) -> !
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    // The preview is a box around a single row of bars.
+    const PREVIEW_LINES: usize = 3;
+    // Fast enough to show the quickest (2 Hz) blinking.
+    const REFRESH_INTERVAL: Duration = Duration::from_millis(50);
+
+    bargraph.set_emulate_blink(true);
+
+    loop {
+        bargraph
+            .show()
+            .expect("Failed to show the current display on-screen");
+
+        std::thread::sleep(REFRESH_INTERVAL);
+
+        // Move the cursor back to the start of the preview.
+        print!("\x1B[{}A", PREVIEW_LINES);
+    }
+}
+
+// Repeat the animation, also on the terminal, until interrupted or a shutdown is
+// requested.
+fn animate<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    animation: &Animation,
+    args: &Args,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    info!(logger, "Playing the animation"; "animation" => ?animation);
+
+    let frames = animation.frames(bargraph.resolution());
+    let mut outputs = command_outputs(bargraph, args);
+
+    for frame in frames.iter().cycle() {
+        if SHUTDOWN.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if let Err(e) = outputs.render(frame) {
+            error!(logger, "Failed to play a frame of the animation"; "error" => %e);
+        }
+
+        thread::sleep(animation.interval);
+    }
+}
+
+// Write changing frames as fast as the display takes them, timing each update, then
+// print a summary.
+fn bench<I2C, E>(bargraph: &mut Bargraph<I2C>, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    if args.flag_frames == 0 {
+        docopt::Error::Argv("Invalid frames [0], expected at least [1]".to_string()).exit();
+    }
+
+    info!(logger, "Benchmarking the display"; "frames" => args.flag_frames);
+
+    let range = bargraph.resolution();
+    let before = bargraph.metrics();
+    let mut latencies = Vec::with_capacity(args.flag_frames as usize);
+
+    let start = Instant::now();
+    for frame in 0..args.flag_frames {
+        // Cycle through every value, so that each frame changes the display.
+        let value = (frame % (u32::from(range) + 1)) as u8;
+
+        let update = Instant::now();
+        bargraph
+            .update(value, range, false)
+            .expect("Failed to update the display");
+        latencies.push(update.elapsed());
+    }
+    let elapsed = start.elapsed();
+
+    let metrics = bargraph.metrics();
+    let summary = BenchSummary::new(&mut latencies, elapsed);
+    let frames = f64::from(args.flag_frames);
+
+    println!("Frames:     {} in {:?}", summary.frames, elapsed);
+    println!(
+        "Latency:    min {:?}, median {:?}, 99th percentile {:?}, max {:?}",
+        summary.min, summary.median, summary.p99, summary.max
+    );
+    println!("Frame rate: {:.1} fps", summary.frame_rate);
+    println!(
+        "I2C:        {:.1} transactions & {:.1} bytes per frame, {} errors",
+        (metrics.i2c_transactions - before.i2c_transactions) as f64 / frames,
+        (metrics.i2c_bytes - before.i2c_bytes) as f64 / frames,
+        metrics.i2c_errors - before.i2c_errors
+    );
+
+    bargraph.clear().expect("Failed to clear the display");
+}
+
+// The latencies of the frames written by `bench`.
+#[derive(Debug, PartialEq)]
+struct BenchSummary {
+    frames: usize,
+    min: Duration,
+    median: Duration,
+    p99: Duration,
+    max: Duration,
+    // The frames written per second, back to back.
+    frame_rate: f64,
+}
+
+impl BenchSummary {
+    // Summarize at least one latency, sorting them, with how long writing all the
+    // frames took.
+    fn new(latencies: &mut [Duration], elapsed: Duration) -> Self {
+        latencies.sort();
+
+        // The nearest-rank percentile.
+        let percentile = |percent: usize| {
+            let rank = (latencies.len() * percent).div_ceil(100);
+            latencies[rank.max(1) - 1]
+        };
+
+        BenchSummary {
+            frames: latencies.len(),
+            min: latencies[0],
+            median: percentile(50),
+            p99: percentile(99),
+            max: latencies[latencies.len() - 1],
+            frame_rate: latencies.len() as f64 / elapsed.as_secs_f64(),
+        }
+    }
+}
+
+// Light each bar in turn, printing where it should be. When waiting for the user,
+// ask where each bar actually is & generate a mapping that corrects the differences.
+fn identify<I2C, E>(bargraph: &mut Bargraph<I2C>, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let delay = parse_duration(&args.flag_delay).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+
+    info!(logger, "Identifying the bars"; "wait" => args.flag_wait);
+
+    // The mapping is being checked, so ignore the orientation.
+    bargraph.set_flipped(false);
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock().lines();
+
+    let mut positions: Vec<u8> = (0..BARGRAPH_RESOLUTION).collect();
+
+    for bar in 0..BARGRAPH_RESOLUTION {
+        let colors = (0..BARGRAPH_RESOLUTION).map(|other| {
+            if other == bar {
+                LedColor::Yellow
+            } else {
+                LedColor::Off
+            }
+        });
+        bargraph
+            .set_from_iter(colors)
+            .expect("Failed to light the bar");
+
+        let (row, common) = bargraph.mapping()[bar as usize];
+
+        if !args.flag_wait {
+            println!("Bar {:>2}: row {}, common {}", bar, row, common);
+            std::thread::sleep(delay);
+            continue;
+        }
+
+        loop {
+            print!(
+                "Bar {:>2}: row {}, common {}. Press Enter if it's bar {} from the bottom, \
+                 otherwise enter the lit bar's position (0 - {}): ",
+                bar,
+                row,
+                common,
+                bar,
+                BARGRAPH_RESOLUTION - 1
+            );
+            io::stdout().flush().expect("Failed to write the prompt");
+
+            let line = match input.next() {
+                Some(line) => line.expect("Failed to read the position"),
+                // End of input, the remaining bars are assumed to be correct.
+                None => break,
+            };
+
+            if line.trim().is_empty() {
+                break;
+            }
+
+            match line.trim().parse() {
+                Ok(position) if position < BARGRAPH_RESOLUTION => {
+                    positions[bar as usize] = position;
+                    break;
+                }
+                _ => println!("Invalid position [{}]", line.trim()),
+            }
+        }
+    }
+
+    bargraph.clear().expect("Failed to clear the display");
+
+    let differences: Vec<(usize, &u8)> = positions
+        .iter()
+        .enumerate()
+        .filter(|&(bar, &position)| bar != position as usize)
+        .collect();
+
+    if differences.is_empty() {
+        println!("Every bar matches the mapping");
+        return;
+    }
+
+    for (bar, position) in differences {
+        println!("Bar {} is at position {}", bar, position);
+    }
+
+    let locations = identified_mapping(bargraph.mapping(), &positions)
+        .unwrap_or_else(|e| panic!("Failed to generate a mapping: {}", e));
+    let contents = format_mapping(&locations);
+
+    match args.arg_mapping_file {
+        Some(ref path) => {
+            fs::write(path, contents).expect("Failed to write the mapping file");
+            println!("Wrote the corrected mapping to {}", path);
+        }
+        None => print!("{}", contents),
+    }
+}
+
+// Generate the `(row, common)` of each bar, given the position where each bar of
+// the mapping was seen. Every position must be seen exactly once.
+fn identified_mapping(
+    table: &[(u8, u8)],
+    positions: &[u8],
+) -> result::Result<Vec<(u8, u8)>, String> {
+    let mut locations = vec![None; BARGRAPH_RESOLUTION as usize];
+
+    for (bar, &position) in positions.iter().enumerate() {
+        let location = locations
+            .get_mut(position as usize)
+            .ok_or_else(|| format!("Position [{}] is out of range", position))?;
+
+        if location.is_some() {
+            return Err(format!("Position [{}] was seen more than once", position));
+        }
+
+        *location = Some(table[bar]);
+    }
+
+    locations
+        .into_iter()
+        .enumerate()
+        .map(|(position, location)| {
+            location.ok_or_else(|| format!("Position [{}] was never seen", position))
+        })
+        .collect()
+}
+
+// The contents of a mapping file.
+#[derive(Debug, Deserialize)]
+struct MappingFile {
+    bars: Vec<(u8, u8)>,
+}
+
+// Read the frame kept by `--if-changed`, as the compact form of the bars followed by
+// `blink` if the display is blinking. A missing or invalid state file is unknown.
+fn read_state(path: &Path) -> Option<Frame> {
+    parse_state(&fs::read_to_string(path).ok()?)
+}
+
+fn parse_state(contents: &str) -> Option<Frame> {
+    let mut parts = contents.split_whitespace();
+
+    let mut frame: Frame = parts.next()?.parse().ok()?;
+    frame.blink = match parts.next() {
+        Some("blink") => true,
+        Some(_) => return None,
+        None => false,
+    };
+
+    match parts.next() {
+        Some(_) => None,
+        None => Some(frame),
+    }
+}
+
+fn write_state(path: &Path, frame: &Frame) -> io::Result<()> {
+    fs::write(path, format_state(frame))
+}
+
+fn format_state(frame: &Frame) -> String {
+    if frame.blink {
+        format!("{} blink\n", frame)
+    } else {
+        format!("{}\n", frame)
+    }
+}
+
+fn remove_state(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// Load a mapping file, see `format_mapping()`.
+fn load_mapping(path: &Path) -> io::Result<[(u8, u8); BARGRAPH_RESOLUTION as usize]> {
+    parse_mapping(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn parse_mapping(
+    contents: &str,
+) -> result::Result<[(u8, u8); BARGRAPH_RESOLUTION as usize], String> {
+    let file: MappingFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+
+    let mut table = [(0, 0); BARGRAPH_RESOLUTION as usize];
+    if file.bars.len() != table.len() {
+        return Err(format!(
+            "Expected [{}] bars, found [{}]",
+            table.len(),
+            file.bars.len()
+        ));
+    }
+    table.copy_from_slice(&file.bars);
+
+    Ok(table)
+}
+
+// Format a mapping as TOML.
+fn format_mapping(locations: &[(u8, u8)]) -> String {
+    let mut contents = String::from(
+        "# The bar mapping, generated by `led-bargraph identify`.\n\
+         # Each bar, from the bottom, is the `[row, common]` of its red LED, the green LED\n\
+         # is on the next row.\n\
+         bars = [\n",
+    );
+
+    for (row, common) in locations {
+        contents.push_str(&format!("    [{}, {}],\n", row, common));
+    }
+    contents.push_str("]\n");
+
+    contents
+}
+
+// Whether an LED lit correctly, as answered by the user of `test-leds`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LedCheck {
+    Lit,
+    // Nothing lit.
+    Dead,
+    // A different LED lit, or the LED & others.
+    Miswired,
+}
+
+// Light every LED in turn, the red then the green LED of each bar. When interactive, ask
+// whether each lit correctly & report the LEDs that didn't.
+fn test_leds<I2C, E>(bargraph: &mut Bargraph<I2C>, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let delay = parse_duration(&args.flag_delay).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+
+    info!(logger, "Testing the LEDs"; "interactive" => args.flag_interactive);
+
+    // The wiring is being checked, so ignore the orientation.
+    bargraph.set_flipped(false);
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock().lines();
+
+    let mut failures = Vec::new();
+
+    for bar in 0..BARGRAPH_RESOLUTION {
+        for &color in [LedColor::Red, LedColor::Green].iter() {
+            let colors =
+                (0..BARGRAPH_RESOLUTION).map(
+                    |other| {
+                        if other == bar {
+                            color
+                        } else {
+                            LedColor::Off
+                        }
+                    },
+                );
+            bargraph
+                .set_from_iter(colors)
+                .expect("Failed to light the LED");
+
+            let led = describe_led(bargraph.mapping(), bar, color);
+
+            if !args.flag_interactive {
+                println!("{}", led);
+                std::thread::sleep(delay);
+                continue;
+            }
+
+            let check = loop {
+                print!(
+                    "{}. Is only bar {} from the bottom lit {}? \
+                     [Y]es, [n]othing is lit or [w]rong LED: ",
+                    led, bar, color
+                );
+                io::stdout().flush().expect("Failed to write the prompt");
+
+                let line = match input.next() {
+                    Some(line) => line.expect("Failed to read the answer"),
+                    // End of input, the remaining LEDs are assumed to be correct.
+                    None => break LedCheck::Lit,
+                };
+
+                match parse_led_check(&line) {
+                    Some(check) => break check,
+                    None => println!("Invalid answer [{}]", line.trim()),
+                }
+            };
+
+            if check != LedCheck::Lit {
+                failures.push((led, check));
+            }
+        }
+    }
+
+    bargraph.clear().expect("Failed to clear the display");
+
+    if args.flag_interactive {
+        print!("{}", format_led_report(&failures));
+    }
+}
+
+// The bar, color & location of an LED, e.g. `Bar  3 red: row 0, common 3`. The green
+// LED is on the row after the red LED.
+fn describe_led(mapping: &[(u8, u8)], bar: u8, color: LedColor) -> String {
+    let (row, common) = mapping[bar as usize];
+    let row = if color == LedColor::Green {
+        row + 1
+    } else {
+        row
+    };
+
+    format!("Bar {:>2} {}: row {}, common {}", bar, color, row, common)
+}
+
+// The answer to whether an LED lit correctly, an empty answer is yes.
+fn parse_led_check(answer: &str) -> Option<LedCheck> {
+    match answer.trim().to_lowercase().as_str() {
+        "" | "y" | "yes" => Some(LedCheck::Lit),
+        "n" | "no" | "nothing" => Some(LedCheck::Dead),
+        "w" | "wrong" => Some(LedCheck::Miswired),
+        _ => None,
+    }
+}
+
+// The report of the LEDs that didn't light correctly.
+fn format_led_report(failures: &[(String, LedCheck)]) -> String {
+    let total = usize::from(BARGRAPH_RESOLUTION) * 2;
+
+    if failures.is_empty() {
+        return format!("All {} LEDs lit correctly\n", total);
+    }
+
+    let mut report = format!("{} of {} LEDs failed:\n", failures.len(), total);
+    for (led, check) in failures {
+        let failure = match check {
+            LedCheck::Dead => "dead",
+            _ => "miswired",
+        };
+        report.push_str(&format!("    {} is {}\n", led, failure));
+    }
+
+    report
+}
+
+// Clear the display, fading it out first if requested.
+fn clear<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    fade_out: Option<Duration>,
+) -> result::Result<(), Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    match fade_out {
+        Some(duration) => bargraph.clear_with_fade(duration),
+        None => bargraph.clear(),
+    }
+}
+
+// Clear the display once `expire` has passed, from a forked process so that the
+// command returns immediately.
+#[cfg(unix)]
+fn clear_after<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    expire: Duration,
+    fade_out: Option<Duration>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    info!(logger, "Clearing the display after a delay"; "expire" => ?expire);
+
+    match unsafe { libc::fork() } {
+        -1 => panic!(
+            "Failed to fork the expiry timer: {}",
+            io::Error::last_os_error()
+        ),
+        0 => {
+            // The child can't log, the async logger's thread only exists in the parent.
+            // Detach from the terminal so that closing it doesn't stop the timer.
+            unsafe { libc::setsid() };
+
+            std::thread::sleep(expire);
+
+            let code = if clear(bargraph, fade_out).is_ok() {
+                0
+            } else {
+                1
+            };
+            std::process::exit(code);
+        }
+        _ => {}
+    }
+}
+
+// Without `fork()` the command waits to clear the display itself.
+#[cfg(not(unix))]
+fn clear_after<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    expire: Duration,
+    fade_out: Option<Duration>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    info!(logger, "Waiting to clear the display"; "expire" => ?expire);
+
+    std::thread::sleep(expire);
+
+    clear(bargraph, fade_out).expect("Failed to clear the display");
+}
+
+// Print the state of the display, the transport & the daemons as JSON, exiting with 1
+// if the device doesn't respond.
+fn status<I2C, E>(bargraph: &mut Bargraph<I2C>, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    info!(logger, "Reading the state of the display");
+
+    let mut status = device_status(bargraph, read_state(Path::new(&args.flag_state_file)));
+
+    let transport = transport(args);
+    status["transport"] = json!({
+        "name": format!("{:?}", transport).to_lowercase(),
+        "address": args.flag_i2c_address,
+        "path": match transport {
+            Transport::Linux => Some(&args.flag_i2c_path),
+            _ => None,
+        },
+    });
+    status["daemons"] = daemon_status(args);
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&status).expect("The status is valid JSON")
+    );
+
+    if status["connected"] != json!(true) {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use led_bargraph::mapping;
+
+    #[test]
+    fn parse_state_round_trip() {
+        let mut frame = Frame::from_value(25, 24);
+        assert!(frame.blink);
+        assert_eq!(Some(frame), parse_state(&format_state(&frame)));
+
+        frame.blink = false;
+        assert_eq!(Some(frame), parse_state(&format_state(&frame)));
+
+        assert_eq!(None, parse_state(""));
+        assert_eq!(None, parse_state("YYY"));
+        assert_eq!(None, parse_state(&format!("{} fast", frame)));
+    }
+
+    #[test]
+    fn identified_mapping_swapped() {
+        let mut positions: Vec<u8> = (0..BARGRAPH_RESOLUTION).collect();
+        positions.swap(0, 1);
+
+        let locations = identified_mapping(&mapping::builtin_table(), &positions).unwrap();
+
+        assert_eq!((0, 1), locations[0]);
+        assert_eq!((0, 0), locations[1]);
+        assert_eq!(mapping::bar_to_row_common(23), locations[23]);
+    }
+
+    #[test]
+    fn identified_mapping_invalid() {
+        let mut positions: Vec<u8> = (0..BARGRAPH_RESOLUTION).collect();
+        positions[0] = 1;
+        assert!(identified_mapping(&mapping::builtin_table(), &positions).is_err());
+
+        positions[0] = BARGRAPH_RESOLUTION;
+        assert!(identified_mapping(&mapping::builtin_table(), &positions).is_err());
+    }
+
+    #[test]
+    fn format_mapping_toml() {
+        let contents = format_mapping(&[(0, 1), (4, 7)]);
+
+        assert!(contents.starts_with("# The bar mapping"));
+        assert!(contents.ends_with("bars = [\n    [0, 1],\n    [4, 7],\n]\n"));
+    }
+
+    #[test]
+    fn test_leds_report() {
+        let table = mapping::builtin_table();
+        let (row, common) = table[3];
+        assert_eq!(
+            format!("Bar  3 green: row {}, common {}", row + 1, common),
+            describe_led(&table, 3, LedColor::Green)
+        );
+
+        assert_eq!(Some(LedCheck::Lit), parse_led_check("\n"));
+        assert_eq!(Some(LedCheck::Dead), parse_led_check(" N "));
+        assert_eq!(Some(LedCheck::Miswired), parse_led_check("wrong"));
+        assert_eq!(None, parse_led_check("maybe"));
+
+        assert_eq!("All 48 LEDs lit correctly\n", format_led_report(&[]));
+        assert_eq!(
+            "2 of 48 LEDs failed:\n    a is dead\n    b is miswired\n",
+            format_led_report(&[
+                ("a".to_string(), LedCheck::Dead),
+                ("b".to_string(), LedCheck::Miswired),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_mapping_round_trip() {
+        let mut table = mapping::builtin_table();
+        table.swap(3, 4);
+
+        assert_eq!(Ok(table), parse_mapping(&format_mapping(&table)));
+    }
+
+    #[test]
+    fn parse_mapping_invalid() {
+        assert!(parse_mapping("bars = [[0, 0]]").is_err());
+        assert!(parse_mapping("bars = 1").is_err());
+        assert!(parse_mapping("").is_err());
+    }
+
+    #[test]
+    fn bench_summary() {
+        let mut latencies: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+
+        assert_eq!(
+            BenchSummary {
+                frames: 100,
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(50),
+                p99: Duration::from_millis(99),
+                max: Duration::from_millis(100),
+                frame_rate: 50.0,
+            },
+            BenchSummary::new(&mut latencies, Duration::from_secs(2))
+        );
+
+        let single = BenchSummary::new(&mut [Duration::from_millis(4)], Duration::from_millis(4));
+        assert_eq!(Duration::from_millis(4), single.median);
+        assert_eq!(Duration::from_millis(4), single.p99);
+        assert_eq!(250.0, single.frame_rate);
+    }
+}
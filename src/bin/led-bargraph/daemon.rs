@@ -0,0 +1,1044 @@
+//! The `listen`, `fifo` & `shm` daemons, displaying the values they receive.
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use hal::blocking::i2c::{Write, WriteRead};
+use led_bargraph::{
+    Animation, Bargraph, ColorMap, Frame, Outputs, Pipeline, Render, Sample, Scale,
+};
+
+use args::{parse_animation, parse_duration, Args};
+use outputs::{add_mirror_outputs, add_option_transforms};
+
+use std::fmt::Debug;
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::net::UdpSocket;
+use std::path::Path;
+use std::sync::atomic;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Set by SIGTERM or SIGINT when `--shutdown` is given, stopping `listen`, `fifo` & `shm`.
+pub(crate) static SHUTDOWN: atomic::AtomicBool = atomic::AtomicBool::new(false);
+
+// How often `listen`, `fifo` & `shm` check for a shutdown while waiting for a value.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Display each value received on the UDP socket, until a shutdown is requested.
+//
+// Errors while receiving or displaying a value are logged and the value is
+// dropped, a single bad datagram or bus glitch shouldn't stop the listener.
+pub(crate) fn listen<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let socket = UdpSocket::bind(&args.flag_udp_bind).expect("Failed to bind the UDP socket");
+    socket
+        .set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL))
+        .expect("Failed to set the UDP socket timeout");
+
+    info!(logger, "Listening for values"; "address" => &args.flag_udp_bind);
+
+    let mut pipeline = received_pipeline(bargraph, args, colors);
+    let mut display = DaemonDisplay::new(bargraph, args);
+    let mut buffer = [0u8; 1024];
+
+    while !SHUTDOWN.load(Ordering::SeqCst) {
+        display.idle(logger);
+
+        socket
+            .set_read_timeout(Some(display.timeout()))
+            .expect("Failed to set the UDP socket timeout");
+
+        let (size, source) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(ref e)
+                if e.kind() == io::ErrorKind::WouldBlock
+                    || e.kind() == io::ErrorKind::TimedOut
+                    || e.kind() == io::ErrorKind::Interrupted =>
+            {
+                continue;
+            }
+            Err(e) => {
+                error!(logger, "Failed to receive a datagram"; "error" => format!("{}", e));
+                continue;
+            }
+        };
+
+        let received = match parse_datagram(&buffer[..size]) {
+            Some(value) => value,
+            None => {
+                warn!(logger, "Ignoring unrecognized datagram";
+                      "source" => format!("{}", source), "size" => size);
+                continue;
+            }
+        };
+
+        debug!(logger, "Received a datagram"; "source" => format!("{}", source));
+
+        display_received(&mut display, &mut pipeline, received, logger);
+    }
+
+    display.stop(logger);
+}
+
+// Display each line written to the named pipe, until a shutdown is requested.
+//
+// The pipe reaches EOF whenever the last writer closes it, so it's re-opened to
+// wait for the next writer. Opening & reading the pipe block, so it's read from
+// a background thread.
+pub(crate) fn read_fifo<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let path = Path::new(&args.flag_fifo);
+
+    create_fifo(path).expect("Failed to create the named pipe");
+
+    info!(logger, "Reading values from the named pipe"; "path" => &args.flag_fifo);
+
+    let mut pipeline = received_pipeline(bargraph, args, colors);
+    let mut display = DaemonDisplay::new(bargraph, args);
+    let (sender, receiver) = mpsc::channel();
+    let reader_path = path.to_path_buf();
+    let reader_logger = logger.clone();
+
+    thread::spawn(move || loop {
+        let fifo = fs::File::open(&reader_path).expect("Failed to open the named pipe");
+
+        for line in BufReader::new(fifo).lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    error!(reader_logger, "Failed to read from the named pipe"; "error" => format!("{}", e));
+                    break;
+                }
+            };
+
+            if sender.send(line).is_err() {
+                return;
+            }
+        }
+    });
+
+    while !SHUTDOWN.load(Ordering::SeqCst) {
+        display.idle(logger);
+
+        let line = match receiver.recv_timeout(display.timeout()) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => panic!("Stopped reading the named pipe"),
+        };
+
+        match line.trim().parse() {
+            Ok(received) => display_received(&mut display, &mut pipeline, received, logger),
+            Err(_) => warn!(logger, "Ignoring unrecognized line"; "line" => line),
+        }
+    }
+
+    display.stop(logger);
+}
+
+// Display the values written to the shared memory ring buffer `--fps` times a second,
+// until a shutdown is requested.
+//
+// Every new value goes through the pipeline, e.g. for smoothing, but only the last is
+// displayed. Values overwritten before they're read are dropped.
+pub(crate) fn read_shm<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let ring =
+        SharedRing::open(Path::new(&args.flag_shm)).expect("Failed to map the shared memory");
+
+    info!(logger, "Reading values from the shared memory"; "path" => &args.flag_shm);
+
+    let mut pipeline = received_pipeline(bargraph, args, colors);
+    let mut display = DaemonDisplay::new(bargraph, args);
+    let period = Duration::from_secs(1) / args.flag_fps;
+
+    // Only the values written from now on are displayed.
+    let mut read = ring.written();
+    let mut dropped = 0;
+
+    while !SHUTDOWN.load(Ordering::SeqCst) {
+        display.idle(logger);
+        thread::sleep(display.timeout().min(period));
+
+        let (values, written) = ring.read_since(read);
+        if written - read > values.len() as u64 {
+            debug!(logger, "Dropped values overwritten before they were read";
+                   "dropped" => written - read - values.len() as u64);
+            dropped += written - read - values.len() as u64;
+        }
+        read = written;
+
+        // Every value counts towards the span of `--daily-range`, not only the latest.
+        let mut latest = None;
+        let mut count = 0;
+        for value in values.into_iter().filter(|value| !value.is_nan()) {
+            let sample = pipeline.process(Sample::new(value));
+            latest = Some((display.frame(&pipeline, &sample), sample));
+            count += 1;
+        }
+
+        if let Some((frame, sample)) = latest {
+            debug!(logger, "Read values"; "sample" => ?sample);
+            display.coalesced(count - 1);
+            display.show(&frame, logger);
+        }
+    }
+
+    info!(logger, "Values overwritten before they were read"; "dropped" => dropped);
+    display.stop(logger);
+}
+
+// How many values the shared memory ring buffer holds.
+const SHM_CAPACITY: usize = 1024;
+
+// The size of the shared memory file: the count of values written, then the values.
+const SHM_SIZE: usize = 8 + SHM_CAPACITY * 4;
+
+// A ring buffer of values in a memory-mapped file, written by another process. The
+// count of values written is read with acquire ordering, so every value counted has
+// been written.
+struct SharedRing {
+    map: *mut u8,
+}
+
+#[cfg(unix)]
+impl SharedRing {
+    // Map the file, creating it if it doesn't exist.
+    fn open(path: &Path) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+        use std::ptr;
+
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        if file.metadata()?.len() < SHM_SIZE as u64 {
+            file.set_len(SHM_SIZE as u64)?;
+        }
+
+        // The mapping outlives the file, which can be closed.
+        let map = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                SHM_SIZE,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if map == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(SharedRing {
+            map: map as *mut u8,
+        })
+    }
+
+    // The count of values ever written.
+    fn written(&self) -> u64 {
+        // The mapping is page aligned, so the count is aligned for an atomic.
+        let count = unsafe { &*(self.map as *const atomic::AtomicU64) };
+        count.load(Ordering::Acquire)
+    }
+
+    // The values written since the count `read`, up to the capacity, & the count of
+    // values written. Values overwritten while they were read are dropped.
+    fn read_since(&self, read: u64) -> (Vec<f32>, u64) {
+        let written = self.written();
+        let oldest = |written: u64| written.saturating_sub(SHM_CAPACITY as u64).max(read);
+
+        let values: Vec<f32> = (oldest(written)..written)
+            .map(|n| {
+                let index = 8 + (n as usize % SHM_CAPACITY) * 4;
+                unsafe { std::ptr::read_volatile(self.map.add(index) as *const f32) }
+            })
+            .collect();
+
+        // Drop the values the writer has since lapped, including the one it may be
+        // writing now.
+        let overwritten = (oldest(self.written() + 1) - oldest(written)) as usize;
+
+        (values[overwritten.min(values.len())..].to_vec(), written)
+    }
+}
+
+#[cfg(not(unix))]
+impl SharedRing {
+    fn open(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::other("Shared memory is only supported on Unix"))
+    }
+
+    fn written(&self) -> u64 {
+        0
+    }
+
+    fn read_since(&self, read: u64) -> (Vec<f32>, u64) {
+        (Vec::new(), read)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SharedRing {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.map as *mut libc::c_void, SHM_SIZE);
+        }
+    }
+}
+
+// Request a shutdown on SIGTERM or SIGINT, rather than being killed.
+#[cfg(unix)]
+pub(crate) fn handle_shutdown_signals() {
+    extern "C" fn request_shutdown(_signal: libc::c_int) {
+        SHUTDOWN.store(true, Ordering::SeqCst);
+    }
+
+    let handler = request_shutdown as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    for signal in [libc::SIGTERM, libc::SIGINT].iter() {
+        if unsafe { libc::signal(*signal, handler) } == libc::SIG_ERR {
+            panic!(
+                "Failed to handle the shutdown signals: {}",
+                io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn handle_shutdown_signals() {
+    panic!("The shutdown animation is only supported on Unix");
+}
+
+// Create the named pipe at `path`, an existing named pipe is re-used.
+#[cfg(unix)]
+fn create_fifo(path: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        if metadata.file_type().is_fifo() {
+            return Ok(());
+        }
+
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a named pipe", path.display()),
+        ));
+    }
+
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+
+    // Allow anyone to write values, subject to the umask (same as `mkfifo`).
+    if unsafe { libc::mkfifo(c_path.as_ptr(), 0o666) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn create_fifo(_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "Named pipes are only supported on Unix",
+    ))
+}
+
+// Display a value received from one of the input sources, through the pipeline.
+fn display_received<I2C, E>(
+    display: &mut DaemonDisplay<I2C>,
+    pipeline: &mut Pipeline,
+    received: f32,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let sample = pipeline.process(Sample::new(received));
+
+    debug!(logger, "Received a value"; "received" => received, "sample" => ?sample);
+
+    let frame = display.frame(pipeline, &sample);
+    display.show(&frame, logger);
+}
+
+// The brightness of the display while the screensaver plays.
+const SCREENSAVER_BRIGHTNESS: u8 = 0;
+
+// The display of `listen` & `fifo`, mirrored to the other outputs. It's kept apart from
+// the mirrors so that it can be dimmed while the `--screensaver` plays.
+struct DaemonDisplay<'a, I2C: 'a> {
+    bargraph: &'a mut Bargraph<I2C>,
+    mirrors: Outputs<'static>,
+    screensaver: Option<Screensaver>,
+    // The brightness to restore once the screensaver stops.
+    brightness: u8,
+    daily_range: Option<DailyRange>,
+    limiter: FrameLimiter,
+}
+
+impl<'a, I2C, E> DaemonDisplay<'a, I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    fn new(bargraph: &'a mut Bargraph<I2C>, args: &Args) -> Self {
+        let resolution = bargraph.resolution();
+
+        let mut mirrors = Outputs::new();
+        add_mirror_outputs(&mut mirrors, resolution, args);
+
+        let screensaver = args.flag_screensaver.as_ref().map(|animation| {
+            let idle =
+                parse_duration(&args.flag_idle).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+
+            Screensaver::new(
+                &parse_animation(animation),
+                resolution,
+                idle,
+                Instant::now(),
+            )
+        });
+
+        let brightness = bargraph.brightness();
+
+        if args.flag_fps == 0 {
+            docopt::Error::Argv("Invalid fps [0], expected at least [1]".to_string()).exit();
+        }
+        let limiter = FrameLimiter::new(Duration::from_secs(1) / args.flag_fps);
+
+        let daily_range = if args.flag_daily_range {
+            let reset =
+                NaiveTime::parse_from_str(&args.flag_daily_reset, "%H:%M").unwrap_or_else(|_| {
+                    docopt::Error::Argv(format!(
+                        "Invalid daily reset [{}], expected `HH:MM`",
+                        args.flag_daily_reset
+                    ))
+                    .exit()
+                });
+
+            Some(DailyRange::new(reset))
+        } else {
+            None
+        };
+
+        DaemonDisplay {
+            bargraph,
+            mirrors,
+            screensaver,
+            brightness,
+            daily_range,
+            limiter,
+        }
+    }
+
+    // The frame of a received sample, its span with `--daily-range`.
+    fn frame(&mut self, pipeline: &Pipeline, sample: &Sample) -> Frame {
+        match self.daily_range {
+            Some(ref mut daily_range) => {
+                daily_range.record(sample, Local::now());
+                daily_range.frame(pipeline, sample)
+            }
+            None => pipeline.frame(sample),
+        }
+    }
+
+    // How long to wait for a value, without missing a shutdown, a held frame or the
+    // screensaver.
+    fn timeout(&self) -> Duration {
+        let now = Instant::now();
+        let screensaver = self
+            .screensaver
+            .as_ref()
+            .map_or(SHUTDOWN_POLL_INTERVAL, |screensaver| {
+                screensaver.next_frame_in(now)
+            });
+        let held = self.limiter.held_for(now).unwrap_or(SHUTDOWN_POLL_INTERVAL);
+
+        // A zero timeout is invalid for a socket.
+        screensaver
+            .min(held)
+            .min(SHUTDOWN_POLL_INTERVAL)
+            .max(Duration::from_millis(1))
+    }
+
+    // Count values that were never shown because a later value replaced them.
+    fn coalesced(&mut self, count: u64) {
+        self.limiter.coalesced += count;
+    }
+
+    // Log the counters once the values stop.
+    fn stop(&self, logger: &slog::Logger) {
+        info!(logger, "Stopped displaying values"; "coalesced" => self.limiter.coalesced);
+    }
+
+    // Show the frame of a received value, at most `--fps` times a second. A frame
+    // received sooner is held until it's due, replacing any frame already held. A
+    // change wakes the display from the screensaver, unchanged values don't.
+    fn show(&mut self, frame: &Frame, logger: &slog::Logger) {
+        let frame = match self.limiter.offer(*frame, Instant::now()) {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        self.show_now(&frame, logger);
+    }
+
+    fn show_now(&mut self, frame: &Frame, logger: &slog::Logger) {
+        if let Some(ref mut screensaver) = self.screensaver {
+            let changed = screensaver.change(frame, Instant::now());
+
+            if screensaver.is_playing() {
+                if !changed {
+                    return;
+                }
+
+                info!(logger, "Stopping the screensaver");
+                screensaver.stop();
+
+                if let Err(e) = self.bargraph.set_brightness(self.brightness) {
+                    error!(logger, "Failed to restore the brightness"; "error" => %e);
+                }
+            }
+        }
+
+        self.render(frame, logger);
+    }
+
+    // Show the held frame or play the screensaver's next frame, once it's due.
+    fn idle(&mut self, logger: &slog::Logger) {
+        if let Some(frame) = self.limiter.due(Instant::now()) {
+            self.show_now(&frame, logger);
+        }
+
+        let (starting, frame) = match self.screensaver {
+            Some(ref mut screensaver) => {
+                let starting = !screensaver.is_playing();
+
+                match screensaver.next_frame(Instant::now()) {
+                    Some(frame) => (starting, frame),
+                    None => return,
+                }
+            }
+            None => return,
+        };
+
+        if starting {
+            info!(logger, "Starting the screensaver");
+
+            self.brightness = self.bargraph.brightness();
+            if let Err(e) = self.bargraph.set_brightness(SCREENSAVER_BRIGHTNESS) {
+                error!(logger, "Failed to dim the display"; "error" => %e);
+            }
+        }
+
+        self.render(&frame, logger);
+    }
+
+    fn render(&mut self, frame: &Frame, logger: &slog::Logger) {
+        if let Err(e) = self.bargraph.render(frame) {
+            error!(logger, "Failed to set a value within a range on the display";
+                   "error" => %e);
+        }
+
+        if let Err(e) = self.mirrors.render(frame) {
+            error!(logger, "Failed to mirror the display"; "error" => %e);
+        }
+    }
+}
+
+// Limits how often received values are shown, holding the latest frame that arrives
+// too soon until it's due so that a fast source can't back up behind the display.
+struct FrameLimiter {
+    period: Duration,
+    // When a frame was last shown.
+    shown: Option<Instant>,
+    held: Option<Frame>,
+    // How many frames were replaced by a later frame before they were shown.
+    coalesced: u64,
+}
+
+impl FrameLimiter {
+    fn new(period: Duration) -> Self {
+        FrameLimiter {
+            period,
+            shown: None,
+            held: None,
+            coalesced: 0,
+        }
+    }
+
+    // The frame to show now, or `None` if it's held until it's due.
+    fn offer(&mut self, frame: Frame, now: Instant) -> Option<Frame> {
+        if self.held.replace(frame).is_some() {
+            self.coalesced += 1;
+        }
+
+        self.due(now)
+    }
+
+    // The held frame, once it's due.
+    fn due(&mut self, now: Instant) -> Option<Frame> {
+        if self.held_for(now)? > Duration::from_secs(0) {
+            return None;
+        }
+
+        self.shown = Some(now);
+        self.held.take()
+    }
+
+    // How long until the held frame is due, if there is one.
+    fn held_for(&self, now: Instant) -> Option<Duration> {
+        self.held?;
+
+        Some(self.shown.map_or(Duration::from_secs(0), |shown| {
+            (shown + self.period).saturating_duration_since(now)
+        }))
+    }
+}
+
+// The lowest & highest samples since the reset time last passed, for `--daily-range`.
+struct DailyRange {
+    reset: NaiveTime,
+    next_reset: Option<DateTime<Local>>,
+    // The lowest & highest values, once there's a sample.
+    span: Option<(f32, f32)>,
+}
+
+impl DailyRange {
+    fn new(reset: NaiveTime) -> Self {
+        DailyRange {
+            reset,
+            next_reset: None,
+            span: None,
+        }
+    }
+
+    // Add the sample to the span, restarting the span first if the reset time has
+    // passed since the last sample.
+    fn record(&mut self, sample: &Sample, now: DateTime<Local>) {
+        if self.next_reset.is_none_or(|next_reset| now >= next_reset) {
+            self.span = None;
+            self.next_reset = Some(next_daily(now, self.reset));
+        }
+
+        let value = sample.value;
+        let (low, high) = self.span.get_or_insert((value, value));
+        *low = low.min(value);
+        *high = high.max(value);
+    }
+
+    // The frame drawing the span as a band, with the sample as the marker.
+    fn frame(&self, pipeline: &Pipeline, sample: &Sample) -> Frame {
+        let (low, high) = self.span.unwrap_or((sample.value, sample.value));
+
+        pipeline.frame_band(&Sample::new(low), &Sample::new(high), sample)
+    }
+}
+
+// The next time after `now` that the local time is `time`. A time skipped by a
+// daylight saving change happens the day after.
+fn next_daily(now: DateTime<Local>, time: NaiveTime) -> DateTime<Local> {
+    (0..3)
+        .filter_map(|days| {
+            let date = now.date_naive() + chrono::Duration::days(days);
+            Local.from_local_datetime(&date.and_time(time)).earliest()
+        })
+        .find(|next| *next > now)
+        .expect("The time happens within three days")
+}
+
+// Repeats an animation once the values shown have been unchanged for a while, until
+// they next change.
+struct Screensaver {
+    frames: Vec<Frame>,
+    interval: Duration,
+    idle: Duration,
+    // The values last shown, & when they changed.
+    shown: Option<Frame>,
+    changed: Instant,
+    // The next frame to play & when, while playing.
+    playing: Option<(usize, Instant)>,
+}
+
+impl Screensaver {
+    fn new(animation: &Animation, resolution: u8, idle: Duration, now: Instant) -> Self {
+        Screensaver {
+            frames: animation.frames(resolution),
+            interval: animation.interval,
+            idle,
+            shown: None,
+            changed: now,
+            playing: None,
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    // Note the values shown, returning whether they changed.
+    fn change(&mut self, frame: &Frame, now: Instant) -> bool {
+        if self.shown == Some(*frame) {
+            return false;
+        }
+
+        self.shown = Some(*frame);
+        self.changed = now;
+        true
+    }
+
+    // Stop playing, the idle time starts again from the last change.
+    fn stop(&mut self) {
+        self.playing = None;
+    }
+
+    // How long until the next frame is due.
+    fn next_frame_in(&self, now: Instant) -> Duration {
+        let due = match self.playing {
+            Some((_, due)) => due,
+            None => self.changed + self.idle,
+        };
+
+        due.saturating_duration_since(now)
+    }
+
+    // The next frame, once it's due, starting to play once idle.
+    fn next_frame(&mut self, now: Instant) -> Option<Frame> {
+        if self.next_frame_in(now) > Duration::from_secs(0) || self.frames.is_empty() {
+            return None;
+        }
+
+        let index = self.playing.map_or(0, |(index, _)| index);
+        self.playing = Some(((index + 1) % self.frames.len(), now + self.interval));
+
+        Some(self.frames[index])
+    }
+}
+
+// The pipeline of the daemon, received values are in units of the range unless
+// they're normalized.
+fn received_pipeline<I2C, E>(
+    bargraph: &Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+) -> Pipeline
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    let mut pipeline = bargraph.pipeline(args.arg_range);
+    pipeline.set_colors(colors.cloned());
+    pipeline.add_transform(received_scale(args.arg_range, args.flag_normalized));
+    add_option_transforms(&mut pipeline, args);
+
+    pipeline
+}
+
+// Whether each daemon is running: `listen` if its address is in use, `fifo` & `shm` if
+// their files exist, with the number of values written to `shm`.
+pub(crate) fn daemon_status(args: &Args) -> serde_json::Value {
+    let listening = match UdpSocket::bind(&args.flag_udp_bind) {
+        Ok(_) => false,
+        Err(e) => e.kind() == io::ErrorKind::AddrInUse,
+    };
+
+    let fifo = Path::new(&args.flag_fifo);
+    let shm = Path::new(&args.flag_shm);
+    let written = if shm.exists() {
+        SharedRing::open(shm).ok().map(|ring| ring.written())
+    } else {
+        None
+    };
+
+    json!({
+        "listen": {"address": args.flag_udp_bind, "running": listening},
+        "fifo": {"path": fifo, "running": fifo.exists()},
+        "shm": {"path": shm, "running": written.is_some(), "written": written},
+    })
+}
+
+// Convert received values into the fraction of the display, either already fractions
+// of the range or in its units. Out-of-range values are passed through so that they
+// blink.
+fn received_scale(range: u8, normalized: bool) -> Scale {
+    if normalized {
+        Scale::new(0.0, 1.0)
+    } else {
+        Scale::new(0.0, f32::from(range))
+    }
+}
+
+// Extract the value from a datagram, either the first numeric argument of an OSC
+// message or a plain-text number.
+//
+// OSC spec: http://opensoundcontrol.org/spec-1_0
+fn parse_datagram(datagram: &[u8]) -> Option<f32> {
+    if datagram.first() == Some(&b'/') {
+        return parse_osc_message(datagram);
+    }
+
+    std::str::from_utf8(datagram).ok()?.trim().parse().ok()
+}
+
+fn parse_osc_message(message: &[u8]) -> Option<f32> {
+    let (_address, remainder) = parse_osc_string(message)?;
+    let (type_tags, arguments) = parse_osc_string(remainder)?;
+
+    let mut type_tags = type_tags.chars();
+    if type_tags.next() != Some(',') {
+        return None;
+    }
+
+    // Only the first argument is used, numbers are big-endian.
+    match type_tags.next()? {
+        'f' => Some(f32::from_bits(u32::from_be_bytes(
+            *arguments.first_chunk::<4>()?,
+        ))),
+        'i' => Some(i32::from_be_bytes(*arguments.first_chunk::<4>()?) as f32),
+        'd' => Some(f64::from_bits(u64::from_be_bytes(*arguments.first_chunk::<8>()?)) as f32),
+        'h' => Some(i64::from_be_bytes(*arguments.first_chunk::<8>()?) as f32),
+        _ => None,
+    }
+}
+
+// OSC strings are null-terminated and padded with nulls to a multiple of 4 bytes.
+fn parse_osc_string(bytes: &[u8]) -> Option<(&str, &[u8])> {
+    let length = bytes.iter().position(|&byte| byte == 0)?;
+    let padded_length = (length / 4 + 1) * 4;
+
+    if padded_length > bytes.len() {
+        return None;
+    }
+
+    let string = std::str::from_utf8(&bytes[..length]).ok()?;
+
+    Some((string, &bytes[padded_length..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use led_bargraph::Transform;
+
+    #[test]
+    fn parse_datagram_plain() {
+        assert_eq!(Some(7.0), parse_datagram(b"7"));
+        assert_eq!(Some(0.5), parse_datagram(b" 0.5\n"));
+        assert_eq!(None, parse_datagram(b"seven"));
+    }
+
+    #[test]
+    fn parse_datagram_osc() {
+        let mut message = b"/bargraph/value\0,f\0\0".to_vec();
+        message.extend_from_slice(&0.75f32.to_be_bytes());
+        assert_eq!(Some(0.75), parse_datagram(&message));
+
+        let mut message = b"/value\0\0,i\0\0".to_vec();
+        message.extend_from_slice(&12i32.to_be_bytes());
+        assert_eq!(Some(12.0), parse_datagram(&message));
+    }
+
+    #[test]
+    fn parse_datagram_osc_invalid() {
+        // Missing argument data.
+        assert_eq!(None, parse_datagram(b"/value\0\0,f\0\0"));
+        // Unsupported argument type.
+        assert_eq!(None, parse_datagram(b"/value\0\0,s\0\0abc\0"));
+        // Missing type tags.
+        assert_eq!(None, parse_datagram(b"/value\0\0"));
+    }
+
+    #[test]
+    fn received_scale_normalized() {
+        assert_eq!(
+            Sample::new(0.7),
+            received_scale(10, true).apply(Sample::new(0.7))
+        );
+        assert_eq!(
+            Sample::new(0.7),
+            received_scale(10, false).apply(Sample::new(7.0))
+        );
+        assert_eq!(
+            Sample::new(-0.1),
+            received_scale(10, false).apply(Sample::new(-1.0))
+        );
+        assert_eq!(
+            Sample::new(100.0),
+            received_scale(10, false).apply(Sample::new(1000.0))
+        );
+    }
+
+    #[test]
+    fn screensaver() {
+        let start = Instant::now();
+        let animation: Animation = "sweep:green:100".parse().unwrap();
+        let mut screensaver = Screensaver::new(&animation, 3, Duration::from_secs(60), start);
+
+        let gauge = Frame::from_value(2, 3);
+        assert!(screensaver.change(&gauge, start));
+        assert_eq!(Duration::from_secs(60), screensaver.next_frame_in(start));
+        assert_eq!(None, screensaver.next_frame(start));
+
+        // Unchanged values don't delay it.
+        let idle = start + Duration::from_secs(60);
+        assert!(!screensaver.change(&gauge, start + Duration::from_secs(30)));
+        assert_eq!(
+            Some("G......................."),
+            screensaver
+                .next_frame(idle)
+                .map(|f| f.to_string())
+                .as_deref()
+        );
+        assert!(screensaver.is_playing());
+
+        // Each frame is played after the interval, repeating.
+        assert_eq!(None, screensaver.next_frame(idle));
+        let frames: Vec<String> = (1..=3)
+            .map(|frame| idle + Duration::from_millis(100 * frame))
+            .filter_map(|now| screensaver.next_frame(now))
+            .map(|frame| frame.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                ".G......................",
+                "..G.....................",
+                "G.......................",
+            ],
+            frames
+        );
+
+        // A change stops it, until idle again.
+        let changed = idle + Duration::from_secs(1);
+        assert!(screensaver.change(&Frame::from_value(1, 3), changed));
+        screensaver.stop();
+        assert!(!screensaver.is_playing());
+        assert_eq!(Duration::from_secs(60), screensaver.next_frame_in(changed));
+    }
+
+    #[test]
+    fn daily_range() {
+        let at = |day, hour| Local.with_ymd_and_hms(2024, 6, day, hour, 0, 0).unwrap();
+        let pipeline = Pipeline::new(6);
+        let mut daily_range = DailyRange::new(NaiveTime::from_hms_opt(6, 0, 0).unwrap());
+
+        for (hour, value) in [(7, 0.5), (12, 0.85), (18, 0.35)].iter() {
+            daily_range.record(&Sample::new(*value), at(21, *hour));
+        }
+        assert_eq!(
+            "....RRRRGGGGGGGGGGGG....",
+            daily_range.frame(&pipeline, &Sample::new(0.35)).to_string()
+        );
+
+        // The span restarts once the reset time passes.
+        daily_range.record(&Sample::new(0.5), at(22, 5));
+        daily_range.record(&Sample::new(0.7), at(22, 6));
+        assert_eq!(
+            "............RRRR........",
+            daily_range.frame(&pipeline, &Sample::new(0.7)).to_string()
+        );
+    }
+
+    #[test]
+    fn frame_limiter() {
+        let start = Instant::now();
+        let period = Duration::from_millis(100);
+        let mut limiter = FrameLimiter::new(period);
+        let frame = |value| Frame::from_value(value, 6);
+
+        // The first frame is shown straight away.
+        assert_eq!(Some(frame(1)), limiter.offer(frame(1), start));
+        assert_eq!(None, limiter.held_for(start));
+
+        // Frames received sooner are held, only the latest is shown once it's due.
+        let soon = start + Duration::from_millis(10);
+        assert_eq!(None, limiter.offer(frame(2), soon));
+        assert_eq!(None, limiter.offer(frame(3), soon));
+        assert_eq!(Some(Duration::from_millis(90)), limiter.held_for(soon));
+        assert_eq!(None, limiter.due(soon));
+        assert_eq!(Some(frame(3)), limiter.due(start + period));
+        assert_eq!(None, limiter.due(start + period * 2));
+        assert_eq!(1, limiter.coalesced);
+
+        assert_eq!(Some(frame(4)), limiter.offer(frame(4), start + period * 3));
+    }
+
+    #[test]
+    fn next_daily() {
+        let now = Local.with_ymd_and_hms(2024, 6, 21, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            Local.with_ymd_and_hms(2024, 6, 21, 18, 30, 0).unwrap(),
+            super::next_daily(now, NaiveTime::from_hms_opt(18, 30, 0).unwrap())
+        );
+        assert_eq!(
+            Local.with_ymd_and_hms(2024, 6, 22, 12, 0, 0).unwrap(),
+            super::next_daily(now, NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn shared_ring() {
+        use std::os::unix::fs::FileExt;
+
+        let path = std::env::temp_dir().join(format!("led-bargraph-shm-{}", std::process::id()));
+        let ring = SharedRing::open(&path).unwrap();
+        assert_eq!(SHM_SIZE as u64, fs::metadata(&path).unwrap().len());
+        assert_eq!(0, ring.written());
+
+        // Write values the way a producer would: the value, then the count.
+        let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+        let write = |n: u64, value: f32| {
+            let index = 8 + (n as usize % SHM_CAPACITY) * 4;
+            file.write_at(&value.to_ne_bytes(), index as u64).unwrap();
+            file.write_at(&(n + 1).to_ne_bytes(), 0).unwrap();
+        };
+
+        write(0, 1.5);
+        write(1, 2.5);
+        assert_eq!((vec![1.5, 2.5], 2), ring.read_since(0));
+        assert_eq!((vec![2.5], 2), ring.read_since(1));
+        assert_eq!((vec![], 2), ring.read_since(2));
+
+        // Values lapped by the writer are dropped, & the oldest in case it's being
+        // written.
+        for n in 2..(SHM_CAPACITY as u64 + 5) {
+            write(n, n as f32);
+        }
+        let (values, written) = ring.read_since(2);
+        assert_eq!(SHM_CAPACITY as u64 + 5, written);
+        assert_eq!(SHM_CAPACITY - 1, values.len());
+        assert_eq!(Some(&6.0), values.first());
+        assert_eq!(Some(&(SHM_CAPACITY as f32 + 4.0)), values.last());
+
+        drop(ring);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,440 @@
+//! Logging to the terminal, the system log & a rotating `--log-file`.
+use chrono::{DateTime, Local, NaiveDate};
+use slog::{Drain, KV};
+
+use args::parse_size;
+
+use std::fmt::{self, Debug};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::result;
+use std::sync::atomic::Ordering;
+use std::sync::{atomic, Arc};
+
+// Custom Drain logic to support enabling different log levels.
+pub(crate) struct RuntimeLevelFilter<D> {
+    pub(crate) drain: D,
+    pub(crate) debug: Arc<atomic::AtomicBool>,
+    pub(crate) trace: Arc<atomic::AtomicBool>,
+    pub(crate) verbose: Arc<atomic::AtomicBool>,
+}
+
+impl<D> Drain for RuntimeLevelFilter<D>
+where
+    D: Drain,
+{
+    type Ok = Option<D::Ok>;
+    type Err = Option<D::Err>;
+
+    fn log(
+        &self,
+        record: &slog::Record,
+        values: &slog::OwnedKVList,
+    ) -> result::Result<Self::Ok, Self::Err> {
+        let current_level = if self.trace.load(Ordering::Relaxed) {
+            slog::Level::Trace
+        } else if self.debug.load(Ordering::Relaxed) {
+            slog::Level::Debug
+        } else if self.verbose.load(Ordering::Relaxed) {
+            slog::Level::Info
+        } else {
+            slog::Level::Warning
+        };
+
+        if record.level().is_at_least(current_level) {
+            self.drain.log(record, values).map(Some).map_err(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+// Where the log is written, from `--log-target`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub(crate) enum LogTarget {
+    Terminal,
+    Syslog,
+    Journald,
+}
+
+// The key-values of a log record & its logger, formatted, for the system log.
+struct LogFields(Vec<(String, String)>);
+
+impl slog::Serializer for LogFields {
+    fn emit_arguments(&mut self, key: slog::Key, val: &fmt::Arguments) -> slog::Result {
+        self.0.push((key.to_string(), val.to_string()));
+        Ok(())
+    }
+}
+
+fn log_fields(record: &slog::Record, values: &slog::OwnedKVList) -> Vec<(String, String)> {
+    let mut fields = LogFields(Vec::new());
+    let _ = record.kv().serialize(record, &mut fields);
+    let _ = values.serialize(record, &mut fields);
+
+    fields.0
+}
+
+// The syslog priority of the level, also used by journald.
+fn log_priority(level: slog::Level) -> u8 {
+    match level {
+        slog::Level::Critical => 2,
+        slog::Level::Error => 3,
+        slog::Level::Warning => 4,
+        slog::Level::Info => 6,
+        slog::Level::Debug | slog::Level::Trace => 7,
+    }
+}
+
+// The message & its key-values as one line, like the terminal, e.g.
+// `Listening for values, address: 0.0.0.0:9000`.
+fn format_log_message(message: &str, fields: &[(String, String)]) -> String {
+    fields
+        .iter()
+        .fold(message.to_string(), |line, (key, value)| {
+            format!("{}, {}: {}", line, key, value)
+        })
+}
+
+// Logs to syslog, with the key-values in the message.
+#[cfg(unix)]
+struct Syslog;
+
+#[cfg(unix)]
+impl Drain for Syslog {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(
+        &self,
+        record: &slog::Record,
+        values: &slog::OwnedKVList,
+    ) -> result::Result<Self::Ok, Self::Err> {
+        use std::ffi::CString;
+
+        let message = format_log_message(&record.msg().to_string(), &log_fields(record, values));
+        if let Ok(message) = CString::new(message.replace('\0', "")) {
+            unsafe {
+                libc::syslog(
+                    libc::c_int::from(log_priority(record.level())),
+                    b"%s\0".as_ptr() as *const libc::c_char,
+                    message.as_ptr(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Where journald receives entries in its native protocol.
+//
+// Protocol: https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
+#[cfg(unix)]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+// Logs to journald, with the key-values as fields of the entry, e.g. `VALUE=7`.
+#[cfg(unix)]
+struct Journald(std::os::unix::net::UnixDatagram);
+
+#[cfg(unix)]
+impl Drain for Journald {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(
+        &self,
+        record: &slog::Record,
+        values: &slog::OwnedKVList,
+    ) -> result::Result<Self::Ok, Self::Err> {
+        let mut fields = vec![
+            ("MESSAGE".to_string(), record.msg().to_string()),
+            (
+                "PRIORITY".to_string(),
+                log_priority(record.level()).to_string(),
+            ),
+            ("SYSLOG_IDENTIFIER".to_string(), "led-bargraph".to_string()),
+            ("CODE_FILE".to_string(), record.file().to_string()),
+            ("CODE_LINE".to_string(), record.line().to_string()),
+        ];
+        fields.extend(
+            log_fields(record, values)
+                .into_iter()
+                .map(|(key, value)| (journal_field_name(&key), value)),
+        );
+
+        // An entry that can't be sent is dropped, there's nowhere else to log it.
+        let _ = self.0.send(&journal_entry(&fields));
+
+        Ok(())
+    }
+}
+
+// The journald field of a key, only uppercase letters, digits & underscores, e.g.
+// `sample rate` is `SAMPLE_RATE`. Leading underscores are reserved for journald.
+#[cfg(unix)]
+fn journal_field_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' | c @ '0'..='9' => c,
+            _ => '_',
+        })
+        .collect();
+    let name = name.trim_start_matches('_');
+
+    match name.chars().next() {
+        Some('A'..='Z') => name.to_string(),
+        _ => format!("FIELD_{}", name),
+    }
+}
+
+// The datagram of a journald entry: `NAME=value` lines, or the name, the length &
+// the value for values spanning lines.
+#[cfg(unix)]
+fn journal_entry(fields: &[(String, String)]) -> Vec<u8> {
+    let mut entry = Vec::new();
+    for (name, value) in fields {
+        entry.extend_from_slice(name.as_bytes());
+        if value.contains('\n') {
+            entry.push(b'\n');
+            entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            entry.push(b'=');
+        }
+        entry.extend_from_slice(value.as_bytes());
+        entry.push(b'\n');
+    }
+
+    entry
+}
+
+// The drain of the system log of `--log-target`.
+#[cfg(unix)]
+pub(crate) fn open_system_log(
+    target: LogTarget,
+) -> io::Result<Box<dyn Drain<Ok = (), Err = slog::Never> + Send>> {
+    match target {
+        LogTarget::Syslog => {
+            // syslog keeps the identity, so it must be static.
+            unsafe {
+                libc::openlog(
+                    b"led-bargraph\0".as_ptr() as *const libc::c_char,
+                    libc::LOG_PID,
+                    libc::LOG_DAEMON,
+                )
+            };
+            Ok(Box::new(Syslog))
+        }
+        LogTarget::Journald => {
+            let socket = std::os::unix::net::UnixDatagram::unbound()?;
+            socket.connect(JOURNALD_SOCKET)?;
+            Ok(Box::new(Journald(socket)))
+        }
+        LogTarget::Terminal => unreachable!("The terminal isn't a system log"),
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn open_system_log(
+    _target: LogTarget,
+) -> io::Result<Box<dyn Drain<Ok = (), Err = slog::Never> + Send>> {
+    Err(io::Error::other("The system log is only supported on Unix"))
+}
+
+// When the `--log-file` is rotated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LogRotation {
+    // Once it's at least this many bytes.
+    Size(u64),
+    // Once the local date changes.
+    Daily,
+}
+
+// The `--log-file`, moved to `<path>.1` when it's rotated, shifting the older files
+// up to `<path>.<keep>` & removing the oldest. Only rotated between records, so that
+// each record is whole in one file.
+pub(crate) struct RotatingLog {
+    path: PathBuf,
+    rotation: LogRotation,
+    keep: u32,
+    file: fs::File,
+    // The size of the file & the local date it was started on.
+    size: u64,
+    started: NaiveDate,
+    // Whether the next write starts a record.
+    between_records: bool,
+}
+
+impl RotatingLog {
+    pub(crate) fn open(path: &Path, rotation: LogRotation, keep: u32) -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let metadata = file.metadata()?;
+
+        // Carry on with an existing file, as of the date it was last written.
+        let started = metadata
+            .modified()
+            .map(|modified| DateTime::<Local>::from(modified).date_naive())
+            .unwrap_or_else(|_| Local::now().date_naive());
+
+        Ok(RotatingLog {
+            path: path.to_path_buf(),
+            rotation,
+            keep,
+            file,
+            size: metadata.len(),
+            started,
+            between_records: true,
+        })
+    }
+
+    fn is_due(&self, today: NaiveDate) -> bool {
+        match self.rotation {
+            LogRotation::Size(max) => self.size >= max,
+            LogRotation::Daily => today != self.started,
+        }
+    }
+
+    fn rotate(&mut self, today: NaiveDate) -> io::Result<()> {
+        let rotated = |n: u32| PathBuf::from(format!("{}.{}", self.path.display(), n));
+
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..self.keep).rev() {
+                if rotated(n).exists() {
+                    fs::rename(rotated(n), rotated(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+
+        self.file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.started = today;
+
+        Ok(())
+    }
+}
+
+impl io::Write for RotatingLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.between_records {
+            let today = Local::now().date_naive();
+            if self.is_due(today) {
+                self.rotate(today)?;
+            }
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        self.between_records = buf[..written].last() == Some(&b'\n');
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+// When to rotate the `--log-file` from `--log-rotate`, either `daily` or a size.
+pub(crate) fn parse_log_rotation(rotation: &str) -> result::Result<LogRotation, String> {
+    if rotation.trim() == "daily" {
+        return Ok(LogRotation::Daily);
+    }
+
+    parse_size(rotation).map(LogRotation::Size).map_err(|_| {
+        format!(
+            "Invalid log rotation [{}], expected `daily` or e.g. `10M`",
+            rotation
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as IoWrite;
+
+    #[test]
+    fn format_log_message_fields() {
+        assert_eq!("Cleared", format_log_message("Cleared", &[]));
+        assert_eq!(
+            "Received, value: 7, source: 10.0.0.2:5000",
+            format_log_message(
+                "Received",
+                &[
+                    ("value".to_string(), "7".to_string()),
+                    ("source".to_string(), "10.0.0.2:5000".to_string()),
+                ]
+            )
+        );
+        assert_eq!(3, log_priority(slog::Level::Error));
+        assert_eq!(7, log_priority(slog::Level::Trace));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn journal_entry_fields() {
+        assert_eq!("VALUE", journal_field_name("value"));
+        assert_eq!("SAMPLE_RATE", journal_field_name("sample rate"));
+        assert_eq!("PID", journal_field_name("_pid"));
+        assert_eq!("FIELD_2X", journal_field_name("2x"));
+
+        let entry = journal_entry(&[
+            ("MESSAGE".to_string(), "Received".to_string()),
+            ("LINES".to_string(), "a\nb".to_string()),
+        ]);
+
+        let mut expected = b"MESSAGE=Received\nLINES\n".to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"a\nb\n");
+        assert_eq!(expected, entry);
+    }
+
+    #[test]
+    fn parse_log_rotation_size() {
+        assert_eq!(Ok(LogRotation::Daily), parse_log_rotation("daily"));
+        assert_eq!(Ok(LogRotation::Size(10 << 20)), parse_log_rotation("10M"));
+        assert!(parse_log_rotation("weekly").is_err());
+    }
+
+    #[test]
+    fn rotating_log() {
+        let dir = std::env::temp_dir().join(format!("led-bargraph-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("led-bargraph.log");
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+
+        let mut log = RotatingLog::open(&path, LogRotation::Size(6), 2).unwrap();
+        for record in ["first\n", "second\n", "third\n", "fourth\n"].iter() {
+            log.write_all(record.as_bytes()).unwrap();
+        }
+
+        // A record is only rotated once it's whole.
+        log.write_all(b"fif").unwrap();
+        log.write_all(b"th\n").unwrap();
+
+        assert_eq!("fifth\n", read("led-bargraph.log"));
+        assert_eq!("fourth\n", read("led-bargraph.log.1"));
+        assert_eq!("third\n", read("led-bargraph.log.2"));
+        assert!(!dir.join("led-bargraph.log.3").exists());
+
+        // The date of an existing file is when it was last written.
+        let mut log = RotatingLog::open(&path, LogRotation::Daily, 2).unwrap();
+        log.started = log.started.pred_opt().unwrap();
+        log.write_all(b"sixth\n").unwrap();
+        assert_eq!("sixth\n", read("led-bargraph.log"));
+        assert_eq!("fifth\n", read("led-bargraph.log.1"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+extern crate chrono;
+extern crate docopt;
+
+extern crate embedded_hal as hal;
+extern crate ht16k33;
+extern crate led_bargraph;
+
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+extern crate shared_bus;
+
+#[macro_use]
+extern crate slog;
+extern crate slog_async;
+extern crate slog_term;
+extern crate toml;
+
+#[cfg(unix)]
+extern crate libc;
+
+#[cfg(feature = "ftdi")]
+extern crate ftdi_embedded_hal;
+#[cfg(feature = "network")]
+extern crate imap;
+#[cfg(feature = "ftdi")]
+extern crate libftd2xx;
+#[cfg(feature = "network")]
+extern crate native_tls;
+#[cfg(feature = "network")]
+extern crate ureq;
+
+// The `linux_embedded_hal` only compiles on linux.
+#[cfg(target_os = "linux")]
+extern crate linux_embedded_hal;
+
+mod args;
+mod commands;
+mod daemon;
+mod logging;
+mod mock;
+mod net;
+mod outputs;
+mod rpc;
+mod sources;
+
+use docopt::Docopt;
+
+use hal::blocking::i2c::{Read, Write, WriteRead};
+
+use led_bargraph::{FaultyI2c, Ht16k33Emulator, TracingI2c};
+use slog::Drain;
+
+#[cfg(target_os = "linux")]
+use linux_embedded_hal::I2cdev;
+
+use args::{parse_simulate_errors, transport, Args, Transport, USAGE};
+use commands::execute;
+use logging::{open_system_log, parse_log_rotation, LogTarget, RotatingLog, RuntimeLevelFilter};
+use mock::MockI2c;
+use sources::now_millis;
+
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{atomic, Arc};
+
+fn main() {
+    let debug = Arc::new(atomic::AtomicBool::new(false));
+    let trace = Arc::new(atomic::AtomicBool::new(false));
+    let verbose = Arc::new(atomic::AtomicBool::new(false));
+
+    let args: Args = Docopt::new(USAGE)
+        .and_then(|d| d.deserialize())
+        .unwrap_or_else(|e| e.exit());
+
+    // Setup logging for the terminal (e.g. STDERR), or the system log.
+    let output: Box<dyn Drain<Ok = (), Err = slog::Never> + Send> = match args.flag_log_target {
+        LogTarget::Terminal => {
+            let decorator = slog_term::TermDecorator::new().build();
+            Box::new(slog_term::FullFormat::new(decorator).build().fuse())
+        }
+        target => open_system_log(target).unwrap_or_else(|e| {
+            docopt::Error::Argv(format!("Failed to open the {:?} log: {}", target, e)).exit()
+        }),
+    };
+
+    // Also log to the `--log-file`, if any.
+    let file: Box<dyn Drain<Ok = (), Err = slog::Never> + Send> = match args.flag_log_file {
+        Some(ref path) => {
+            let rotation = parse_log_rotation(&args.flag_log_rotate)
+                .unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+            let log = RotatingLog::open(Path::new(path), rotation, args.flag_log_keep)
+                .unwrap_or_else(|e| {
+                    docopt::Error::Argv(format!("Failed to open the log file [{}]: {}", path, e))
+                        .exit()
+                });
+
+            let decorator = slog_term::PlainSyncDecorator::new(log);
+            Box::new(slog_term::FullFormat::new(decorator).build().fuse())
+        }
+        None => Box::new(slog::Discard),
+    };
+
+    let drain = RuntimeLevelFilter {
+        drain: slog::Duplicate::new(output, file).fuse(),
+        debug: debug.clone(),
+        trace: trace.clone(),
+        verbose: verbose.clone(),
+    }
+    .fuse();
+    let drain = slog_async::Async::new(drain)
+        // It's OK to block on logging if we log too fast (e.g. `trace`).
+        .overflow_strategy(slog_async::OverflowStrategy::Block)
+        .build()
+        .fuse();
+
+    // Journald keeps the display of each record, to filter on.
+    let logger = if args.flag_log_target == LogTarget::Journald {
+        slog::Logger::root(
+            drain,
+            o!("device" => format!("{:#04x}", args.flag_i2c_address)),
+        )
+    } else {
+        slog::Logger::root(drain, o!())
+    };
+
+    // Enable debug logging if requested. If both `--debug` and `--trace` are enabled,
+    // then log level will be trace.
+    debug.store(args.flag_debug || args.flag_trace_i2c, Ordering::Relaxed);
+    trace.store(args.flag_trace, Ordering::Relaxed);
+    verbose.store(args.flag_verbose, Ordering::Relaxed);
+
+    debug!(logger, "{:?}", args);
+
+    match transport(&args) {
+        Transport::Linux => run_linux(&args, &logger),
+        Transport::Ftdi => run_ftdi(&args, &logger),
+        Transport::Mock => run_mock(&args, &logger),
+        Transport::Auto => unreachable!("The transport is resolved"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn run_linux(args: &Args, logger: &slog::Logger) {
+    info!(logger, "Instantiating linux I2C device");
+    let mut i2c_device = I2cdev::new(&args.flag_i2c_path).unwrap();
+    i2c_device
+        .set_slave_address(u16::from(args.flag_i2c_address))
+        .unwrap();
+
+    run(i2c_device, args, logger);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn run_linux(_args: &Args, _logger: &slog::Logger) {
+    panic!("The linux transport is only available on Linux");
+}
+
+// The I2C bus is on the FT232H's AD0 (SCL) and AD1 + AD2 (SDA, shorted together).
+#[cfg(feature = "ftdi")]
+fn run_ftdi(args: &Args, logger: &slog::Logger) {
+    use ftdi_embedded_hal::FtHal;
+    use libftd2xx::{Ft232h, Ftdi};
+    use std::convert::TryInto;
+
+    // The HT16K33 supports "fast mode" I2C.
+    const I2C_FREQUENCY_HZ: u32 = 400_000;
+
+    info!(logger, "Instantiating FTDI I2C device");
+    let device: Ft232h = Ftdi::new()
+        .expect("Failed to open the FTDI device")
+        .try_into()
+        .expect("The FTDI device is not an FT232H");
+    let ftdi_hal =
+        FtHal::init_freq(device, I2C_FREQUENCY_HZ).expect("Failed to initialize the FTDI device");
+    let i2c_device = ftdi_hal.i2c().expect("Failed to acquire the FTDI I2C bus");
+
+    run(i2c_device, args, logger);
+}
+
+#[cfg(not(feature = "ftdi"))]
+fn run_ftdi(_args: &Args, _logger: &slog::Logger) {
+    panic!("The ftdi transport is not available, rebuild with `--features ftdi`");
+}
+
+fn run_mock(args: &Args, logger: &slog::Logger) {
+    info!(logger, "Instantiating mock I2C device");
+    let mock_logger = logger.new(o!("mod" => "HT16K33::emulator"));
+    let mut i2c_device = MockI2c {
+        display: Ht16k33Emulator::new(mock_logger),
+        address: args.flag_i2c_address,
+        state: args.flag_mock_state.as_ref().map(PathBuf::from),
+    };
+
+    if let Some(ref path) = args.flag_mock_state {
+        info!(logger, "Loading the mock state"; "path" => path);
+        i2c_device
+            .load_state()
+            .expect("Failed to load the mock state");
+    }
+
+    run(i2c_device, args, logger);
+}
+
+// Execute the requested command against the given I2C device, failing some of the
+// I2C transactions if requested.
+fn run<I2C, E>(i2c_device: I2C, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E> + Read<Error = E>,
+    E: Debug,
+{
+    match args.flag_simulate_errors {
+        Some(ref simulate_errors) => {
+            let rate = parse_simulate_errors(simulate_errors)
+                .unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+            warn!(logger, "Simulating I2C errors"; "rate" => rate);
+
+            // Each run fails different transactions, like a real bus.
+            let seed = now_millis() as u32;
+            run_traced(FaultyI2c::with_seed(i2c_device, rate, seed), args, logger);
+        }
+        None => run_traced(i2c_device, args, logger),
+    }
+}
+
+// Execute the requested command against the given I2C device, tracing the
+// I2C transactions if requested.
+fn run_traced<I2C, E>(i2c_device: I2C, args: &Args, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E> + Read<Error = E>,
+    E: Debug,
+{
+    if args.flag_trace_i2c {
+        let i2c_logger = logger.new(o!("mod" => "i2c"));
+        execute(TracingI2c::new(i2c_device, i2c_logger), args, logger);
+    } else {
+        execute(i2c_device, args, logger);
+    }
+}
@@ -0,0 +1,141 @@
+//! The `mock` transport, an emulated display whose memory can persist between runs.
+use hal::blocking::i2c::{Read, Write, WriteRead};
+use ht16k33::i2c_mock::I2cMockError;
+use led_bargraph::Ht16k33Emulator;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::result;
+
+// The mock I2C device only emulates the display at its address, there are no sensors
+// on the bus.
+pub(crate) struct MockI2c {
+    pub(crate) display: Ht16k33Emulator,
+    pub(crate) address: u8,
+    // Where the display's registers are kept between runs, with `--mock-state`.
+    pub(crate) state: Option<PathBuf>,
+}
+
+impl MockI2c {
+    // Load the display's registers kept by a previous run, a missing state file is a
+    // display that's never been written.
+    pub(crate) fn load_state(&mut self) -> io::Result<()> {
+        let path = match self.state {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        let registers = match fs::read(path) {
+            Ok(registers) => registers,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        if registers.len() != self.display.display_ram().len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} has [{}] bytes, expected [{}]",
+                    path.display(),
+                    registers.len(),
+                    self.display.display_ram().len()
+                ),
+            ));
+        }
+
+        self.display.display_ram_mut().copy_from_slice(&registers);
+        Ok(())
+    }
+
+    // Keep the display's registers for the next run. They're replaced in one step, so
+    // a concurrent run never loads a partial state.
+    fn save_state(&self) -> io::Result<()> {
+        let path = match self.state {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let partial = path.with_extension("partial");
+        fs::write(&partial, self.display.display_ram())?;
+        fs::rename(&partial, path)
+    }
+}
+
+impl Write for MockI2c {
+    type Error = I2cMockError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> result::Result<(), I2cMockError> {
+        if address != self.address {
+            return Err(I2cMockError);
+        }
+
+        self.display.write(address, bytes)?;
+
+        // A state that can't be saved fails the write, like a real device would.
+        self.save_state().map_err(|_| I2cMockError)
+    }
+}
+
+impl WriteRead for MockI2c {
+    type Error = I2cMockError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> result::Result<(), I2cMockError> {
+        if address != self.address {
+            return Err(I2cMockError);
+        }
+
+        self.display.write_read(address, bytes, buffer)
+    }
+}
+
+impl Read for MockI2c {
+    type Error = I2cMockError;
+
+    fn read(&mut self, _address: u8, _buffer: &mut [u8]) -> result::Result<(), I2cMockError> {
+        Err(I2cMockError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn mock_state_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("led-bargraph-{}", std::process::id()))
+            .join("mock.bin");
+        let mock = |path: &Path| MockI2c {
+            display: Ht16k33Emulator::new(None),
+            address: 0x70,
+            state: Some(path.to_path_buf()),
+        };
+
+        // A missing state is a display that's never been written.
+        let mut first = mock(&path);
+        first.load_state().unwrap();
+        first.write(0x70, &[0x00, 0xAB, 0xCD]).unwrap();
+
+        let mut second = mock(&path);
+        second.load_state().unwrap();
+        let mut buffer = [0; 2];
+        second.write_read(0x70, &[0x00], &mut buffer).unwrap();
+        assert_eq!([0xAB, 0xCD], buffer);
+
+        fs::write(&path, [0x00]).unwrap();
+        assert!(mock(&path).load_state().is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
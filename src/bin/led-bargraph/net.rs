@@ -0,0 +1,315 @@
+//! Fetching over HTTP & searching IMAP mailboxes, for the `ci` & `mail` sources.
+#[cfg(feature = "network")]
+use std::io;
+use std::result;
+#[cfg(feature = "network")]
+use std::sync::Arc;
+#[cfg(feature = "network")]
+use std::time::Duration;
+
+// Fetch & parse the JSON at the URL, sending the header if given.
+pub(crate) fn fetch_json(
+    url: &str,
+    header: Option<&str>,
+) -> result::Result<serde_json::Value, String> {
+    let output = fetch(url, header)?;
+
+    serde_json::from_slice(&output).map_err(|e| e.to_string())
+}
+
+// How long fetching a URL, or each step of an IMAP search, may take before it fails.
+#[cfg(feature = "network")]
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+// The most of a response that's read, far more than any pipeline or calendar.
+#[cfg(feature = "network")]
+const FETCH_LIMIT: u64 = 16 * 1024 * 1024;
+
+// Fetch the HTTP(S) URL, sending the header if given, e.g. `PRIVATE-TOKEN: <token>`.
+// Error responses, e.g. a 404, are failures.
+#[cfg(feature = "network")]
+pub(crate) fn fetch(url: &str, header: Option<&str>) -> result::Result<Vec<u8>, String> {
+    let tls = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+    let agent = ureq::AgentBuilder::new()
+        .tls_connector(Arc::new(tls))
+        .timeout(FETCH_TIMEOUT)
+        .build();
+
+    let mut request = agent.get(url);
+    if let Some(header) = header {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid header [{}], expected `<name>: <value>`", header))?;
+        request = request.set(name.trim(), value.trim());
+    }
+
+    let reader = request.call().map_err(|e| e.to_string())?.into_reader();
+
+    let mut response = Vec::new();
+    io::Read::read_to_end(&mut io::Read::take(reader, FETCH_LIMIT), &mut response)
+        .map_err(|e| e.to_string())?;
+
+    Ok(response)
+}
+
+#[cfg(not(feature = "network"))]
+pub(crate) fn fetch(url: &str, _header: Option<&str>) -> result::Result<Vec<u8>, String> {
+    Err(format!(
+        "Can't fetch [{}], built without the `network` feature",
+        url
+    ))
+}
+
+// Whether an `imap://` or `imaps://` URL uses TLS, its host, port & mailbox, `INBOX`
+// if it's not given.
+#[cfg(feature = "network")]
+fn parse_imap_url(url: &str) -> Option<(bool, &str, u16, &str)> {
+    let (tls, rest) = match url.split_once("://")? {
+        ("imaps", rest) => (true, rest),
+        ("imap", rest) => (false, rest),
+        _ => return None,
+    };
+
+    let (address, mailbox) = rest.split_once('/').unwrap_or((rest, ""));
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (address, if tls { 993 } else { 143 }),
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+
+    Some((
+        tls,
+        host,
+        port,
+        if mailbox.is_empty() { "INBOX" } else { mailbox },
+    ))
+}
+
+// Count the messages of the mailbox at the `imap://` or `imaps://` URL matching each
+// search criteria, e.g. `UNSEEN`, logging in with the `<user>:<password>`. The
+// mailbox is opened read-only, so nothing is marked as seen.
+#[cfg(feature = "network")]
+pub(crate) fn imap_search(
+    url: &str,
+    login: &str,
+    criteria: &[String],
+) -> result::Result<Vec<usize>, String> {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let (tls, host, port, mailbox) = parse_imap_url(url).ok_or_else(|| {
+        format!(
+            "Invalid IMAP URL [{}], expected `imap[s]://<host>[:<port>]/<mailbox>`",
+            url
+        )
+    })?;
+    let (user, password) = login.split_once(':').unwrap_or((login, ""));
+
+    let address = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("Failed to resolve [{}]", host))?;
+    let stream = TcpStream::connect_timeout(&address, FETCH_TIMEOUT).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(FETCH_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+
+    if tls {
+        let connector = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+        let stream = connector.connect(host, stream).map_err(|e| e.to_string())?;
+        search_mailbox(imap::Client::new(stream), user, password, mailbox, criteria)
+    } else {
+        search_mailbox(imap::Client::new(stream), user, password, mailbox, criteria)
+    }
+}
+
+#[cfg(feature = "network")]
+fn search_mailbox<T>(
+    mut client: imap::Client<T>,
+    user: &str,
+    password: &str,
+    mailbox: &str,
+    criteria: &[String],
+) -> result::Result<Vec<usize>, String>
+where
+    T: io::Read + io::Write,
+{
+    client.read_greeting().map_err(|e| e.to_string())?;
+
+    let mut session = client
+        .login(user, password)
+        .map_err(|(e, _)| e.to_string())?;
+    session.examine(mailbox).map_err(|e| e.to_string())?;
+    let found = criteria
+        .iter()
+        .map(|criteria| session.search(criteria).map(|found| found.len()))
+        .collect::<imap::error::Result<Vec<usize>>>()
+        .map_err(|e| e.to_string())?;
+
+    // The search is done, a failure to log out doesn't matter.
+    session.logout().ok();
+
+    Ok(found)
+}
+
+#[cfg(not(feature = "network"))]
+pub(crate) fn imap_search(
+    url: &str,
+    _login: &str,
+    _criteria: &[String],
+) -> result::Result<Vec<usize>, String> {
+    Err(format!(
+        "Can't search [{}], built without the `network` feature",
+        url
+    ))
+}
+
+#[cfg(all(test, feature = "network"))]
+mod tests {
+    use super::*;
+    use led_bargraph::{Sample, Status, ValueSource};
+    use sources::MailSource;
+    use std::io::{BufRead, BufReader, Write as IoWrite};
+    use std::thread;
+
+    // Serve a single connection on a local port, answering each request with the
+    // responses of `respond`, until it returns `None`. Returns the port & the requests.
+    fn serve<F>(greeting: &'static str, mut respond: F) -> (u16, thread::JoinHandle<Vec<String>>)
+    where
+        F: FnMut(&str) -> Option<String> + Send + 'static,
+    {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            writer.write_all(greeting.as_bytes()).unwrap();
+
+            let mut requests = Vec::new();
+            for line in BufReader::new(stream).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+
+                let response = respond(&line);
+                requests.push(line);
+                match response {
+                    Some(response) => writer.write_all(response.as_bytes()).unwrap(),
+                    None => break,
+                }
+            }
+
+            requests
+        });
+
+        (port, server)
+    }
+
+    // An HTTP server answering a single request with the status & body.
+    fn serve_http(
+        status: &'static str,
+        body: &'static str,
+    ) -> (u16, thread::JoinHandle<Vec<String>>) {
+        serve("", move |line| {
+            if !line.is_empty() {
+                return Some(String::new());
+            }
+
+            Some(format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            ))
+        })
+    }
+
+    #[test]
+    fn parse_imap_url_parts() {
+        assert_eq!(
+            Some((true, "imap.example.com", 993, "INBOX")),
+            parse_imap_url("imaps://imap.example.com/INBOX")
+        );
+        assert_eq!(
+            Some((false, "localhost", 1143, "INBOX")),
+            parse_imap_url("imap://localhost:1143")
+        );
+        assert_eq!(
+            Some((false, "localhost", 143, "Lists/rust")),
+            parse_imap_url("imap://localhost/Lists/rust")
+        );
+
+        assert_eq!(None, parse_imap_url("https://imap.example.com/INBOX"));
+        assert_eq!(None, parse_imap_url("imaps://imap.example.com:port/INBOX"));
+        assert_eq!(None, parse_imap_url("imaps:///INBOX"));
+    }
+
+    #[test]
+    fn fetch_json_header() {
+        let (port, server) = serve_http("200 OK", r#"{"result":"SUCCESS"}"#);
+
+        let json = fetch_json(
+            &format!("http://127.0.0.1:{}/api/json", port),
+            Some(r#"Authorization: Bearer a"b\c"#),
+        )
+        .unwrap();
+        assert_eq!("SUCCESS", json["result"]);
+
+        let requests = server.join().unwrap();
+        assert_eq!("GET /api/json HTTP/1.1", requests[0]);
+        assert!(requests.contains(&r#"Authorization: Bearer a"b\c"#.to_string()));
+    }
+
+    #[test]
+    fn fetch_error_status() {
+        let (port, server) = serve_http("404 Not Found", "");
+
+        assert!(fetch(&format!("http://127.0.0.1:{}/missing", port), None).is_err());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn mail_source_search() {
+        let (port, server) = serve("* OK IMAP4rev1 ready\r\n", |line| {
+            let (tag, command) = line.split_once(' ')?;
+            let untagged = match command.split(' ').next()? {
+                "EXAMINE" => "* 5 EXISTS\r\n* 0 RECENT\r\n* FLAGS (\\Seen)\r\n",
+                "SEARCH" if command.contains("FROM") => "* SEARCH 4\r\n",
+                "SEARCH" => "* SEARCH 2 4 5\r\n",
+                "LOGOUT" => "* BYE\r\n",
+                _ => "",
+            };
+
+            Some(format!("{}{} OK done\r\n", untagged, tag))
+        });
+
+        let mut source = MailSource {
+            url: format!("imap://127.0.0.1:{}/INBOX", port),
+            user: Some(r#"me:pa"ss\word"#.to_string()),
+            filter: Some("FROM pager".to_string()),
+            range: 10,
+        };
+
+        assert_eq!(
+            Some(Sample::with_status(0.3, Status::Alert)),
+            source.poll().unwrap()
+        );
+
+        drop(source);
+        let requests = server.join().unwrap();
+        // The credentials are quoted, the mailbox is only examined.
+        assert_eq!(r#"a1 LOGIN "me" "pa\"ss\\word""#, requests[0]);
+        assert_eq!(r#"a2 EXAMINE "INBOX""#, requests[1]);
+        assert_eq!("a4 SEARCH UNSEEN FROM pager", requests[3]);
+    }
+}
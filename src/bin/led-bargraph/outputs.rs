@@ -0,0 +1,152 @@
+//! The pipeline & outputs of the commands' frames, the display & the terminal.
+use hal::blocking::i2c::{Write, WriteRead};
+use led_bargraph::{
+    Bargraph, ColorMap, ColorSupport, Error, Frame, Outputs, Pipeline, Render, Smooth,
+    TerminalRenderer, TerminalSupport, Threshold,
+};
+
+use args::{Args, PreviewColors, PreviewGlyphs};
+
+use std::fmt::Debug;
+use std::io;
+use std::result;
+
+// The pipeline of a command, drawing its samples against the range with the
+// transforms requested by the options.
+pub(crate) fn command_pipeline<I2C, E>(
+    bargraph: &Bargraph<I2C>,
+    range: u8,
+    colors: Option<&ColorMap>,
+    args: &Args,
+) -> Pipeline
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    let mut pipeline = bargraph.pipeline(range);
+    pipeline.set_colors(colors.cloned());
+    add_option_transforms(&mut pipeline, args);
+
+    pipeline
+}
+
+// Add the `--smoothing` & `--alert-above` transforms, in that order.
+pub(crate) fn add_option_transforms(pipeline: &mut Pipeline, args: &Args) {
+    if let Some(smoothing) = args.flag_smoothing {
+        if !(0.0..1.0).contains(&smoothing) {
+            docopt::Error::Argv(format!(
+                "Invalid smoothing [{}], expected [0.0] to below [1.0]",
+                smoothing
+            ))
+            .exit();
+        }
+
+        pipeline.add_transform(Smooth::new(smoothing));
+    }
+
+    if let Some(percent) = args.flag_alert_above {
+        pipeline.add_transform(Threshold::new(f32::from(percent) / 100.0));
+    }
+}
+
+// The outputs of a command's frames: the display, then the terminal with `--show`.
+pub(crate) fn command_outputs<'a, I2C, E>(
+    bargraph: &'a mut Bargraph<I2C>,
+    args: &Args,
+) -> Outputs<'a>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let resolution = bargraph.resolution();
+
+    let mut outputs = Outputs::new();
+    outputs.add(Hardware(bargraph));
+    add_mirror_outputs(&mut outputs, resolution, args);
+
+    outputs
+}
+
+// What the terminal showing the display supports, detected unless it's overridden by
+// `--preview-glyphs` or `--preview-colors`.
+pub(crate) fn terminal_support(args: &Args) -> Option<TerminalSupport> {
+    if args.flag_preview_glyphs == PreviewGlyphs::Auto
+        && args.flag_preview_colors == PreviewColors::Auto
+    {
+        return None;
+    }
+
+    let detected = TerminalSupport::detect();
+
+    Some(TerminalSupport {
+        unicode: match args.flag_preview_glyphs {
+            PreviewGlyphs::Auto => detected.unicode,
+            PreviewGlyphs::Unicode => true,
+            PreviewGlyphs::Ascii => false,
+        },
+        colors: match args.flag_preview_colors {
+            PreviewColors::Auto => detected.colors,
+            PreviewColors::None => ColorSupport::None,
+            PreviewColors::Basic => ColorSupport::Basic,
+            PreviewColors::Extended => ColorSupport::Extended,
+        },
+    })
+}
+
+// Add the outputs mirroring the display: the terminal with `--show`.
+pub(crate) fn add_mirror_outputs(outputs: &mut Outputs, resolution: u8, args: &Args) {
+    if args.flag_show {
+        let mut terminal = TerminalRenderer::new(resolution);
+        terminal.set_terminal(terminal_support(args));
+        outputs.add(terminal);
+    }
+}
+
+// The outputs of a polled command, like `command_outputs()`. Unchanged samples
+// aren't written to the display again, so an alert's blinking isn't restarted by
+// every poll.
+pub(crate) fn polled_outputs<'a, I2C, E>(
+    bargraph: &'a mut Bargraph<I2C>,
+    args: &Args,
+) -> Outputs<'a>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    bargraph.set_skip_unchanged(true);
+
+    command_outputs(bargraph, args)
+}
+
+// The display as one of the outputs, its errors are only logged.
+struct Hardware<'a, I2C: 'a>(&'a mut Bargraph<I2C>);
+
+impl<'a, I2C, E> Render for Hardware<'a, I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    type Error = io::Error;
+
+    fn render(&mut self, frame: &Frame) -> io::Result<()> {
+        self.0
+            .render(frame)
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+}
+
+// Display the value against the range, colored by the breakpoints if given.
+pub(crate) fn update_value<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    value: u8,
+    range: u8,
+    colors: Option<&ColorMap>,
+    show: bool,
+) -> result::Result<(), Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    match colors {
+        Some(colors) => bargraph.update_colored(value, range, colors, show),
+        None => bargraph.update(value, range, show),
+    }
+}
@@ -0,0 +1,359 @@
+//! The `rpc` command, answering JSON-RPC 2.0 requests from stdin, & the state of the
+//! device shown by it & `status`.
+use hal::blocking::i2c::{Write, WriteRead};
+use led_bargraph::{Bargraph, Error, Frame, LedColor, BARGRAPH_RESOLUTION};
+
+use std::fmt::Debug;
+use std::io::{self, BufRead, Write as IoWrite};
+use std::result;
+
+// JSON-RPC 2.0 error codes.
+const RPC_PARSE_ERROR: i64 = -32700;
+
+const RPC_INVALID_REQUEST: i64 = -32600;
+
+const RPC_METHOD_NOT_FOUND: i64 = -32601;
+
+const RPC_INVALID_PARAMS: i64 = -32602;
+
+const RPC_DEVICE_ERROR: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+    // Notifications have no id & get no response.
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetValueParams {
+    value: u8,
+    range: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetBarsParams {
+    bars: Vec<String>,
+}
+
+// Whether the device responds, & the bars read back from it, with the blink of the
+// `--if-changed` state, if any.
+pub(crate) fn device_status<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    state: Option<Frame>,
+) -> serde_json::Value
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let resolution = bargraph.resolution();
+    let (bars, error) = match bargraph.read_frame() {
+        Ok(frame) => (
+            Some(
+                frame.bars[..resolution as usize]
+                    .iter()
+                    .map(|color| color.to_string())
+                    .collect::<Vec<_>>(),
+            ),
+            None,
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    json!({
+        "connected": error.is_none(),
+        "error": error,
+        "bars": bars,
+        "blink": state.map(|frame| frame.blink),
+        "resolution": resolution,
+    })
+}
+
+// Answer each JSON-RPC request read from stdin on stdout, until stdin is closed.
+//
+// Logging goes to stderr, so stdout only has the responses.
+pub(crate) fn rpc<I2C, E>(bargraph: &mut Bargraph<I2C>, logger: &slog::Logger)
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    info!(logger, "Reading JSON-RPC requests from stdin");
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for line in stdin.lock().lines() {
+        let line = line.expect("Failed to read from stdin");
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_rpc(bargraph, &line, logger) {
+            writeln!(stdout, "{}", response)
+                .and_then(|_| stdout.flush())
+                .expect("Failed to write to stdout");
+        }
+    }
+}
+
+// Apply a single JSON-RPC request, returning the response unless it's a notification.
+fn handle_rpc<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    line: &str,
+    logger: &slog::Logger,
+) -> Option<serde_json::Value>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let request = match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(request) => request,
+        Err(e) => return Some(rpc_error(None, RPC_PARSE_ERROR, e.to_string())),
+    };
+    let request = match serde_json::from_value::<RpcRequest>(request) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        Ok(_) => {
+            return Some(rpc_error(
+                None,
+                RPC_INVALID_REQUEST,
+                "Expected JSON-RPC 2.0".to_string(),
+            ))
+        }
+        Err(e) => return Some(rpc_error(None, RPC_INVALID_REQUEST, e.to_string())),
+    };
+
+    debug!(logger, "Received a JSON-RPC request"; "method" => &request.method, "id" => ?request.id);
+
+    let result = call_rpc(bargraph, &request.method, request.params);
+    if let Err((_, ref message)) = result {
+        warn!(logger, "JSON-RPC request failed"; "method" => &request.method, "error" => message);
+    }
+
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err((code, message)) => rpc_error(Some(id), code, message),
+    })
+}
+
+// Call the method, returning its result or the error code & message.
+fn call_rpc<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    method: &str,
+    params: serde_json::Value,
+) -> result::Result<serde_json::Value, (i64, String)>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    match method {
+        "set_value" => {
+            let params: SetValueParams = rpc_params(params)?;
+            bargraph
+                .try_update(params.value, params.range, false)
+                .map_err(rpc_device_error)?;
+        }
+        "set_bars" => {
+            let params: SetBarsParams = rpc_params(params)?;
+            if params.bars.len() > BARGRAPH_RESOLUTION as usize {
+                return Err((
+                    RPC_INVALID_PARAMS,
+                    format!("Expected at most [{}] bars", BARGRAPH_RESOLUTION),
+                ));
+            }
+
+            let colors = params
+                .bars
+                .iter()
+                .map(|color| color.parse::<LedColor>())
+                .collect::<result::Result<Vec<_>, _>>()
+                .map_err(|e| (RPC_INVALID_PARAMS, e.to_string()))?;
+            bargraph.set_from_iter(colors).map_err(rpc_device_error)?;
+        }
+        "clear" => bargraph.clear().map_err(rpc_device_error)?,
+        "get_state" => {
+            let frame = bargraph.last_frame();
+            let bars = frame.map(|frame| {
+                frame.bars[..bargraph.resolution() as usize]
+                    .iter()
+                    .map(|color| color.to_string())
+                    .collect::<Vec<_>>()
+            });
+
+            return Ok(json!({
+                "bars": bars,
+                "blink": frame.map(|frame| frame.blink),
+                "brightness": bargraph.brightness(),
+                "resolution": bargraph.resolution(),
+            }));
+        }
+        _ => return Err((RPC_METHOD_NOT_FOUND, format!("Unknown method [{}]", method))),
+    }
+
+    Ok(serde_json::Value::Null)
+}
+
+fn rpc_params<T>(params: serde_json::Value) -> result::Result<T, (i64, String)>
+where
+    T: serde::de::DeserializeOwned,
+{
+    serde_json::from_value(params).map_err(|e| (RPC_INVALID_PARAMS, e.to_string()))
+}
+
+fn rpc_device_error<E: Debug>(error: Error<E>) -> (i64, String) {
+    let code = match error {
+        Error::InvalidArgument { .. } => RPC_INVALID_PARAMS,
+        _ => RPC_DEVICE_ERROR,
+    };
+
+    (code, error.to_string())
+}
+
+fn rpc_error(id: Option<serde_json::Value>, code: i64, message: String) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message},
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use led_bargraph::{FaultyI2c, BRIGHTNESS_MAX};
+
+    fn rpc_bargraph() -> Bargraph<I2cMock> {
+        Bargraph::new(I2cMock::new(None), 0, None)
+    }
+
+    fn rpc_logger() -> slog::Logger {
+        slog::Logger::root(slog::Discard, o!())
+    }
+
+    #[test]
+    fn handle_rpc_set_value() {
+        let mut bargraph = rpc_bargraph();
+
+        let response = handle_rpc(
+            &mut bargraph,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "set_value", "params": {"value": 3, "range": 6}}"#,
+            &rpc_logger(),
+        );
+
+        assert_eq!(
+            Some(json!({"jsonrpc": "2.0", "id": 1, "result": null})),
+            response
+        );
+        assert_eq!(Some(Frame::from_value(3, 6)), bargraph.last_frame());
+    }
+
+    #[test]
+    fn handle_rpc_set_bars_get_state() {
+        let mut bargraph = rpc_bargraph();
+        bargraph.set_resolution(3);
+
+        let response = handle_rpc(
+            &mut bargraph,
+            r#"{"jsonrpc": "2.0", "method": "set_bars", "params": {"bars": ["red", "g"]}}"#,
+            &rpc_logger(),
+        );
+        assert_eq!(None, response);
+
+        let response = handle_rpc(
+            &mut bargraph,
+            r#"{"jsonrpc": "2.0", "id": "state", "method": "get_state"}"#,
+            &rpc_logger(),
+        );
+        assert_eq!(
+            Some(json!({
+                "jsonrpc": "2.0",
+                "id": "state",
+                "result": {
+                    "bars": ["red", "green", "off"],
+                    "blink": false,
+                    "brightness": BRIGHTNESS_MAX,
+                    "resolution": 3,
+                },
+            })),
+            response
+        );
+    }
+
+    #[test]
+    fn device_status_state() {
+        let mut bargraph = rpc_bargraph();
+        bargraph.set_resolution(3);
+        bargraph.update(1, 3, false).unwrap();
+
+        let state = Frame {
+            blink: true,
+            ..Frame::default()
+        };
+        assert_eq!(
+            json!({
+                "connected": true,
+                "error": null,
+                "bars": ["red", "green", "green"],
+                "blink": true,
+                "resolution": 3,
+            }),
+            device_status(&mut bargraph, Some(state))
+        );
+
+        let mut broken = Bargraph::new(FaultyI2c::new(I2cMock::new(None), 1.0), 0, None);
+        let status = device_status(&mut broken, None);
+        assert_eq!(json!(false), status["connected"]);
+        assert!(status["error"].is_string());
+        assert!(status["bars"].is_null() && status["blink"].is_null());
+    }
+
+    #[test]
+    fn handle_rpc_errors() {
+        let mut bargraph = rpc_bargraph();
+        let code = |line: &str| {
+            handle_rpc(&mut rpc_bargraph(), line, &rpc_logger()).unwrap()["error"]["code"].clone()
+        };
+
+        assert_eq!(json!(RPC_PARSE_ERROR), code("{"));
+        assert_eq!(
+            json!(RPC_INVALID_REQUEST),
+            code(r#"{"id": 1, "method": "clear"}"#)
+        );
+        assert_eq!(
+            json!(RPC_METHOD_NOT_FOUND),
+            code(r#"{"jsonrpc": "2.0", "id": 1, "method": "explode"}"#)
+        );
+        assert_eq!(
+            json!(RPC_INVALID_PARAMS),
+            code(r#"{"jsonrpc": "2.0", "id": 1, "method": "set_value", "params": {"value": 3}}"#)
+        );
+        assert_eq!(
+            json!(RPC_INVALID_PARAMS),
+            code(
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "set_value", "params": {"value": 3, "range": 0}}"#
+            )
+        );
+        assert_eq!(
+            json!(RPC_INVALID_PARAMS),
+            code(
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "set_bars", "params": {"bars": ["blue"]}}"#
+            )
+        );
+
+        let response = handle_rpc(
+            &mut bargraph,
+            r#"{"jsonrpc": "2.0", "id": 7, "method": "clear"}"#,
+            &rpc_logger(),
+        );
+        assert_eq!(
+            Some(json!({"jsonrpc": "2.0", "id": 7, "result": null})),
+            response
+        );
+    }
+}
@@ -0,0 +1,1283 @@
+//! The value sources polled by the `progress`, `copy-progress`, `packages`, `ci`,
+//! `mail`, `countdown`, `daylight`, `co2`, `environment` & `meter` commands.
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use hal::blocking::i2c::{Read, Write, WriteRead};
+use led_bargraph::{
+    Bargraph, Climate, Co2Sensor, ColorMap, Electrical, EnvironmentSensor, Frame, Ina, LedColor,
+    Outputs, Pipeline, PowerMeter, Render, Sample, Scd, Status, ValueSource,
+};
+
+use args::{
+    parse_duration, parse_size, Args, Co2Model, IoDirection, MeterQuantity, PowerMonitor, Quantity,
+};
+use net::{fetch, fetch_json, imap_search};
+use outputs::{command_pipeline, polled_outputs, update_value};
+
+use std::fmt::{self, Debug};
+use std::fs;
+use std::io::{self, BufRead, Write as IoWrite};
+use std::path::PathBuf;
+use std::result;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Where `progress` reads how many bytes have been done.
+#[derive(Debug)]
+enum ProgressSource {
+    Process { pid: u32, direction: IoDirection },
+    File(PathBuf),
+}
+
+impl ProgressSource {
+    fn from_args(args: &Args) -> Self {
+        match (args.flag_pid, &args.flag_path) {
+            (Some(pid), None) => ProgressSource::Process {
+                pid,
+                direction: args.flag_io,
+            },
+            (None, Some(path)) => ProgressSource::File(PathBuf::from(path)),
+            _ => docopt::Error::Argv("Expected either `--pid` or `--path`".to_string()).exit(),
+        }
+    }
+
+    // The bytes done, an error once the process has exited.
+    fn read(&self) -> io::Result<u64> {
+        match *self {
+            ProgressSource::Process { pid, direction } => {
+                let contents = fs::read_to_string(format!("/proc/{}/io", pid))?;
+                let field = match direction {
+                    IoDirection::Read => "rchar",
+                    IoDirection::Written => "wchar",
+                };
+
+                parse_proc_io(&contents, field).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("Missing [{}]", field))
+                })
+            }
+            // The file may not have been created yet.
+            ProgressSource::File(ref path) => match fs::metadata(path) {
+                Ok(metadata) => Ok(metadata.len()),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+                Err(e) => Err(e),
+            },
+        }
+    }
+}
+
+// The bytes done by the `ProgressSource` against the total, complete once the total
+// is done or the source can no longer be read, e.g. the process has exited.
+struct Progress {
+    source: ProgressSource,
+    total: u64,
+    range: u8,
+    last: Option<f32>,
+}
+
+impl ValueSource for Progress {
+    type Error = io::Error;
+
+    fn poll(&mut self) -> io::Result<Option<Sample>> {
+        let done = match (self.source.read(), self.last) {
+            (Ok(done), _) => done,
+            (Err(_), Some(last)) => return Ok(Some(Sample::with_status(last, Status::Complete))),
+            (Err(e), None) => return Err(e),
+        };
+
+        let value = f32::from(progress_value(done, self.total, self.range)) / f32::from(self.range);
+        self.last = Some(value);
+
+        Ok(Some(if done >= self.total {
+            Sample::with_status(value, Status::Complete)
+        } else {
+            Sample::new(value)
+        }))
+    }
+}
+
+// Display the bytes done by the source against the total, until it's complete or
+// can no longer be read.
+pub(crate) fn progress<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let source = ProgressSource::from_args(args);
+    let total = args
+        .flag_total
+        .as_ref()
+        .ok_or_else(|| "Expected `--total`".to_string())
+        .and_then(|total| parse_size(total))
+        .unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+
+    info!(logger, "Following the progress"; "source" => ?source, "total" => total);
+
+    if let Err(e) = source.read() {
+        panic!("Failed to read the progress: {}", e);
+    }
+
+    let mut progress = Progress {
+        source,
+        total,
+        range: args.arg_range,
+        last: None,
+    };
+
+    let mut pipeline = command_pipeline(bargraph, args.arg_range, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut progress,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| pipeline.frame(sample),
+        logger,
+    );
+
+    info!(logger, "The progress is complete");
+}
+
+// The size of the file copied by the `ProgressSource` against the expected size,
+// complete once it's reached & the size stops changing.
+struct CopyProgress {
+    source: ProgressSource,
+    expected: u64,
+    range: u8,
+    last_size: Option<u64>,
+}
+
+impl ValueSource for CopyProgress {
+    type Error = io::Error;
+
+    fn poll(&mut self) -> io::Result<Option<Sample>> {
+        let size = self.source.read()?;
+        let value =
+            f32::from(progress_value(size, self.expected, self.range)) / f32::from(self.range);
+
+        let complete = size >= self.expected && self.last_size == Some(size);
+        self.last_size = Some(size);
+
+        Ok(Some(if complete {
+            Sample::with_status(value, Status::Complete)
+        } else {
+            Sample::new(value)
+        }))
+    }
+}
+
+// Display the percentage of the file copied on every bar, until it's complete & its
+// size stops changing, then blink green.
+pub(crate) fn copy_progress<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let source = ProgressSource::from_args(args);
+    let expected = args
+        .flag_expected_size
+        .as_ref()
+        .map(|size| parse_size(size).unwrap_or_else(|e| docopt::Error::Argv(e).exit()))
+        .expect("docopt requires `--expected-size`");
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let range = bargraph.resolution();
+
+    info!(logger, "Following the copy"; "source" => ?source, "expected" => expected);
+
+    let mut copy = CopyProgress {
+        source,
+        expected,
+        range,
+        last_size: None,
+    };
+
+    let mut pipeline = command_pipeline(bargraph, range, None, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut copy,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| pipeline.frame(sample),
+        logger,
+    );
+
+    info!(logger, "The copy is complete");
+
+    // The complete copy isn't a sample, it's displayed directly.
+    drop(outputs);
+    bargraph
+        .set_from_iter(vec![LedColor::Green; range as usize])
+        .and_then(|_| bargraph.set_blink(true))
+        .expect("Failed to display the complete copy");
+}
+
+// Poll the source every `poll` & render its samples to the outputs until it finishes,
+// returning the final sample. Each sample is processed by the pipeline, then drawn by
+// `frame`. Errors polling the source are logged & retried, e.g. a server may be
+// briefly unavailable.
+fn follow<S, F>(
+    outputs: &mut Outputs,
+    source: &mut S,
+    pipeline: &mut Pipeline,
+    poll: Duration,
+    mut frame: F,
+    logger: &slog::Logger,
+) -> Sample
+where
+    S: ValueSource,
+    S::Error: fmt::Display,
+    F: FnMut(&Pipeline, &S, &Sample) -> Frame,
+{
+    loop {
+        match source.poll() {
+            Ok(Some(sample)) => {
+                let sample = pipeline.process(sample);
+                debug!(logger, "Sample"; "sample" => ?sample);
+
+                let frame = frame(pipeline, source, &sample);
+                if let Err(e) = outputs.render(&frame) {
+                    error!(logger, "Failed to display the sample"; "error" => %e);
+                }
+
+                if sample.is_finished() {
+                    return sample;
+                }
+            }
+            Ok(None) => trace!(logger, "No new sample"),
+            Err(e) => error!(logger, "Failed to poll the source"; "error" => %e),
+        }
+
+        thread::sleep(poll);
+    }
+}
+
+// Display the progress of the package manager output on stdin, passing it through
+// to stdout, until stdin is closed.
+pub(crate) fn packages<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    info!(logger, "Reading package manager output from stdin");
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for line in stdin.lock().lines() {
+        let line = line.expect("Failed to read from stdin");
+        writeln!(stdout, "{}", line).expect("Failed to write to stdout");
+
+        let percent = match parse_package_progress(&line) {
+            Some(percent) => percent,
+            None => continue,
+        };
+
+        let value = (percent.clamp(0.0, 100.0) * f32::from(args.arg_range) / 100.0) as u8;
+        debug!(logger, "Package progress"; "percent" => percent, "value" => value);
+
+        if let Err(e) = update_value(bargraph, value, args.arg_range, colors, args.flag_show) {
+            error!(logger, "Failed to display the progress"; "error" => %e);
+        }
+    }
+}
+
+// Find the percentage complete in a line of apt or dnf output, from apt's
+// `APT::Status-Fd` (e.g. `pmstatus:vim:42.5:Installing vim`), apt's fancy progress
+// (e.g. `Progress: [ 42%]`) or dnf's transaction (e.g. `Installing : vim  3/10`).
+fn parse_package_progress(line: &str) -> Option<f32> {
+    let line = line.trim();
+
+    if line.starts_with("pmstatus:") || line.starts_with("dlstatus:") {
+        return line.split(':').nth(2)?.parse().ok();
+    }
+
+    if let Some(progress) = line.strip_prefix("Progress: [") {
+        return progress.split('%').next()?.trim().parse().ok();
+    }
+
+    // dnf only counts the packages of the transaction.
+    if line.contains(" : ") {
+        let (done, total) = line.split_whitespace().last()?.split_once('/')?;
+        let done: f32 = done.parse().ok()?;
+        let total: f32 = total.parse().ok()?;
+
+        if total > 0.0 {
+            return Some(done * 100.0 / total);
+        }
+    }
+
+    None
+}
+
+// The environment variable holding a header to authenticate with the CI server.
+const CI_HEADER_ENV: &str = "LED_BARGRAPH_CI_HEADER";
+
+// The state of a CI pipeline, `done` of `total` in the units of the server.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CiStatus {
+    done: u64,
+    total: u64,
+    finished: bool,
+    failed: bool,
+}
+
+// The progress of a CI pipeline, failed if any job or the build failed.
+struct CiSource {
+    url: String,
+    header: Option<String>,
+    range: u8,
+}
+
+impl ValueSource for CiSource {
+    type Error = String;
+
+    fn poll(&mut self) -> result::Result<Option<Sample>, String> {
+        let json = fetch_json(&self.url, self.header.as_deref())?;
+        let status = parse_ci_status(&json, now_millis())
+            .ok_or_else(|| "Expected GitLab jobs or a Jenkins build".to_string())?;
+
+        Ok(Some(match status {
+            CiStatus { failed: true, .. } => Sample::with_status(1.0, Status::Failed),
+            CiStatus { finished: true, .. } => Sample::with_status(1.0, Status::Complete),
+            CiStatus { done, total, .. } => {
+                // Only a finished pipeline is full.
+                let value = progress_value(done, total, self.range).min(self.range - 1);
+                Sample::new(f32::from(value) / f32::from(self.range))
+            }
+        }))
+    }
+}
+
+// Display the progress of the CI pipeline until it finishes, blinking red if it failed.
+pub(crate) fn ci<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let url = args
+        .flag_ci_url
+        .as_ref()
+        .expect("docopt requires `--ci-url`");
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let failed_colors = ColorMap::new(vec![(0, LedColor::Red)]);
+
+    info!(logger, "Following the CI pipeline"; "url" => url);
+
+    let mut source = CiSource {
+        url: url.to_string(),
+        header: std::env::var(CI_HEADER_ENV).ok(),
+        range: args.arg_range,
+    };
+
+    let mut pipeline = command_pipeline(bargraph, args.arg_range, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    let sample = follow(
+        &mut outputs,
+        &mut source,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| match sample.status {
+            Some(Status::Failed) => pipeline.frame_colored(sample, &failed_colors),
+            _ => pipeline.frame(sample),
+        },
+        logger,
+    );
+
+    info!(logger, "The CI pipeline finished"; "failed" => sample.status == Some(Status::Failed));
+}
+
+// The environment variable holding the `<user>:<password>` to log in to IMAP with.
+const IMAP_USER_ENV: &str = "LED_BARGRAPH_IMAP_USER";
+
+// The unread messages in an IMAP mailbox, alerting while any match the filter.
+pub(crate) struct MailSource {
+    pub(crate) url: String,
+    pub(crate) user: Option<String>,
+    pub(crate) filter: Option<String>,
+    pub(crate) range: u8,
+}
+
+impl ValueSource for MailSource {
+    type Error = String;
+
+    fn poll(&mut self) -> result::Result<Option<Sample>, String> {
+        let user = self
+            .user
+            .as_ref()
+            .ok_or_else(|| format!("Expected the `<user>:<password>` in [{}]", IMAP_USER_ENV))?;
+
+        let mut criteria = vec!["UNSEEN".to_string()];
+        criteria.extend(
+            self.filter
+                .iter()
+                .map(|filter| format!("UNSEEN {}", filter)),
+        );
+
+        let found = imap_search(&self.url, user, &criteria)?;
+        let unread = found[0];
+        let matched = found.get(1).cloned().unwrap_or(0);
+
+        let value = unread.min(usize::from(self.range)) as f32 / f32::from(self.range);
+
+        Ok(Some(if matched > 0 {
+            Sample::with_status(value, Status::Alert)
+        } else {
+            Sample::new(value)
+        }))
+    }
+}
+
+// Display the unread messages in the mailbox until interrupted, blinking while any
+// match the filter.
+pub(crate) fn mail<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let url = args
+        .flag_imap_url
+        .as_ref()
+        .expect("docopt requires `--imap-url`");
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+
+    info!(logger, "Checking the mailbox"; "url" => url);
+
+    let mut source = MailSource {
+        url: url.to_string(),
+        user: std::env::var(IMAP_USER_ENV).ok(),
+        filter: args.flag_mail_filter.clone(),
+        range: args.arg_range,
+    };
+
+    let mut pipeline = command_pipeline(bargraph, args.arg_range, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut source,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| pipeline.frame(sample),
+        logger,
+    );
+}
+
+// The final minutes before a meeting, when `countdown` blinks red.
+const COUNTDOWN_URGENT: Duration = Duration::from_secs(5 * 60);
+
+// How often `countdown` fetches the calendar again.
+const ICS_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// When the next meeting starts, either fixed by `--remaining` or the next event of
+// the calendar.
+enum Countdown {
+    Fixed(DateTime<Utc>),
+    Calendar {
+        url: String,
+        starts: Vec<DateTime<Utc>>,
+        fetched: Option<Instant>,
+    },
+}
+
+impl Countdown {
+    fn from_args(args: &Args) -> Self {
+        match (&args.flag_remaining, &args.flag_ics_url) {
+            (Some(remaining), _) => {
+                let remaining =
+                    parse_duration(remaining).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+                let remaining = chrono::Duration::from_std(remaining)
+                    .unwrap_or_else(|e| docopt::Error::Argv(e.to_string()).exit());
+
+                Countdown::Fixed(Utc::now() + remaining)
+            }
+            (None, Some(url)) => Countdown::Calendar {
+                url: url.to_string(),
+                starts: Vec::new(),
+                fetched: None,
+            },
+            (None, None) => unreachable!("docopt requires `--remaining` or `--ics-url`"),
+        }
+    }
+
+    // The start of the next meeting after `now`, fetching the calendar when it's stale.
+    // A calendar that can't be fetched keeps its previous events.
+    fn next_start(&mut self, now: DateTime<Utc>) -> result::Result<Option<DateTime<Utc>>, String> {
+        match *self {
+            Countdown::Fixed(start) => Ok(Some(start).filter(|start| *start > now)),
+            Countdown::Calendar {
+                ref url,
+                ref mut starts,
+                ref mut fetched,
+            } => {
+                let mut result = Ok(());
+                if fetched.is_none_or(|fetched| fetched.elapsed() >= ICS_REFRESH_INTERVAL) {
+                    result = fetch(url, None)
+                        .map(|ics| *starts = parse_ics_starts(&String::from_utf8_lossy(&ics)));
+                    *fetched = Some(Instant::now());
+                }
+
+                result.map(|_| starts.iter().filter(|start| **start > now).min().cloned())
+            }
+        }
+    }
+}
+
+// The time left before the next meeting, draining over the window & alerting in the
+// final minutes. Without a meeting to count down to the value is `0.0`, a fixed
+// meeting is complete once it starts.
+struct CountdownSource {
+    countdown: Countdown,
+    window: Duration,
+    resolution: u8,
+}
+
+impl ValueSource for CountdownSource {
+    type Error = String;
+
+    fn poll(&mut self) -> result::Result<Option<Sample>, String> {
+        let now = Utc::now();
+
+        let start = match (self.countdown.next_start(now)?, &self.countdown) {
+            (Some(start), _) => start,
+            (None, Countdown::Fixed(_)) => {
+                return Ok(Some(Sample::with_status(0.0, Status::Complete)))
+            }
+            (None, Countdown::Calendar { .. }) => return Ok(Some(Sample::new(0.0))),
+        };
+
+        let remaining = (start - now).to_std().unwrap_or_default();
+        let value = f32::from(remaining_bars(remaining, self.window, self.resolution))
+            / f32::from(self.resolution);
+
+        Ok(Some(if remaining <= COUNTDOWN_URGENT {
+            Sample::with_status(value, Status::Alert)
+        } else {
+            Sample::new(value)
+        }))
+    }
+}
+
+// Count down to the next meeting, until interrupted or the `--remaining` time has
+// passed, blinking red in the final minutes.
+pub(crate) fn countdown<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let window =
+        parse_duration(&args.flag_window).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let resolution = bargraph.resolution();
+    let urgent_colors = ColorMap::new(vec![(0, LedColor::Red)]);
+
+    let mut source = CountdownSource {
+        countdown: Countdown::from_args(args),
+        window,
+        resolution,
+    };
+
+    let mut pipeline = command_pipeline(bargraph, resolution, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut source,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| match sample.status {
+            // A meeting always has a bar lit, so an empty display has nothing to count down to.
+            _ if sample.value == 0.0 => Frame::default(),
+            Some(Status::Alert) => pipeline.frame_colored(sample, &urgent_colors),
+            _ => pipeline.frame(sample),
+        },
+        logger,
+    );
+
+    info!(logger, "The meeting has started");
+}
+
+// The bars of the display left before the meeting starts, draining over the window.
+// A bar stays lit until the meeting starts.
+fn remaining_bars(remaining: Duration, window: Duration, resolution: u8) -> u8 {
+    if remaining >= window {
+        return resolution;
+    }
+
+    let bars = (remaining.as_secs_f64() / window.as_secs_f64() * f64::from(resolution)).ceil();
+    bars.max(1.0) as u8
+}
+
+// The starts of the timed events of an ICS calendar. All-day events aren't meetings,
+// so are skipped.
+fn parse_ics_starts(ics: &str) -> Vec<DateTime<Utc>> {
+    // Long lines are folded onto continuation lines starting with whitespace.
+    let mut unfolded: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (
+            line.strip_prefix(|c| c == ' ' || c == '\t'),
+            unfolded.last_mut(),
+        ) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => unfolded.push(line.trim_end().to_string()),
+        }
+    }
+
+    let mut in_event = false;
+    let mut starts = Vec::new();
+    for line in &unfolded {
+        match line.as_str() {
+            "BEGIN:VEVENT" => in_event = true,
+            "END:VEVENT" => in_event = false,
+            _ if in_event && line.starts_with("DTSTART") => {
+                if let Some(start) = parse_ics_start(line) {
+                    starts.push(start);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    starts
+}
+
+// Parse a `DTSTART` property, in UTC (e.g. `DTSTART:20261015T140000Z`) or local time
+// (e.g. `DTSTART;TZID=Europe/London:20261015T140000`).
+fn parse_ics_start(line: &str) -> Option<DateTime<Utc>> {
+    let value = line.rsplit(':').next()?;
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let start = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&start));
+    }
+
+    let start = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&start)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+}
+
+// The daylight left today at the position.
+struct DaylightSource {
+    latitude: f64,
+    longitude: f64,
+    resolution: u8,
+}
+
+impl ValueSource for DaylightSource {
+    type Error = String;
+
+    fn poll(&mut self) -> result::Result<Option<Sample>, String> {
+        let (sunrise, sunset) = sun_times(Local::now().date_naive(), self.latitude, self.longitude);
+        let value = daylight_bars(Utc::now(), sunrise, sunset, self.resolution);
+
+        Ok(Some(Sample::new(
+            f32::from(value) / f32::from(self.resolution),
+        )))
+    }
+}
+
+// Display the daylight left today until interrupted.
+pub(crate) fn daylight<I2C, E>(
+    bargraph: &mut Bargraph<I2C>,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let latitude = args.flag_latitude.expect("docopt requires `--latitude`");
+    let longitude = args.flag_longitude.expect("docopt requires `--longitude`");
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        docopt::Error::Argv(format!(
+            "Invalid position [{}, {}], expected a latitude of [-90] to [90] & a longitude of [-180] to [180]",
+            latitude, longitude
+        ))
+        .exit();
+    }
+
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let resolution = bargraph.resolution();
+
+    let mut source = DaylightSource {
+        latitude,
+        longitude,
+        resolution,
+    };
+
+    let mut pipeline = command_pipeline(bargraph, resolution, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut source,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| pipeline.frame(sample),
+        logger,
+    );
+}
+
+// The bars of the display for the daylight left between sunrise & sunset, none at
+// night. A bar stays lit until sunset.
+fn daylight_bars(
+    now: DateTime<Utc>,
+    sunrise: DateTime<Utc>,
+    sunset: DateTime<Utc>,
+    resolution: u8,
+) -> u8 {
+    if now < sunrise || now >= sunset {
+        return 0;
+    }
+
+    let left = (sunset - now).num_seconds() as f64 / (sunset - sunrise).num_seconds() as f64;
+    (left * f64::from(resolution))
+        .ceil()
+        .clamp(1.0, f64::from(resolution)) as u8
+}
+
+// The sunrise & sunset of the date at the position, from the sunrise equation, see
+// https://en.wikipedia.org/wiki/Sunrise_equation. Without a sunrise (polar night)
+// both are at noon, without a sunset (midnight sun) they're 24 hours apart.
+fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> (DateTime<Utc>, DateTime<Utc>) {
+    let epoch = NaiveDate::from_ymd_opt(2000, 1, 1).expect("valid date");
+    let days = (date - epoch).num_days() as f64;
+
+    // The mean solar noon, in days since the J2000 epoch.
+    let noon = days - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let center =
+        1.9148 * anomaly.sin() + 0.0200 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let declination = (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let latitude = latitude.to_radians();
+    let hour_angle = (((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos()))
+    .clamp(-1.0, 1.0)
+    .acos()
+    .to_degrees();
+
+    // J2000 is noon UTC on 2000-01-01.
+    let at = |days: f64| {
+        Utc.timestamp_opt(946_728_000 + (days * 86_400.0).round() as i64, 0)
+            .single()
+            .expect("valid timestamp")
+    };
+
+    (
+        at(transit - hour_angle / 360.0),
+        at(transit + hour_angle / 360.0),
+    )
+}
+
+// Display the CO2 concentration until interrupted, checking for a new measurement
+// every `--poll`.
+pub(crate) fn co2<I2C, E, S>(
+    bargraph: &mut Bargraph<I2C>,
+    sensor_bus: S,
+    args: &Args,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    S: Write<Error = E> + Read<Error = E>,
+    E: Debug,
+{
+    let (yellow, red) = parse_co2_thresholds(&args.flag_co2_thresholds, args.flag_co2_max)
+        .unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let colors = ColorMap::new(vec![
+        (0, LedColor::Green),
+        (yellow, LedColor::Yellow),
+        (red, LedColor::Red),
+    ]);
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let resolution = bargraph.resolution();
+
+    let model = match args.flag_sensor {
+        Co2Model::Scd30 => Scd::Scd30,
+        Co2Model::Scd41 => Scd::Scd41,
+    };
+    let mut sensor = Co2Sensor::new(sensor_bus, model, args.flag_co2_max);
+
+    info!(logger, "Starting the CO2 sensor"; "sensor" => ?args.flag_sensor);
+    sensor.start().expect("Failed to start the CO2 sensor");
+
+    let mut pipeline = command_pipeline(bargraph, resolution, Some(&colors), args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut sensor,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| pipeline.frame(sample),
+        logger,
+    );
+}
+
+// Parse the `<yellow>,<red>` thresholds in ppm as percentages of the maximum.
+fn parse_co2_thresholds(thresholds: &str, max: u16) -> result::Result<(u8, u8), String> {
+    let invalid = || {
+        format!(
+            "Invalid CO2 thresholds [{}], expected `<yellow>,<red>` in ppm up to [{}]",
+            thresholds, max
+        )
+    };
+
+    let (yellow, red) = thresholds.split_once(',').ok_or_else(invalid)?;
+    let yellow: u16 = yellow.trim().parse().map_err(|_| invalid())?;
+    let red: u16 = red.trim().parse().map_err(|_| invalid())?;
+    if yellow > red || red > max {
+        return Err(invalid());
+    }
+
+    let percent = |ppm: u16| (u32::from(ppm) * 100 / u32::from(max)) as u8;
+    Ok((percent(yellow), percent(red)))
+}
+
+// Display the temperature or humidity until interrupted, measuring every `--poll`.
+pub(crate) fn environment<I2C, E, S>(
+    bargraph: &mut Bargraph<I2C>,
+    sensor_bus: S,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    S: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    let temperature_scale =
+        parse_scale(&args.flag_temperature_scale).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let humidity_scale =
+        parse_scale(&args.flag_humidity_scale).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let alternate = args.flag_alternate.as_ref().map(|alternate| {
+        parse_duration(alternate).unwrap_or_else(|e| docopt::Error::Argv(e).exit())
+    });
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let resolution = bargraph.resolution();
+    let humidity_colors = ColorMap::new(vec![(0, LedColor::Green)]);
+
+    let mut sensor = EnvironmentSensor::new(sensor_bus, args.flag_bme_address)
+        .expect("Failed to set up the sensor");
+    info!(logger, "Measuring the environment"; "model" => ?sensor.model());
+
+    sensor.set_climate(match args.flag_measure {
+        Quantity::Temperature => Climate::Temperature,
+        Quantity::Humidity => Climate::Humidity,
+    });
+    sensor.set_scale(
+        Climate::Temperature,
+        temperature_scale.0,
+        temperature_scale.1,
+    );
+    sensor.set_scale(Climate::Humidity, humidity_scale.0, humidity_scale.1);
+    sensor.set_alternate(alternate);
+
+    let mut pipeline = command_pipeline(bargraph, resolution, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut sensor,
+        &mut pipeline,
+        poll,
+        |pipeline, sensor, sample| match sensor.climate() {
+            // The humidity is green while alternating, to tell it from the temperature.
+            Climate::Humidity if alternate.is_some() => {
+                pipeline.frame_colored(sample, &humidity_colors)
+            }
+            _ => pipeline.frame(sample),
+        },
+        logger,
+    );
+}
+
+// Parse a `<min>,<max>` scale.
+fn parse_scale(scale: &str) -> result::Result<(f64, f64), String> {
+    let invalid = || format!("Invalid scale [{}], expected `<min>,<max>`", scale);
+
+    let (min, max) = scale.split_once(',').ok_or_else(invalid)?;
+    let min: f64 = min.trim().parse().map_err(|_| invalid())?;
+    let max: f64 = max.trim().parse().map_err(|_| invalid())?;
+    if min >= max {
+        return Err(invalid());
+    }
+
+    Ok((min, max))
+}
+
+// Display the current or power until interrupted, measuring every `--poll`.
+pub(crate) fn meter<I2C, E, S>(
+    bargraph: &mut Bargraph<I2C>,
+    sensor_bus: S,
+    args: &Args,
+    colors: Option<&ColorMap>,
+    logger: &slog::Logger,
+) where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    S: WriteRead<Error = E>,
+    E: Debug,
+{
+    let max = args.arg_max.expect("docopt requires `<max>`");
+    if max <= 0.0 || args.flag_shunt <= 0.0 {
+        docopt::Error::Argv("The maximum & shunt must be greater than [0]".to_string()).exit();
+    }
+    let poll = parse_duration(&args.flag_poll).unwrap_or_else(|e| docopt::Error::Argv(e).exit());
+    let resolution = bargraph.resolution();
+
+    info!(logger, "Measuring the power"; "monitor" => ?args.flag_ina, "max" => max);
+
+    let model = match args.flag_ina {
+        PowerMonitor::Ina219 => Ina::Ina219,
+        PowerMonitor::Ina260 => Ina::Ina260,
+    };
+    let mut meter = PowerMeter::new(
+        sensor_bus,
+        args.flag_ina_address,
+        model,
+        args.flag_shunt,
+        max,
+    );
+    meter.set_electrical(match args.flag_meter {
+        MeterQuantity::Current => Electrical::Current,
+        MeterQuantity::Power => Electrical::Power,
+    });
+
+    let mut pipeline = command_pipeline(bargraph, resolution, colors, args);
+    let mut outputs = polled_outputs(bargraph, args);
+
+    follow(
+        &mut outputs,
+        &mut meter,
+        &mut pipeline,
+        poll,
+        |pipeline, _, sample| pipeline.frame(sample),
+        logger,
+    );
+}
+
+pub(crate) fn now_millis() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Parse a GitLab pipeline's jobs, counting the finished jobs, or a Jenkins build,
+// measuring the time elapsed against the estimated duration.
+fn parse_ci_status(json: &serde_json::Value, now_millis: u64) -> Option<CiStatus> {
+    if let Some(jobs) = json.as_array() {
+        let mut status = CiStatus {
+            done: 0,
+            total: jobs.len() as u64,
+            finished: true,
+            failed: false,
+        };
+
+        for job in jobs {
+            match job.get("status")?.as_str()? {
+                "created"
+                | "pending"
+                | "preparing"
+                | "waiting_for_resource"
+                | "scheduled"
+                | "running" => status.finished = false,
+                "failed" if job.get("allow_failure") != Some(&json!(true)) => {
+                    status.done += 1;
+                    status.failed = true;
+                }
+                _ => status.done += 1,
+            }
+        }
+
+        return Some(status);
+    }
+
+    let building = json.get("building")?.as_bool()?;
+    let started = json.get("timestamp")?.as_u64()?;
+    let estimated = json.get("estimatedDuration")?.as_i64()?;
+
+    Some(CiStatus {
+        done: now_millis.saturating_sub(started),
+        // Jenkins estimates `-1` without a previous build.
+        total: estimated.max(1) as u64,
+        finished: !building,
+        failed: !building && json.get("result")?.as_str() != Some("SUCCESS"),
+    })
+}
+
+// The whole values of the range done, rounding down so it's only full once complete.
+fn progress_value(done: u64, total: u64, range: u8) -> u8 {
+    let value = u128::from(done) * u128::from(range) / u128::from(total.max(1));
+
+    value.min(u128::from(range)) as u8
+}
+
+// Find a field of `/proc/<pid>/io`, e.g. `wchar: 1024`.
+fn parse_proc_io(contents: &str, field: &str) -> Option<u64> {
+    contents.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.trim() == field {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_value_rounds_down() {
+        assert_eq!(0, progress_value(0, 100, 24));
+        assert_eq!(11, progress_value(49, 100, 24));
+        assert_eq!(23, progress_value(99, 100, 24));
+        assert_eq!(24, progress_value(100, 100, 24));
+        assert_eq!(24, progress_value(u64::MAX, 100, 24));
+    }
+
+    #[test]
+    fn parse_package_progress_formats() {
+        assert_eq!(
+            Some(42.5),
+            parse_package_progress("pmstatus:vim:42.5:Installing vim (2:9.0)")
+        );
+        assert_eq!(
+            Some(10.0),
+            parse_package_progress("dlstatus:1:10:Retrieving file 1 of 10")
+        );
+        assert_eq!(Some(42.0), parse_package_progress("Progress: [ 42%] "));
+        assert_eq!(
+            Some(30.0),
+            parse_package_progress("  Installing       : vim-enhanced-9.0-1.fc38.x86_64     3/10 ")
+        );
+        assert_eq!(
+            None,
+            parse_package_progress("Reading package lists... Done")
+        );
+        assert_eq!(None, parse_package_progress("  Verifying : vim  3/0"));
+        assert_eq!(None, parse_package_progress("pmstatus:vim:soon:Installing"));
+    }
+
+    #[test]
+    fn parse_ci_status_gitlab() {
+        let jobs = json!([
+            {"status": "success"},
+            {"status": "failed", "allow_failure": true},
+            {"status": "running"},
+            {"status": "created"},
+        ]);
+
+        assert_eq!(
+            Some(CiStatus {
+                done: 2,
+                total: 4,
+                finished: false,
+                failed: false,
+            }),
+            parse_ci_status(&jobs, 0)
+        );
+
+        let jobs = json!([{"status": "success"}, {"status": "failed"}, {"status": "skipped"}]);
+
+        assert_eq!(
+            Some(CiStatus {
+                done: 3,
+                total: 3,
+                finished: true,
+                failed: true,
+            }),
+            parse_ci_status(&jobs, 0)
+        );
+    }
+
+    #[test]
+    fn parse_ci_status_jenkins() {
+        let build = json!({
+            "building": true,
+            "timestamp": 1000,
+            "estimatedDuration": 60000,
+            "result": null,
+        });
+
+        assert_eq!(
+            Some(CiStatus {
+                done: 30000,
+                total: 60000,
+                finished: false,
+                failed: false,
+            }),
+            parse_ci_status(&build, 31000)
+        );
+
+        let build = json!({
+            "building": false,
+            "timestamp": 1000,
+            "estimatedDuration": -1,
+            "result": "FAILURE",
+        });
+
+        assert_eq!(
+            Some(CiStatus {
+                done: 30000,
+                total: 1,
+                finished: true,
+                failed: true,
+            }),
+            parse_ci_status(&build, 31000)
+        );
+
+        assert_eq!(
+            None,
+            parse_ci_status(&json!({"message": "404 Not Found"}), 0)
+        );
+    }
+
+    #[test]
+    fn remaining_bars_drains() {
+        let hour = Duration::from_secs(60 * 60);
+
+        assert_eq!(24, remaining_bars(2 * hour, hour, 24));
+        assert_eq!(24, remaining_bars(hour, hour, 24));
+        assert_eq!(12, remaining_bars(hour / 2, hour, 24));
+        assert_eq!(1, remaining_bars(Duration::from_secs(1), hour, 24));
+        assert_eq!(1, remaining_bars(Duration::from_secs(0), hour, 24));
+    }
+
+    #[test]
+    fn parse_ics_starts_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   DTSTART:20200101T000000Z\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Stand-up\r\n\
+                   DTSTART:20261015T140000Z\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;VALUE=DATE:20261016\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;TZID=Europe/London:202610\r\n \
+                   17T093000\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+        let local = Local
+            .from_local_datetime(
+                &NaiveDateTime::parse_from_str("20261017T093000", "%Y%m%dT%H%M%S").unwrap(),
+            )
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            vec![Utc.with_ymd_and_hms(2026, 10, 15, 14, 0, 0).unwrap(), local],
+            parse_ics_starts(ics)
+        );
+    }
+
+    #[test]
+    fn sun_times_london() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let (sunrise, sunset) = sun_times(date, 51.5074, -0.1278);
+
+        // 03:43 & 20:21 UTC, to within a couple of minutes.
+        let expected_sunrise = Utc.with_ymd_and_hms(2024, 6, 21, 3, 43, 0).unwrap();
+        let expected_sunset = Utc.with_ymd_and_hms(2024, 6, 21, 20, 21, 0).unwrap();
+        assert!(
+            (sunrise - expected_sunrise).num_minutes().abs() <= 2,
+            "{}",
+            sunrise
+        );
+        assert!(
+            (sunset - expected_sunset).num_minutes().abs() <= 2,
+            "{}",
+            sunset
+        );
+    }
+
+    #[test]
+    fn sun_times_polar() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+
+        let (sunrise, sunset) = sun_times(date, 80.0, 0.0);
+        assert_eq!(sunrise, sunset);
+
+        let (sunrise, sunset) = sun_times(date, -80.0, 0.0);
+        assert_eq!(24, (sunset - sunrise).num_hours());
+    }
+
+    #[test]
+    fn daylight_bars_drains() {
+        let sunrise = Utc.with_ymd_and_hms(2024, 6, 21, 6, 0, 0).unwrap();
+        let sunset = Utc.with_ymd_and_hms(2024, 6, 21, 18, 0, 0).unwrap();
+        let at = |hour| Utc.with_ymd_and_hms(2024, 6, 21, hour, 0, 0).unwrap();
+
+        assert_eq!(0, daylight_bars(at(5), sunrise, sunset, 24));
+        assert_eq!(24, daylight_bars(at(6), sunrise, sunset, 24));
+        assert_eq!(12, daylight_bars(at(12), sunrise, sunset, 24));
+        assert_eq!(0, daylight_bars(at(18), sunrise, sunset, 24));
+    }
+
+    #[test]
+    fn parse_co2_thresholds_percent() {
+        assert_eq!(Ok((50, 70)), parse_co2_thresholds("1000,1400", 2000));
+        assert_eq!(Ok((50, 70)), parse_co2_thresholds(" 1000 , 1400 ", 2000));
+
+        for invalid in ["", "1000", "1400,1000", "1000,2500", "a,b"].iter() {
+            assert!(parse_co2_thresholds(invalid, 2000).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn parse_scale_range() {
+        assert_eq!(Ok((-10.0, 40.0)), parse_scale("-10, 40"));
+        assert!(parse_scale("40,10").is_err());
+        assert!(parse_scale("40").is_err());
+    }
+
+    #[test]
+    fn parse_proc_io_fields() {
+        let contents = "rchar: 3980\nwchar: 12\nread_bytes: 0\n";
+
+        assert_eq!(Some(3980), parse_proc_io(contents, "rchar"));
+        assert_eq!(Some(12), parse_proc_io(contents, "wchar"));
+        assert_eq!(None, parse_proc_io(contents, "syscr"));
+    }
+}
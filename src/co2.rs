@@ -0,0 +1,364 @@
+//! # CO2
+//!
+//! The Sensirion SCD30 & SCD41 CO2 sensors, read over the same I2C bus as the display,
+//! e.g. through `shared-bus`, as a [ValueSource](trait.ValueSource.html).
+use hal::blocking::i2c::{Read, Write};
+
+use std::error;
+use std::fmt::{self, Debug};
+use std::thread;
+use std::time::Duration;
+
+use super::{Sample, ValueSource};
+
+// The I2C addresses of the sensors.
+const SCD30_ADDRESS: u8 = 0x61;
+const SCD4X_ADDRESS: u8 = 0x62;
+
+// How long the sensors take to execute a command before its response can be read.
+const COMMAND_DELAY: Duration = Duration::from_millis(3);
+
+// How long the SCD41 takes to stop measuring.
+const SCD41_STOP_DELAY: Duration = Duration::from_millis(500);
+
+/// The model of a [Co2Sensor](struct.Co2Sensor.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scd {
+    /// The SCD30, at address `0x61`.
+    Scd30,
+    /// The SCD41, at address `0x62`.
+    Scd41,
+}
+
+/// The error returned by a [Co2Sensor](struct.Co2Sensor.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Co2Error<E> {
+    /// An I2C transaction of the command failed.
+    I2c {
+        /// The command being sent or read.
+        command: u16,
+        /// The error returned by the I2C device.
+        source: E,
+    },
+    /// A word of the command's response didn't match its CRC.
+    Crc {
+        /// The command being read.
+        command: u16,
+    },
+}
+
+impl<E> fmt::Display for Co2Error<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Co2Error::I2c {
+                command,
+                ref source,
+            } => write!(f, "Failed to run command [{:#06x}]: {:?}", command, source),
+            Co2Error::Crc { command } => {
+                write!(
+                    f,
+                    "Invalid CRC in the response to command [{:#06x}]",
+                    command
+                )
+            }
+        }
+    }
+}
+
+impl<E> error::Error for Co2Error<E> where E: Debug {}
+
+/// An SCD30 or SCD41 CO2 sensor, measuring continuously once started. Its samples are
+/// the CO2 concentration against the maximum.
+///
+/// # Examples
+///
+/// ```
+/// extern crate embedded_hal_mock;
+/// extern crate led_bargraph;
+///
+/// use embedded_hal_mock::i2c::{Mock, Transaction};
+/// use led_bargraph::{Co2Sensor, Sample, Scd, ValueSource};
+/// # fn main() {
+///
+/// // A new measurement of 500 ppm.
+/// let i2c = Mock::new(&[
+///     Transaction::write(0x62, vec![0xE4, 0xB8]),
+///     Transaction::read(0x62, vec![0x80, 0x06, 0x04]),
+///     Transaction::write(0x62, vec![0xEC, 0x05]),
+///     Transaction::read(0x62, vec![0x01, 0xF4, 0x33, 0x66, 0x67, 0xA2, 0x5E, 0xB9, 0x3C]),
+/// ]);
+///
+/// let mut sensor = Co2Sensor::new(i2c, Scd::Scd41, 2000);
+/// assert_eq!(Ok(Some(Sample::new(0.25))), sensor.poll());
+///
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Co2Sensor<I2C> {
+    i2c: I2C,
+    model: Scd,
+    max: u16,
+}
+
+impl<I2C, E> Co2Sensor<I2C>
+where
+    I2C: Write<Error = E> + Read<Error = E>,
+{
+    /// Create a Co2Sensor, without starting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C bus of the sensor.
+    /// * `model` - The model of the sensor.
+    /// * `max` - The concentration in ppm of a full display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is `0`.
+    pub fn new(i2c: I2C, model: Scd, max: u16) -> Self {
+        assert!(max > 0, "Maximum must be at least [1]");
+
+        Co2Sensor { i2c, model, max }
+    }
+
+    /// Start measuring continuously, a new measurement is ready every 2s (SCD30) or
+    /// 5s (SCD41).
+    pub fn start(&mut self) -> Result<(), Co2Error<E>> {
+        match self.model {
+            // Continuous measurement without an ambient pressure compensation.
+            Scd::Scd30 => self.command(0x0010, &[0x0000], 0).map(|_| ()),
+            Scd::Scd41 => {
+                // The SCD41 ignores most commands while it's measuring, e.g. if it
+                // was started by a previous run.
+                self.command(0x3F86, &[], 0)?;
+                thread::sleep(SCD41_STOP_DELAY);
+
+                self.command(0x21B1, &[], 0).map(|_| ())
+            }
+        }
+    }
+
+    /// The CO2 concentration in ppm, or `None` if there isn't a new measurement yet.
+    pub fn read_ppm(&mut self) -> Result<Option<f32>, Co2Error<E>> {
+        match self.model {
+            Scd::Scd30 => {
+                if self.command(0x0202, &[], 1)?[0] != 1 {
+                    return Ok(None);
+                }
+
+                let words = self.command(0x0300, &[], 6)?;
+                Ok(Some(f32::from_bits(
+                    u32::from(words[0]) << 16 | u32::from(words[1]),
+                )))
+            }
+            Scd::Scd41 => {
+                if self.command(0xE4B8, &[], 1)?[0] & 0x07FF == 0 {
+                    return Ok(None);
+                }
+
+                // The CO2, temperature & humidity.
+                Ok(Some(f32::from(self.command(0xEC05, &[], 3)?[0])))
+            }
+        }
+    }
+
+    /// Destroy the Co2Sensor, returning its I2C bus.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    // Send the command & its arguments, then read the words of the response once
+    // the sensor has executed it.
+    fn command(
+        &mut self,
+        command: u16,
+        arguments: &[u16],
+        words: usize,
+    ) -> Result<Vec<u16>, Co2Error<E>> {
+        let address = match self.model {
+            Scd::Scd30 => SCD30_ADDRESS,
+            Scd::Scd41 => SCD4X_ADDRESS,
+        };
+        let i2c_error = |source| Co2Error::I2c { command, source };
+
+        let mut bytes = command.to_be_bytes().to_vec();
+        for argument in arguments {
+            bytes.extend_from_slice(&argument.to_be_bytes());
+            bytes.push(crc(&argument.to_be_bytes()));
+        }
+
+        self.i2c.write(address, &bytes).map_err(i2c_error)?;
+
+        if words == 0 {
+            return Ok(Vec::new());
+        }
+
+        thread::sleep(COMMAND_DELAY);
+
+        let mut buffer = vec![0; words * 3];
+        self.i2c.read(address, &mut buffer).map_err(i2c_error)?;
+
+        parse_words(&buffer).ok_or(Co2Error::Crc { command })
+    }
+}
+
+impl<I2C, E> ValueSource for Co2Sensor<I2C>
+where
+    I2C: Write<Error = E> + Read<Error = E>,
+{
+    type Error = Co2Error<E>;
+
+    fn poll(&mut self) -> Result<Option<Sample>, Self::Error> {
+        Ok(self
+            .read_ppm()?
+            .map(|ppm| Sample::new((ppm / f32::from(self.max)).min(1.0))))
+    }
+}
+
+// The CRC-8 the sensors send after each word.
+fn crc(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0xFF, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+// Parse the words of a response, each two bytes & their CRC, or `None` if a CRC
+// doesn't match.
+fn parse_words(bytes: &[u8]) -> Option<Vec<u16>> {
+    bytes
+        .chunks(3)
+        .map(|chunk| match *chunk {
+            [high, low, crc_byte] if crc(&[high, low]) == crc_byte => {
+                Some(u16::from_be_bytes([high, low]))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use embedded_hal_mock::MockError;
+
+    use std::io::ErrorKind;
+
+    // An SCD41 measurement of 500 ppm, 25°C & 37% humidity.
+    const MEASUREMENT: [u8; 9] = [0x01, 0xF4, 0x33, 0x66, 0x67, 0xA2, 0x5E, 0xB9, 0x3C];
+
+    #[test]
+    fn words() {
+        assert_eq!(0x92, crc(&[0xBE, 0xEF]));
+        assert_eq!(
+            Some(vec![0xBEEF, 0x01F4]),
+            parse_words(&[0xBE, 0xEF, 0x92, 0x01, 0xF4, 0x33])
+        );
+        assert_eq!(None, parse_words(&[0xBE, 0xEF, 0x00]));
+    }
+
+    #[test]
+    fn scd30() {
+        let i2c = I2cMock::new(&[
+            // Start measuring, with an ambient pressure of `0`.
+            I2cTransaction::write(SCD30_ADDRESS, vec![0x00, 0x10, 0x00, 0x00, 0x81]),
+            // Not ready.
+            I2cTransaction::write(SCD30_ADDRESS, vec![0x02, 0x02]),
+            I2cTransaction::read(SCD30_ADDRESS, vec![0x00, 0x00, 0x81]),
+            // Ready, with 600 ppm as a float.
+            I2cTransaction::write(SCD30_ADDRESS, vec![0x02, 0x02]),
+            I2cTransaction::read(SCD30_ADDRESS, vec![0x00, 0x01, 0xB0]),
+            I2cTransaction::write(SCD30_ADDRESS, vec![0x03, 0x00]),
+            I2cTransaction::read(
+                SCD30_ADDRESS,
+                vec![
+                    0x44, 0x16, 0x6E, 0x00, 0x00, 0x81, // CO2
+                    0x00, 0x00, 0x81, 0x00, 0x00, 0x81, // Temperature
+                    0x00, 0x00, 0x81, 0x00, 0x00, 0x81, // Humidity
+                ],
+            ),
+        ]);
+
+        let mut sensor = Co2Sensor::new(i2c, Scd::Scd30, 2000);
+        sensor.start().unwrap();
+        assert_eq!(Ok(None), sensor.poll());
+        assert_eq!(Ok(Some(Sample::new(0.3))), sensor.poll());
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn scd41() {
+        let i2c = I2cMock::new(&[
+            // Stop any measurement, then start measuring.
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0x3F, 0x86]),
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0x21, 0xB1]),
+            // Not ready.
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xE4, 0xB8]),
+            I2cTransaction::read(SCD4X_ADDRESS, vec![0x80, 0x00, 0xA2]),
+            // Ready, with 500 ppm.
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xE4, 0xB8]),
+            I2cTransaction::read(SCD4X_ADDRESS, vec![0x80, 0x06, 0x04]),
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xEC, 0x05]),
+            I2cTransaction::read(SCD4X_ADDRESS, MEASUREMENT.to_vec()),
+        ]);
+
+        let mut sensor = Co2Sensor::new(i2c, Scd::Scd41, 1000);
+        sensor.start().unwrap();
+        assert_eq!(Ok(None), sensor.poll());
+        assert_eq!(Ok(Some(Sample::new(0.5))), sensor.poll());
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn above_max() {
+        let i2c = I2cMock::new(&[
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xE4, 0xB8]),
+            I2cTransaction::read(SCD4X_ADDRESS, vec![0x80, 0x06, 0x04]),
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xEC, 0x05]),
+            I2cTransaction::read(SCD4X_ADDRESS, MEASUREMENT.to_vec()),
+        ]);
+
+        // A full display, rather than overflowing it.
+        let mut sensor = Co2Sensor::new(i2c, Scd::Scd41, 400);
+        assert_eq!(Ok(Some(Sample::new(1.0))), sensor.poll());
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn errors() {
+        let i2c = I2cMock::new(&[
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xE4, 0xB8]),
+            I2cTransaction::read(SCD4X_ADDRESS, vec![0x80, 0x06, 0x00]),
+            I2cTransaction::write(SCD4X_ADDRESS, vec![0xE4, 0xB8])
+                .with_error(MockError::Io(ErrorKind::Other)),
+        ]);
+
+        let mut sensor = Co2Sensor::new(i2c, Scd::Scd41, 2000);
+
+        let error = sensor.poll().unwrap_err();
+        assert_eq!(Co2Error::Crc { command: 0xE4B8 }, error);
+        assert_eq!(
+            "Invalid CRC in the response to command [0xe4b8]",
+            error.to_string()
+        );
+
+        match sensor.poll() {
+            Err(Co2Error::I2c { command, .. }) => assert_eq!(0xE4B8, command),
+            result => panic!("Expected an I2C error, got {:?}", result),
+        }
+
+        sensor.destroy().done();
+    }
+}
@@ -0,0 +1,179 @@
+//! # Color Map
+//!
+//! Thresholds for coloring the display by how full it is, e.g. green until 70%,
+//! yellow until 90%, then red.
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::LedColor;
+
+/// Ordered breakpoints, each a percentage (`0` - `100`) of the range & the color
+/// of the values from that percentage until the next breakpoint.
+///
+/// A ColorMap can be parsed from a comma-separated list of `<percent>:<color>`
+/// breakpoints, see [LedColor](enum.LedColor.html) for the colors.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{ColorMap, LedColor};
+/// # fn main() {
+///
+/// let colors: ColorMap = "0:green,70:yellow,90:red".parse().unwrap();
+///
+/// assert_eq!(LedColor::Green, colors.color_at(50));
+/// assert_eq!(LedColor::Yellow, colors.color_at(70));
+/// assert_eq!(LedColor::Red, colors.color_at(100));
+///
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorMap {
+    breakpoints: Vec<(u8, LedColor)>,
+}
+
+impl ColorMap {
+    /// Create a ColorMap from `(percent, color)` breakpoints, in any order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a percentage is greater than `100`.
+    pub fn new<I>(breakpoints: I) -> Self
+    where
+        I: IntoIterator<Item = (u8, LedColor)>,
+    {
+        let mut breakpoints: Vec<(u8, LedColor)> = breakpoints.into_iter().collect();
+
+        for &(percent, _) in &breakpoints {
+            assert!(
+                percent <= 100,
+                "Breakpoint [{}] must be between [0] and [100]",
+                percent
+            );
+        }
+
+        breakpoints.sort_by_key(|&(percent, _)| percent);
+
+        ColorMap { breakpoints }
+    }
+
+    /// The breakpoints, ordered by percentage.
+    pub fn breakpoints(&self) -> &[(u8, LedColor)] {
+        &self.breakpoints
+    }
+
+    /// The color at `percent` of the range, from the highest breakpoint at or below
+    /// it. Percentages below the first breakpoint are `LedColor::Off`.
+    pub fn color_at(&self, percent: u8) -> LedColor {
+        self.breakpoints
+            .iter()
+            .rev()
+            .find(|&&(breakpoint, _)| breakpoint <= percent)
+            .map_or(LedColor::Off, |&(_, color)| color)
+    }
+}
+
+/// The error returned when a [ColorMap](struct.ColorMap.html) can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseColorMapError {
+    breakpoint: String,
+}
+
+impl fmt::Display for ParseColorMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid breakpoint [{}], expected `<percent>:<color>`",
+            self.breakpoint
+        )
+    }
+}
+
+impl error::Error for ParseColorMapError {}
+
+impl FromStr for ColorMap {
+    type Err = ParseColorMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let breakpoints = s
+            .split(',')
+            .map(|breakpoint| {
+                parse_breakpoint(breakpoint.trim()).ok_or_else(|| ParseColorMapError {
+                    breakpoint: breakpoint.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ColorMap::new(breakpoints))
+    }
+}
+
+fn parse_breakpoint(breakpoint: &str) -> Option<(u8, LedColor)> {
+    let (percent, color) = breakpoint.split_once(':')?;
+
+    let percent = percent.trim().parse().ok().filter(|&p: &u8| p <= 100)?;
+    let color = color.trim().parse().ok()?;
+
+    Some((percent, color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_sorts() {
+        let colors = ColorMap::new(vec![(90, LedColor::Red), (0, LedColor::Green)]);
+
+        assert_eq!(
+            &[(0, LedColor::Green), (90, LedColor::Red)],
+            colors.breakpoints()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_percent_too_large() {
+        ColorMap::new(vec![(101, LedColor::Red)]);
+    }
+
+    #[test]
+    fn color_at() {
+        let colors = ColorMap::new(vec![(20, LedColor::Green), (80, LedColor::Red)]);
+
+        assert_eq!(LedColor::Off, colors.color_at(0));
+        assert_eq!(LedColor::Green, colors.color_at(20));
+        assert_eq!(LedColor::Green, colors.color_at(79));
+        assert_eq!(LedColor::Red, colors.color_at(80));
+        assert_eq!(LedColor::Red, colors.color_at(100));
+    }
+
+    #[test]
+    fn parse() {
+        let colors: ColorMap = " 90:Red, 0:green ,70:yellow".parse().unwrap();
+
+        assert_eq!(
+            ColorMap::new(vec![
+                (0, LedColor::Green),
+                (70, LedColor::Yellow),
+                (90, LedColor::Red),
+            ]),
+            colors
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in ["", "0", "0:blue", "101:red", "0:green,,90:red"].iter() {
+            assert!(s.parse::<ColorMap>().is_err(), "{}", s);
+        }
+
+        assert_eq!(
+            "Invalid breakpoint [0:blue], expected `<percent>:<color>`",
+            "0:blue".parse::<ColorMap>().unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! # Color Scheme
+//!
+//! The colors used to draw a value within a range.
+use super::LedColor;
+
+/// The colors of the bars of each value, see [Frame::from_value_with()](struct.Frame.html#method.from_value_with).
+///
+/// Each value is drawn with its top bar as the head & the bars below it as the fill.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{ColorScheme, Frame, LedColor};
+/// # fn main() {
+///
+/// // An all-green gauge.
+/// let scheme = ColorScheme {
+///     fill: LedColor::Green,
+///     head: LedColor::Green,
+///     ..ColorScheme::default()
+/// };
+///
+/// let frame = Frame::from_value_with(1, 12, &scheme);
+/// assert_eq!([LedColor::Green; 2], frame.bars[..2]);
+///
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorScheme {
+    /// The fill of filled values, `LedColor::Yellow` by default.
+    pub fill: LedColor,
+    /// The head of filled values, `LedColor::Red` by default.
+    pub head: LedColor,
+    /// The head of unfilled values, `LedColor::Green` by default. Their fill is off.
+    pub empty: LedColor,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme {
+            fill: LedColor::Yellow,
+            head: LedColor::Red,
+            empty: LedColor::Green,
+        }
+    }
+}
+
+/// Color the filled values by zone instead of with the fill & head of the color scheme:
+/// green below the warning threshold, yellow from it & red from the critical
+/// threshold, e.g. so that a CPU gauge turns red by itself when busy. See
+/// [Bargraph::set_thresholds()](struct.Bargraph.html#method.set_thresholds).
+///
+/// The thresholds are percentages (`0` - `100`) of the range. Without a warning
+/// threshold there's no yellow zone, & without a critical threshold no red zone.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{ColorScheme, LedColor, Thresholds};
+/// # fn main() {
+///
+/// let thresholds = Thresholds {
+///     warn: Some(70),
+///     crit: Some(90),
+/// };
+///
+/// let scheme = thresholds.scheme(20, 24, &ColorScheme::default());
+/// assert_eq!(LedColor::Yellow, scheme.fill);
+///
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thresholds {
+    /// Values from this percentage of the range are yellow, if any.
+    pub warn: Option<u8>,
+    /// Values from this percentage of the range are red, if any.
+    pub crit: Option<u8>,
+}
+
+impl Thresholds {
+    /// The color scheme drawing the value, with the fill & head in the color of its
+    /// zone & the empty values as in the scheme.
+    pub fn scheme(&self, value: u8, range: u8, scheme: &ColorScheme) -> ColorScheme {
+        let percent = u32::from(value) * 100 / u32::from(range.max(1));
+
+        let reached = |threshold: Option<u8>| threshold.is_some_and(|t| percent >= u32::from(t));
+
+        let color = if reached(self.crit) {
+            LedColor::Red
+        } else if reached(self.warn) {
+            LedColor::Yellow
+        } else {
+            LedColor::Green
+        };
+
+        ColorScheme {
+            fill: color,
+            head: color,
+            ..*scheme
+        }
+    }
+}
+
+/// The color of each bar drawn by [update()](struct.Bargraph.html#method.update), for
+/// palettes the color scheme can't express, see
+/// [Bargraph::set_palette()](struct.Bargraph.html#method.set_palette).
+///
+/// Any `Fn(u8, bool, u8) -> LedColor` closure is a Palette.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{LedColor, Palette};
+/// # fn main() {
+///
+/// // Alternating red & green filled bars.
+/// struct Stripes;
+///
+/// impl Palette for Stripes {
+///     fn color_for(&self, bar: u8, filled: bool, _range: u8) -> LedColor {
+///         match (filled, bar % 2) {
+///             (false, _) => LedColor::Off,
+///             (true, 0) => LedColor::Red,
+///             (true, _) => LedColor::Green,
+///         }
+///     }
+/// }
+///
+/// assert_eq!(LedColor::Green, Stripes.color_for(3, true, 12));
+///
+/// # }
+/// ```
+pub trait Palette {
+    /// The color of a bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `bar` - The bar, from the bottom (`0`) of the bars in use.
+    /// * `filled` - Whether the bar is within the filled values.
+    /// * `range` - Total number of values displayed.
+    fn color_for(&self, bar: u8, filled: bool, range: u8) -> LedColor;
+}
+
+impl<F> Palette for F
+where
+    F: Fn(u8, bool, u8) -> LedColor,
+{
+    fn color_for(&self, bar: u8, filled: bool, range: u8) -> LedColor {
+        self(bar, filled, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds() {
+        let thresholds = Thresholds {
+            warn: Some(50),
+            crit: Some(75),
+        };
+        let scheme = ColorScheme {
+            empty: LedColor::Off,
+            ..ColorScheme::default()
+        };
+
+        let zone = |value| thresholds.scheme(value, 12, &scheme);
+
+        assert_eq!(LedColor::Green, zone(0).fill);
+        assert_eq!(LedColor::Green, zone(5).head);
+        assert_eq!(LedColor::Yellow, zone(6).fill);
+        assert_eq!(LedColor::Yellow, zone(8).head);
+        assert_eq!(LedColor::Red, zone(9).fill);
+        assert_eq!(LedColor::Red, zone(13).head);
+        assert_eq!(LedColor::Off, zone(9).empty);
+    }
+
+    #[test]
+    fn thresholds_single_zone() {
+        let scheme = ColorScheme::default();
+
+        let warn = Thresholds {
+            warn: Some(50),
+            crit: None,
+        };
+        assert_eq!(LedColor::Green, warn.scheme(5, 12, &scheme).fill);
+        assert_eq!(LedColor::Yellow, warn.scheme(6, 12, &scheme).fill);
+        assert_eq!(LedColor::Yellow, warn.scheme(12, 12, &scheme).fill);
+
+        let crit = Thresholds {
+            warn: None,
+            crit: Some(75),
+        };
+        assert_eq!(LedColor::Green, crit.scheme(8, 12, &scheme).fill);
+        assert_eq!(LedColor::Red, crit.scheme(9, 12, &scheme).fill);
+    }
+}
@@ -0,0 +1,204 @@
+//! # Display
+//!
+//! The operations shared by every display on an `HT16K33` backpack.
+use ht16k33::Display;
+
+use std::time::Duration;
+
+/// The maximum brightness of a display, see [set_brightness()](trait.Ht16k33Display.html#tymethod.set_brightness).
+pub const BRIGHTNESS_MAX: u8 = 15;
+
+// The perceived brightness of an LED is roughly its duty cycle to the power of `1 / 2.2`.
+const GAMMA: f32 = 2.2;
+
+/// Convert a perceived brightness, from `0.0` to `100.0` percent, to the nearest of
+/// the `HT16K33`'s linear brightness steps, see
+/// [set_brightness_percent()](trait.Ht16k33Display.html#method.set_brightness_percent).
+///
+/// Equal changes of the percentage look like equal changes of brightness, so fades
+/// are smooth instead of jumping at the dim end. Percentages out of range are clamped.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate led_bargraph;
+/// # use led_bargraph::{brightness_from_percent, BRIGHTNESS_MAX};
+/// # fn main() {
+///
+/// assert_eq!(BRIGHTNESS_MAX, brightness_from_percent(100.0));
+/// assert_eq!(2, brightness_from_percent(50.0));
+///
+/// # }
+/// ```
+pub fn brightness_from_percent(percent: f32) -> u8 {
+    let percent = percent.clamp(0.0, 100.0);
+
+    // Each step adds `1 / 16` to the duty cycle, the dimmest step is `1 / 16`.
+    let steps = f32::from(BRIGHTNESS_MAX) + 1.0;
+    let duty = (percent / 100.0).powf(GAMMA);
+
+    ((duty * steps).round() - 1.0).max(0.0) as u8
+}
+
+/// How fast a display blinks, see [Bargraph::blink_rate()](struct.Bargraph.html#method.blink_rate).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlinkRate {
+    /// Not blinking, including when the display is off.
+    Off,
+    /// Blinking every 2 seconds.
+    HalfHz,
+    /// Blinking every second.
+    OneHz,
+    /// Blinking twice a second.
+    TwoHz,
+}
+
+impl BlinkRate {
+    /// The time for one on & off cycle, `None` if not blinking.
+    pub fn period(self) -> Option<Duration> {
+        match self {
+            BlinkRate::Off => None,
+            BlinkRate::HalfHz => Some(Duration::from_secs(2)),
+            BlinkRate::OneHz => Some(Duration::from_secs(1)),
+            BlinkRate::TwoHz => Some(Duration::from_millis(500)),
+        }
+    }
+}
+
+// Find the blink rate of the `HT16K33` display setup.
+pub(crate) fn blink_rate(display: Display) -> BlinkRate {
+    if display == Display::HALF_HZ {
+        BlinkRate::HalfHz
+    } else if display == Display::ONE_HZ {
+        BlinkRate::OneHz
+    } else if display == Display::TWO_HZ {
+        BlinkRate::TwoHz
+    } else {
+        BlinkRate::Off
+    }
+}
+
+/// A display driven by an `HT16K33` backpack.
+///
+/// Each display type also provides these as inherent methods, the trait allows
+/// different displays to be held & driven together.
+///
+/// # Examples
+///
+/// ```
+/// extern crate ht16k33;
+/// extern crate led_bargraph;
+///
+/// use ht16k33::i2c_mock::{I2cMock, I2cMockError};
+/// use led_bargraph::{Bargraph, Error, Ht16k33Display, SevenSegment};
+/// # fn main() {
+///
+/// let mut displays: Vec<Box<dyn Ht16k33Display<Error = Error<I2cMockError>>>> = vec![
+///     Box::new(Bargraph::new(I2cMock::new(None), 0x70, None)),
+///     Box::new(SevenSegment::new(I2cMock::new(None), 0x71, None)),
+/// ];
+///
+/// for display in displays.iter_mut() {
+///     display.initialize().unwrap();
+///     display.set_brightness(8).unwrap();
+/// }
+///
+/// # }
+/// ```
+pub trait Ht16k33Display {
+    /// The error returned by the display, see [Error](enum.Error.html).
+    type Error;
+
+    /// Initialize the display & the connected `HT16K33` device.
+    fn initialize(&mut self) -> Result<(), Self::Error>;
+
+    /// Clear the display.
+    fn clear(&mut self) -> Result<(), Self::Error>;
+
+    /// Write any pending changes to the display.
+    fn flush(&mut self) -> Result<(), Self::Error>;
+
+    /// Enable/Disable continuous blinking of the display.
+    fn set_blink(&mut self, enabled: bool) -> Result<(), Self::Error>;
+
+    /// Set the brightness of the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range.
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), Self::Error>;
+
+    /// Set the perceived brightness of the display, from `0.0` (dimmest) to `100.0`
+    /// percent, see [brightness_from_percent()](fn.brightness_from_percent.html).
+    ///
+    /// The display can't be turned off by dimming, `0.0` is the dimmest step.
+    fn set_brightness_percent(&mut self, percent: f32) -> Result<(), Self::Error> {
+        self.set_brightness(brightness_from_percent(percent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::{I2cMock, I2cMockError};
+    use {AlphaNum4, Bargraph, Error, Matrix8x8, SevenSegment};
+
+    #[test]
+    fn blink_rate_of_display() {
+        assert_eq!(BlinkRate::Off, blink_rate(Display::ON));
+        assert_eq!(BlinkRate::Off, blink_rate(Display::OFF));
+        assert_eq!(BlinkRate::HalfHz, blink_rate(Display::HALF_HZ));
+        assert_eq!(BlinkRate::OneHz, blink_rate(Display::ONE_HZ));
+        assert_eq!(BlinkRate::TwoHz, blink_rate(Display::TWO_HZ));
+    }
+
+    #[test]
+    fn blink_rate_period() {
+        assert_eq!(None, BlinkRate::Off.period());
+        assert_eq!(Some(Duration::from_secs(2)), BlinkRate::HalfHz.period());
+        assert_eq!(Some(Duration::from_millis(500)), BlinkRate::TwoHz.period());
+    }
+
+    #[test]
+    fn brightness_from_percent_curve() {
+        assert_eq!(0, brightness_from_percent(0.0));
+        assert_eq!(0, brightness_from_percent(20.0));
+        assert_eq!(2, brightness_from_percent(50.0));
+        assert_eq!(7, brightness_from_percent(75.0));
+        assert_eq!(BRIGHTNESS_MAX, brightness_from_percent(100.0));
+
+        // Out of range.
+        assert_eq!(0, brightness_from_percent(-10.0));
+        assert_eq!(BRIGHTNESS_MAX, brightness_from_percent(200.0));
+        assert_eq!(0, brightness_from_percent(f32::NAN));
+    }
+
+    #[test]
+    fn brightness_from_percent_is_monotonic() {
+        let steps: Vec<u8> = (0..=100)
+            .map(|percent| brightness_from_percent(percent as f32))
+            .collect();
+
+        assert!(steps.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn heterogeneous_displays() {
+        let mut displays: Vec<Box<dyn Ht16k33Display<Error = Error<I2cMockError>>>> = vec![
+            Box::new(Bargraph::new(I2cMock::new(None), 0, None)),
+            Box::new(SevenSegment::new(I2cMock::new(None), 0, None)),
+            Box::new(AlphaNum4::new(I2cMock::new(None), 0, None)),
+            Box::new(Matrix8x8::new(I2cMock::new(None), 0, None)),
+        ];
+
+        for display in displays.iter_mut() {
+            display.initialize().unwrap();
+            display.clear().unwrap();
+            display.set_brightness(BRIGHTNESS_MAX).unwrap();
+            display.set_brightness_percent(50.0).unwrap();
+            display.set_blink(true).unwrap();
+            display.flush().unwrap();
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! # Emulator
+//!
+//! An I2C device that models the registers of an `HT16K33`, so the commands sent to
+//! a display can be checked without the hardware.
+use hal::blocking::i2c::{Write, WriteRead};
+
+use ht16k33::i2c_mock::I2cMockError;
+use ht16k33::{Dimming, Display, Oscillator, ROWS_SIZE};
+
+use slog::Drain;
+
+// The commands, in the high nibble of the first byte written.
+const DISPLAY_DATA_ADDRESS: u8 = 0x00;
+const SYSTEM_SETUP: u8 = 0x20;
+const KEY_DATA_ADDRESS: u8 = 0x40;
+const INT_FLAG_ADDRESS: u8 = 0x60;
+const DISPLAY_SETUP: u8 = 0x80;
+const ROW_INT_SET: u8 = 0xA0;
+const DIMMING_SET: u8 = 0xE0;
+
+/// The number of bytes of key data, two for each key scan line.
+pub const KEY_DATA_SIZE: usize = 6;
+
+// The keys on each key scan line.
+const KEYS_SIZE: u8 = 13;
+
+/// Emulates an `HT16K33` on the I2C bus, decoding every command into its registers.
+///
+/// Unlike [I2cMock](../ht16k33/i2c_mock/struct.I2cMock.html), which only keeps the
+/// display RAM, the emulator also keeps the oscillator, display (on/off & blink),
+/// dimming & row/int setup, and the key data read by key scanning. Writes the chip
+/// would reject, e.g. an unknown command or data written to a read-only register,
+/// fail with [I2cMockError](../ht16k33/i2c_mock/struct.I2cMockError.html).
+///
+/// Like the `I2cMock` the address is ignored, every transaction is for the emulated
+/// chip.
+///
+/// # Examples
+///
+/// ```
+/// extern crate ht16k33;
+/// extern crate led_bargraph;
+///
+/// use ht16k33::Display;
+/// use led_bargraph::{Bargraph, Ht16k33Emulator};
+/// # fn main() {
+///
+/// let mut bargraph = Bargraph::new(Ht16k33Emulator::new(None), 0, None);
+/// bargraph.initialize().unwrap();
+/// bargraph.set_blink(true).unwrap();
+///
+/// let emulator = bargraph.destroy();
+/// assert!(emulator.is_lit());
+/// assert_eq!(Display::ONE_HZ, emulator.display());
+///
+/// # }
+/// ```
+pub struct Ht16k33Emulator {
+    display_ram: [u8; ROWS_SIZE],
+    key_data: [u8; KEY_DATA_SIZE],
+    oscillator: Oscillator,
+    display: Display,
+    dimming: Dimming,
+    row_int: u8,
+    logger: slog::Logger,
+}
+
+impl Ht16k33Emulator {
+    /// Create an Ht16k33Emulator in its power-on reset state: the oscillator & display
+    /// off, full brightness and nothing in its display RAM.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - A logging instance.
+    ///
+    /// # Notes
+    ///
+    /// `logger = None` will log to the `slog-stdlog` drain.
+    pub fn new<L>(logger: L) -> Self
+    where
+        L: Into<Option<slog::Logger>>,
+    {
+        let logger = logger
+            .into()
+            .unwrap_or_else(|| slog::Logger::root(slog_stdlog::StdLog.fuse(), o!()));
+
+        Ht16k33Emulator {
+            display_ram: [0; ROWS_SIZE],
+            key_data: [0; KEY_DATA_SIZE],
+            oscillator: Oscillator::OFF,
+            display: Display::OFF,
+            dimming: Dimming::BRIGHTNESS_MAX,
+            row_int: 0,
+            logger,
+        }
+    }
+
+    /// The display RAM, a byte for each row of LEDs.
+    pub fn display_ram(&self) -> &[u8; ROWS_SIZE] {
+        &self.display_ram
+    }
+
+    /// The display RAM, to change it without sending commands, e.g. to restore a
+    /// previous state.
+    pub fn display_ram_mut(&mut self) -> &mut [u8; ROWS_SIZE] {
+        &mut self.display_ram
+    }
+
+    /// The system oscillator, the display is only lit while it's on.
+    pub fn oscillator(&self) -> Oscillator {
+        self.oscillator
+    }
+
+    /// The display setup, whether it's on & its blink rate.
+    pub fn display(&self) -> Display {
+        self.display
+    }
+
+    /// The dimming setup, the brightness of the display.
+    pub fn dimming(&self) -> Dimming {
+        self.dimming
+    }
+
+    /// The row/int setup, the low 2 bits of the last row/int set command.
+    pub fn row_int(&self) -> u8 {
+        self.row_int
+    }
+
+    /// Whether the display is lit, i.e. both the oscillator & the display are on.
+    pub fn is_lit(&self) -> bool {
+        self.oscillator.contains(Oscillator::ON) && self.display.contains(Display::ON)
+    }
+
+    /// The key data, two bytes for each key scan line with a bit for each key.
+    pub fn key_data(&self) -> &[u8; KEY_DATA_SIZE] {
+        &self.key_data
+    }
+
+    /// Press or release a key, it stays pressed until it's released like a key that's
+    /// held down.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The key scan line, from `0` to `2`.
+    /// * `key` - The key on the line, from `0` to `12`.
+    /// * `pressed` - Whether the key is pressed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `line` or `key` is out of range.
+    pub fn set_key(&mut self, line: u8, key: u8, pressed: bool) {
+        assert!(
+            usize::from(line) < KEY_DATA_SIZE / 2,
+            "Key scan line [{}] must be less than [{}]",
+            line,
+            KEY_DATA_SIZE / 2
+        );
+        assert!(
+            key < KEYS_SIZE,
+            "Key [{}] must be less than [{}]",
+            key,
+            KEYS_SIZE
+        );
+
+        let index = usize::from(line) * 2 + usize::from(key / 8);
+        let mask = 1 << (key % 8);
+
+        if pressed {
+            self.key_data[index] |= mask;
+        } else {
+            self.key_data[index] &= !mask;
+        }
+    }
+
+    // The interrupt flag is set while any key is pressed.
+    fn int_flag(&self) -> u8 {
+        if self.key_data.iter().any(|data| *data != 0) {
+            0xFF
+        } else {
+            0x00
+        }
+    }
+}
+
+impl Write for Ht16k33Emulator {
+    type Error = I2cMockError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I2cMockError> {
+        trace!(self.logger, "write"; "address" => address, "bytes" => format!("{:02x?}", bytes));
+
+        let (command, data) = match bytes.split_first() {
+            Some((command, data)) => (*command, data),
+            None => return Err(I2cMockError),
+        };
+
+        // Only the display RAM can be written with data, which auto-increments & wraps.
+        if command & 0xF0 == DISPLAY_DATA_ADDRESS {
+            let mut offset = usize::from(command & 0x0F);
+
+            for value in data {
+                self.display_ram[offset] = *value;
+                offset = (offset + 1) % self.display_ram.len();
+            }
+
+            return Ok(());
+        }
+
+        if !data.is_empty() {
+            debug!(self.logger, "Data written to a command"; "command" => format!("{:#04x}", command));
+            return Err(I2cMockError);
+        }
+
+        match command & 0xF0 {
+            SYSTEM_SETUP => {
+                self.oscillator = Oscillator::from_bits_truncate(command & 0x01);
+            }
+            DISPLAY_SETUP => {
+                self.display = Display::from_bits_truncate(command & 0x07);
+            }
+            ROW_INT_SET => {
+                self.row_int = command & 0x03;
+            }
+            DIMMING_SET => {
+                self.dimming = Dimming::from_bits_truncate(command & 0x0F);
+            }
+            // Setting the address of the key data or interrupt flag, before reading.
+            KEY_DATA_ADDRESS | INT_FLAG_ADDRESS => {}
+            _ => {
+                debug!(self.logger, "Unknown command"; "command" => format!("{:#04x}", command));
+                return Err(I2cMockError);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl WriteRead for Ht16k33Emulator {
+    type Error = I2cMockError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), I2cMockError> {
+        trace!(self.logger, "write_read"; "address" => address, "bytes" => format!("{:02x?}", bytes));
+
+        let command = match bytes {
+            [command] => *command,
+            _ => return Err(I2cMockError),
+        };
+
+        // The display RAM & key data are read from the address, auto-incrementing &
+        // wrapping, the interrupt flag is a single byte.
+        let (registers, mut offset): (&[u8], usize) = match command {
+            0x00..=0x0F => (&self.display_ram, usize::from(command)),
+            0x40..=0x45 => (&self.key_data, usize::from(command - KEY_DATA_ADDRESS)),
+            INT_FLAG_ADDRESS => {
+                let flag = self.int_flag();
+                for value in buffer.iter_mut() {
+                    *value = flag;
+                }
+                return Ok(());
+            }
+            _ => {
+                debug!(self.logger, "Register can't be read"; "command" => format!("{:#04x}", command));
+                return Err(I2cMockError);
+            }
+        };
+
+        for value in buffer.iter_mut() {
+            *value = registers[offset];
+            offset = (offset + 1) % registers.len();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use Bargraph;
+
+    #[test]
+    fn power_on_reset() {
+        let emulator = Ht16k33Emulator::new(None);
+
+        assert_eq!(Oscillator::OFF, emulator.oscillator());
+        assert_eq!(Display::OFF, emulator.display());
+        assert_eq!(Dimming::BRIGHTNESS_MAX, emulator.dimming());
+        assert!(!emulator.is_lit());
+        assert_eq!(&[0; ROWS_SIZE], emulator.display_ram());
+    }
+
+    #[test]
+    fn commands() {
+        let mut emulator = Ht16k33Emulator::new(None);
+
+        emulator.write(0, &[0x21]).unwrap();
+        emulator.write(0, &[0x83]).unwrap();
+        emulator.write(0, &[0xE4]).unwrap();
+        emulator.write(0, &[0xA1]).unwrap();
+
+        assert_eq!(Oscillator::ON, emulator.oscillator());
+        assert_eq!(Display::TWO_HZ, emulator.display());
+        assert_eq!(Dimming::BRIGHTNESS_5_16, emulator.dimming());
+        assert_eq!(0x01, emulator.row_int());
+        assert!(emulator.is_lit());
+
+        emulator.write(0, &[0x20]).unwrap();
+        assert!(!emulator.is_lit());
+    }
+
+    #[test]
+    fn invalid_writes() {
+        let mut emulator = Ht16k33Emulator::new(None);
+
+        assert!(emulator.write(0, &[]).is_err());
+        assert!(emulator.write(0, &[0x10]).is_err());
+        assert!(emulator.write(0, &[0x81, 0x00]).is_err());
+        assert!(emulator.write(0, &[0x40, 0xFF]).is_err());
+    }
+
+    #[test]
+    fn display_ram() {
+        let mut emulator = Ht16k33Emulator::new(None);
+
+        emulator.write(0, &[0x0F, 0x01, 0x02]).unwrap();
+        assert_eq!(0x01, emulator.display_ram()[15]);
+        assert_eq!(0x02, emulator.display_ram()[0]);
+
+        let mut buffer = [0; 3];
+        emulator.write_read(0, &[0x0E], &mut buffer).unwrap();
+        assert_eq!([0x00, 0x01, 0x02], buffer);
+
+        assert!(emulator.write_read(0, &[0x81], &mut buffer).is_err());
+    }
+
+    #[test]
+    fn key_scan() {
+        let mut emulator = Ht16k33Emulator::new(None);
+        let mut flag = [0; 1];
+
+        emulator.write_read(0, &[0x60], &mut flag).unwrap();
+        assert_eq!([0x00], flag);
+
+        emulator.set_key(1, 9, true);
+        assert_eq!(&[0x00, 0x00, 0x00, 0x02, 0x00, 0x00], emulator.key_data());
+
+        let mut keys = [0; 2];
+        emulator.write_read(0, &[0x42], &mut keys).unwrap();
+        assert_eq!([0x00, 0x02], keys);
+
+        emulator.write_read(0, &[0x60], &mut flag).unwrap();
+        assert_eq!([0xFF], flag);
+
+        emulator.set_key(1, 9, false);
+        emulator.write_read(0, &[0x60], &mut flag).unwrap();
+        assert_eq!([0x00], flag);
+    }
+
+    #[test]
+    #[should_panic]
+    fn key_scan_invalid() {
+        Ht16k33Emulator::new(None).set_key(0, KEYS_SIZE, true);
+    }
+
+    #[test]
+    fn bargraph() {
+        let mut bargraph = Bargraph::new(Ht16k33Emulator::new(None), 0, None);
+        let mut mock = Bargraph::new(I2cMock::new(None), 0, None);
+
+        bargraph.initialize().unwrap();
+        mock.initialize().unwrap();
+
+        bargraph.set_brightness(4).unwrap();
+        bargraph.update(6, 12, false).unwrap();
+        mock.update(6, 12, false).unwrap();
+        bargraph.set_blink(true).unwrap();
+
+        let emulator = bargraph.destroy();
+        assert!(emulator.is_lit());
+        assert_eq!(Display::ONE_HZ, emulator.display());
+        assert_eq!(Dimming::BRIGHTNESS_5_16, emulator.dimming());
+
+        // The display RAM matches the I2cMock's.
+        assert_ne!(&[0; ROWS_SIZE], emulator.display_ram());
+        assert_eq!(&mock.destroy().data_values, emulator.display_ram());
+    }
+}
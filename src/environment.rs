@@ -0,0 +1,592 @@
+//! # Environment
+//!
+//! The Bosch BME280 & BME680 temperature & humidity sensors, read over the same I2C bus
+//! as the display, e.g. through `shared-bus`, as a [ValueSource](trait.ValueSource.html).
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::error;
+use std::fmt::{self, Debug};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Sample, ValueSource};
+
+// The registers of the sensors, which differ in their calibration & control registers
+// but share the layout of their measurements.
+const CHIP_ID_REGISTER: u8 = 0xD0;
+const BME280_CHIP_ID: u8 = 0x60;
+const BME680_CHIP_ID: u8 = 0x61;
+
+// How long a single measurement takes, without oversampling or filtering.
+const MEASUREMENT_DELAY: Duration = Duration::from_millis(50);
+
+/// The model of an [EnvironmentSensor](struct.EnvironmentSensor.html), identified by
+/// its chip ID.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bme {
+    /// The BME280, chip ID `0x60`.
+    Bme280,
+    /// The BME680, chip ID `0x61`.
+    Bme680,
+}
+
+/// What an [EnvironmentSensor](struct.EnvironmentSensor.html) displays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Climate {
+    /// The temperature in °C.
+    Temperature,
+    /// The relative humidity in %.
+    Humidity,
+}
+
+/// The error returned by an [EnvironmentSensor](struct.EnvironmentSensor.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnvironmentError<E> {
+    /// An I2C transaction with the register failed.
+    I2c {
+        /// The register being read or written.
+        register: u8,
+        /// The error returned by the I2C device.
+        source: E,
+    },
+    /// The chip isn't a BME280 or BME680.
+    UnknownChip {
+        /// The chip ID read from the sensor.
+        chip_id: u8,
+    },
+}
+
+impl<E> fmt::Display for EnvironmentError<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EnvironmentError::I2c {
+                register,
+                ref source,
+            } => write!(
+                f,
+                "Failed to access register [{:#04x}]: {:?}",
+                register, source
+            ),
+            EnvironmentError::UnknownChip { chip_id } => {
+                write!(f, "Unknown sensor with chip ID [{:#04x}]", chip_id)
+            }
+        }
+    }
+}
+
+impl<E> error::Error for EnvironmentError<E> where E: Debug {}
+
+// Temperature & humidity calibration, see the compensation formulas of the datasheets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Calibration {
+    Bme280 {
+        t: (u16, i16, i16),
+        h: (u8, i16, u8, i16, i16, i8),
+    },
+    Bme680 {
+        t: (u16, i16, i8),
+        h: (u16, u16, i8, i8, i8, u8, i8),
+    },
+}
+
+/// A BME280 or BME680 sensor, measuring the temperature & humidity on request. Its
+/// samples are the temperature or humidity as a fraction of its scale, clamped to the
+/// display.
+///
+/// # Examples
+///
+/// ```
+/// extern crate embedded_hal_mock;
+/// extern crate led_bargraph;
+///
+/// use embedded_hal_mock::i2c::{Mock, Transaction};
+/// use led_bargraph::{Bme, EnvironmentSensor, Sample, ValueSource};
+/// # fn main() {
+///
+/// // The example calibration & temperature of the BME280 datasheet, 25.08°C.
+/// let i2c = Mock::new(&[
+///     Transaction::write_read(0x76, vec![0xD0], vec![0x60]),
+///     Transaction::write_read(0x76, vec![0x88], vec![0x70, 0x6B, 0x43, 0x67, 0x18, 0xFC]),
+///     Transaction::write_read(0x76, vec![0xA1], vec![0x4B]),
+///     Transaction::write_read(0x76, vec![0xE1], vec![0; 7]),
+///     Transaction::write(0x76, vec![0xF2, 0x01]),
+///     Transaction::write(0x76, vec![0xF4, 0x25]),
+///     Transaction::write_read(0x76, vec![0xF7], vec![0, 0, 0, 0x7E, 0xED, 0x00, 0, 0]),
+/// ]);
+///
+/// let mut sensor = EnvironmentSensor::new(i2c, 0x76).unwrap();
+/// assert_eq!(Bme::Bme280, sensor.model());
+///
+/// // From 10°C to 35°C.
+/// let sample = sensor.poll().unwrap().unwrap();
+/// assert!((sample.value - 0.6033).abs() < 0.001);
+///
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EnvironmentSensor<I2C> {
+    i2c: I2C,
+    address: u8,
+    calibration: Calibration,
+    climate: Climate,
+    alternate: Option<Duration>,
+    shown_since: Instant,
+    temperature_scale: (f64, f64),
+    humidity_scale: (f64, f64),
+}
+
+impl<I2C, E> EnvironmentSensor<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Create an EnvironmentSensor, identifying the sensor & reading its calibration.
+    ///
+    /// The samples are the temperature from 10°C to 35°C, see
+    /// [set_climate()](#method.set_climate) & [set_scale()](#method.set_scale).
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C bus of the sensor.
+    /// * `address` - The address of the sensor, `0x76` or `0x77`.
+    pub fn new(mut i2c: I2C, address: u8) -> Result<Self, EnvironmentError<E>> {
+        let mut read = |register: u8, buffer: &mut [u8]| {
+            i2c.write_read(address, &[register], buffer)
+                .map_err(|source| EnvironmentError::I2c { register, source })
+        };
+
+        let mut chip_id = [0];
+        read(CHIP_ID_REGISTER, &mut chip_id)?;
+
+        let calibration = match chip_id[0] {
+            BME280_CHIP_ID => {
+                let mut t = [0; 6];
+                let mut h1 = [0];
+                let mut h = [0; 7];
+                read(0x88, &mut t)?;
+                read(0xA1, &mut h1)?;
+                read(0xE1, &mut h)?;
+
+                parse_bme280_calibration(&t, h1[0], &h)
+            }
+            BME680_CHIP_ID => {
+                let mut t1 = [0; 2];
+                let mut t = [0; 3];
+                let mut h = [0; 9];
+                read(0xE9, &mut t1)?;
+                read(0x8A, &mut t)?;
+                read(0xE1, &mut h)?;
+
+                parse_bme680_calibration(&t1, &t, &h)
+            }
+            chip_id => return Err(EnvironmentError::UnknownChip { chip_id }),
+        };
+
+        Ok(EnvironmentSensor {
+            i2c,
+            address,
+            calibration,
+            climate: Climate::Temperature,
+            alternate: None,
+            shown_since: Instant::now(),
+            temperature_scale: (10.0, 35.0),
+            humidity_scale: (0.0, 100.0),
+        })
+    }
+
+    /// The model of the sensor.
+    pub fn model(&self) -> Bme {
+        match self.calibration {
+            Calibration::Bme280 { .. } => Bme::Bme280,
+            Calibration::Bme680 { .. } => Bme::Bme680,
+        }
+    }
+
+    /// What the samples are, which changes while alternating, see
+    /// [set_alternate()](#method.set_alternate).
+    pub fn climate(&self) -> Climate {
+        self.climate
+    }
+
+    /// Set what the samples are, the temperature by default.
+    pub fn set_climate(&mut self, climate: Climate) {
+        self.climate = climate;
+        self.shown_since = Instant::now();
+    }
+
+    /// Alternate between the temperature & humidity, sampling each for the duration,
+    /// or `None` to stop alternating.
+    pub fn set_alternate(&mut self, alternate: Option<Duration>) {
+        self.alternate = alternate;
+        self.shown_since = Instant::now();
+    }
+
+    /// Set the temperature in °C, or relative humidity in %, of an empty & a full
+    /// display, by default 10°C to 35°C & 0% to 100%.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` isn't less than `max`.
+    pub fn set_scale(&mut self, climate: Climate, min: f64, max: f64) {
+        assert!(min < max, "Minimum [{}] must be less than [{}]", min, max);
+
+        match climate {
+            Climate::Temperature => self.temperature_scale = (min, max),
+            Climate::Humidity => self.humidity_scale = (min, max),
+        }
+    }
+
+    /// Measure the temperature in °C & the relative humidity in %, without
+    /// oversampling or filtering.
+    pub fn measure(&mut self) -> Result<(f64, f64), EnvironmentError<E>> {
+        // The humidity control only applies once the measurement control is written,
+        // which requests a single ("forced") measurement.
+        let (ctrl_hum, ctrl_meas, data) = match self.calibration {
+            Calibration::Bme280 { .. } => (0xF2, 0xF4, 0xF7),
+            Calibration::Bme680 { .. } => (0x72, 0x74, 0x1F),
+        };
+        self.write(&[ctrl_hum, 0x01])?;
+        self.write(&[ctrl_meas, 0x25])?;
+
+        thread::sleep(MEASUREMENT_DELAY);
+
+        let mut measurement = [0; 8];
+        self.read(data, &mut measurement)?;
+
+        let adc_t = u32::from(measurement[3]) << 12
+            | u32::from(measurement[4]) << 4
+            | u32::from(measurement[5]) >> 4;
+        let adc_h = u32::from(measurement[6]) << 8 | u32::from(measurement[7]);
+
+        Ok(compensate(&self.calibration, adc_t, adc_h))
+    }
+
+    /// Destroy the EnvironmentSensor, returning its I2C bus.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    fn read(&mut self, register: u8, buffer: &mut [u8]) -> Result<(), EnvironmentError<E>> {
+        self.i2c
+            .write_read(self.address, &[register], buffer)
+            .map_err(|source| EnvironmentError::I2c { register, source })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EnvironmentError<E>> {
+        self.i2c
+            .write(self.address, bytes)
+            .map_err(|source| EnvironmentError::I2c {
+                register: bytes[0],
+                source,
+            })
+    }
+}
+
+impl<I2C, E> ValueSource for EnvironmentSensor<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = EnvironmentError<E>;
+
+    fn poll(&mut self) -> Result<Option<Sample>, Self::Error> {
+        if let Some(alternate) = self.alternate {
+            if self.shown_since.elapsed() >= alternate {
+                self.climate = match self.climate {
+                    Climate::Temperature => Climate::Humidity,
+                    Climate::Humidity => Climate::Temperature,
+                };
+                self.shown_since = Instant::now();
+            }
+        }
+
+        let (temperature, humidity) = self.measure()?;
+
+        Ok(Some(Sample::new(match self.climate {
+            Climate::Temperature => scale_reading(temperature, self.temperature_scale),
+            Climate::Humidity => scale_reading(humidity, self.humidity_scale),
+        })))
+    }
+}
+
+fn parse_bme280_calibration(t: &[u8; 6], h1: u8, h: &[u8; 7]) -> Calibration {
+    Calibration::Bme280 {
+        t: (
+            u16::from_le_bytes([t[0], t[1]]),
+            i16::from_le_bytes([t[2], t[3]]),
+            i16::from_le_bytes([t[4], t[5]]),
+        ),
+        h: (
+            h1,
+            i16::from_le_bytes([h[0], h[1]]),
+            h[2],
+            // The 12-bit H4 & H5 share a byte.
+            i16::from(h[3] as i8) << 4 | i16::from(h[4] & 0x0F),
+            i16::from(h[5] as i8) << 4 | i16::from(h[4] >> 4),
+            h[6] as i8,
+        ),
+    }
+}
+
+fn parse_bme680_calibration(t1: &[u8; 2], t: &[u8; 3], h: &[u8; 9]) -> Calibration {
+    Calibration::Bme680 {
+        t: (
+            u16::from_le_bytes(*t1),
+            i16::from_le_bytes([t[0], t[1]]),
+            t[2] as i8,
+        ),
+        h: (
+            // The 12-bit H1 & H2 share a byte.
+            u16::from(h[2]) << 4 | u16::from(h[1] & 0x0F),
+            u16::from(h[0]) << 4 | u16::from(h[1] >> 4),
+            h[3] as i8,
+            h[4] as i8,
+            h[5] as i8,
+            h[6],
+            h[7] as i8,
+        ),
+    }
+}
+
+// The temperature in °C & relative humidity in % of the raw measurements, with the
+// floating point compensation formulas of the datasheets.
+fn compensate(calibration: &Calibration, adc_t: u32, adc_h: u32) -> (f64, f64) {
+    let (adc_t, adc_h) = (f64::from(adc_t), f64::from(adc_h));
+
+    let (temperature, humidity) = match *calibration {
+        Calibration::Bme280 {
+            t: (t1, t2, t3),
+            h: (h1, h2, h3, h4, h5, h6),
+        } => {
+            let (t1, t2, t3) = (f64::from(t1), f64::from(t2), f64::from(t3));
+            let var1 = (adc_t / 16384.0 - t1 / 1024.0) * t2;
+            let var2 = (adc_t / 131072.0 - t1 / 8192.0).powi(2) * t3;
+            let t_fine = var1 + var2;
+
+            let var_h = t_fine - 76800.0;
+            let var_h = (adc_h - (f64::from(h4) * 64.0 + f64::from(h5) / 16384.0 * var_h))
+                * (f64::from(h2) / 65536.0
+                    * (1.0
+                        + f64::from(h6) / 67108864.0
+                            * var_h
+                            * (1.0 + f64::from(h3) / 67108864.0 * var_h)));
+            let humidity = var_h * (1.0 - f64::from(h1) * var_h / 524288.0);
+
+            (t_fine / 5120.0, humidity)
+        }
+        Calibration::Bme680 {
+            t: (t1, t2, t3),
+            h: (h1, h2, h3, h4, h5, h6, h7),
+        } => {
+            let (t1, t2, t3) = (f64::from(t1), f64::from(t2), f64::from(t3));
+            let var1 = (adc_t / 16384.0 - t1 / 1024.0) * t2;
+            let var2 = (adc_t / 131072.0 - t1 / 8192.0).powi(2) * (t3 * 16.0);
+            let temperature = (var1 + var2) / 5120.0;
+
+            let var1 = adc_h - (f64::from(h1) * 16.0 + f64::from(h3) / 2.0 * temperature);
+            let var2 = var1
+                * (f64::from(h2) / 262144.0
+                    * (1.0
+                        + f64::from(h4) / 16384.0 * temperature
+                        + f64::from(h5) / 1048576.0 * temperature * temperature));
+            let var3 = f64::from(h6) / 16384.0;
+            let var4 = f64::from(h7) / 2097152.0;
+            let humidity = var2 + (var3 + var4 * temperature) * var2 * var2;
+
+            (temperature, humidity)
+        }
+    };
+
+    (temperature, humidity.clamp(0.0, 100.0))
+}
+
+// The fraction of the scale for the reading, clamped to the display.
+fn scale_reading(reading: f64, (min, max): (f64, f64)) -> f32 {
+    ((reading - min) / (max - min)).clamp(0.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use embedded_hal_mock::MockError;
+
+    use std::io::ErrorKind;
+
+    const ADDRESS: u8 = 0x76;
+
+    // The example calibration of the BME280 datasheet, with the humidity calibration
+    // of a BME280 breakout.
+    const BME280_T: [u8; 6] = [0x70, 0x6B, 0x43, 0x67, 0x18, 0xFC];
+    const BME280_H: [u8; 7] = [0x6A, 0x01, 0x00, 0x13, 0x29, 0x03, 0x1E];
+
+    // The measurement of the datasheet's example temperature, 25.08°C.
+    fn measurement(adc_h: u16) -> Vec<u8> {
+        let [high, low] = adc_h.to_be_bytes();
+        vec![0, 0, 0, 0x7E, 0xED, 0x00, high, low]
+    }
+
+    fn bme280_mock(measurements: &[u16]) -> I2cMock {
+        let mut transactions = vec![
+            I2cTransaction::write_read(ADDRESS, vec![0xD0], vec![0x60]),
+            I2cTransaction::write_read(ADDRESS, vec![0x88], BME280_T.to_vec()),
+            I2cTransaction::write_read(ADDRESS, vec![0xA1], vec![75]),
+            I2cTransaction::write_read(ADDRESS, vec![0xE1], BME280_H.to_vec()),
+        ];
+        for &adc_h in measurements {
+            transactions.extend_from_slice(&[
+                I2cTransaction::write(ADDRESS, vec![0xF2, 0x01]),
+                I2cTransaction::write(ADDRESS, vec![0xF4, 0x25]),
+                I2cTransaction::write_read(ADDRESS, vec![0xF7], measurement(adc_h)),
+            ]);
+        }
+
+        I2cMock::new(&transactions)
+    }
+
+    fn assert_near(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 0.01,
+            "{} != {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn bme280_calibration() {
+        assert_eq!(
+            Calibration::Bme280 {
+                t: (27504, 26435, -1000),
+                h: (75, 362, 0, 313, 50, 30),
+            },
+            parse_bme280_calibration(&BME280_T, 75, &BME280_H)
+        );
+
+        // Both 12-bit values are negative.
+        match parse_bme280_calibration(&BME280_T, 75, &[0, 0, 0, 0xFF, 0xEF, 0xFF, 0]) {
+            Calibration::Bme280 { h, .. } => assert_eq!((-1, -2), (h.3, h.4)),
+            calibration => panic!("Expected a BME280, got {:?}", calibration),
+        }
+    }
+
+    #[test]
+    fn bme680_calibration() {
+        assert_eq!(
+            Calibration::Bme680 {
+                t: (27504, 26435, -62),
+                h: (0x2FE, 0x3F2, 0, 45, 20, 120, -100),
+            },
+            parse_bme680_calibration(
+                &[0x70, 0x6B],
+                &[0x43, 0x67, 0xC2],
+                &[0x3F, 0x2E, 0x2F, 0x00, 0x2D, 0x14, 0x78, 0x9C, 0x00],
+            )
+        );
+    }
+
+    #[test]
+    fn bme280() {
+        let mut sensor = EnvironmentSensor::new(bme280_mock(&[0x7000]), ADDRESS).unwrap();
+        assert_eq!(Bme::Bme280, sensor.model());
+
+        let (temperature, humidity) = sensor.measure().unwrap();
+        assert_near(25.08, temperature);
+        assert_near(47.61, humidity);
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn bme680() {
+        let i2c = I2cMock::new(&[
+            I2cTransaction::write_read(ADDRESS, vec![0xD0], vec![0x61]),
+            I2cTransaction::write_read(ADDRESS, vec![0xE9], vec![0x70, 0x6B]),
+            I2cTransaction::write_read(ADDRESS, vec![0x8A], vec![0x43, 0x67, 0xC2]),
+            I2cTransaction::write_read(
+                ADDRESS,
+                vec![0xE1],
+                vec![0x3F, 0x2E, 0x2F, 0x00, 0x2D, 0x14, 0x78, 0x9C, 0x00],
+            ),
+            I2cTransaction::write(ADDRESS, vec![0x72, 0x01]),
+            I2cTransaction::write(ADDRESS, vec![0x74, 0x25]),
+            I2cTransaction::write_read(ADDRESS, vec![0x1F], measurement(0x5000)),
+        ]);
+
+        let mut sensor = EnvironmentSensor::new(i2c, ADDRESS).unwrap();
+        assert_eq!(Bme::Bme680, sensor.model());
+
+        let (temperature, humidity) = sensor.measure().unwrap();
+        assert_near(25.08, temperature);
+        assert_near(41.44, humidity);
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn humidity_clamped() {
+        let mut sensor = EnvironmentSensor::new(bme280_mock(&[0xFFFF, 0]), ADDRESS).unwrap();
+
+        assert_eq!(100.0, sensor.measure().unwrap().1);
+        assert_eq!(0.0, sensor.measure().unwrap().1);
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn poll() {
+        let mut sensor = EnvironmentSensor::new(bme280_mock(&[0x7000; 3]), ADDRESS).unwrap();
+
+        // 25.08°C from 10°C to 35°C.
+        let sample = sensor.poll().unwrap().unwrap();
+        assert!((sample.value - 0.6033).abs() < 0.001, "{:?}", sample);
+
+        // The scale is clamped to the display.
+        sensor.set_scale(Climate::Temperature, 0.0, 20.0);
+        assert_eq!(Some(Sample::new(1.0)), sensor.poll().unwrap());
+
+        sensor.set_climate(Climate::Humidity);
+        let sample = sensor.poll().unwrap().unwrap();
+        assert!((sample.value - 0.4761).abs() < 0.001, "{:?}", sample);
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn alternate() {
+        let mut sensor = EnvironmentSensor::new(bme280_mock(&[0x7000; 2]), ADDRESS).unwrap();
+        sensor.set_alternate(Some(Duration::from_millis(0)));
+
+        sensor.poll().unwrap();
+        assert_eq!(Climate::Humidity, sensor.climate());
+        sensor.poll().unwrap();
+        assert_eq!(Climate::Temperature, sensor.climate());
+
+        sensor.destroy().done();
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_scale_empty() {
+        let mut sensor = EnvironmentSensor::new(bme280_mock(&[]), ADDRESS).unwrap();
+        sensor.set_scale(Climate::Humidity, 50.0, 50.0);
+    }
+
+    #[test]
+    fn errors() {
+        let i2c = I2cMock::new(&[I2cTransaction::write_read(ADDRESS, vec![0xD0], vec![0x58])]);
+        match EnvironmentSensor::new(i2c, ADDRESS) {
+            Err(error) => assert_eq!("Unknown sensor with chip ID [0x58]", error.to_string()),
+            Ok(_) => panic!("Expected an unknown chip"),
+        }
+
+        let i2c = I2cMock::new(&[I2cTransaction::write_read(ADDRESS, vec![0xD0], vec![0])
+            .with_error(MockError::Io(ErrorKind::Other))]);
+        match EnvironmentSensor::new(i2c, ADDRESS) {
+            Err(EnvironmentError::I2c { register, .. }) => assert_eq!(0xD0, register),
+            Err(error) => panic!("Expected an I2C error, got {:?}", error),
+            Ok(_) => panic!("Expected an I2C error"),
+        }
+    }
+}
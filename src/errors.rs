@@ -0,0 +1,251 @@
+//! # Error
+//!
+//! The errors returned by the displays, with enough context to tell which
+//! operation on which device register failed.
+use std::error;
+use std::fmt;
+
+/// The operation on the `HT16K33` device that failed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    /// Initializing the device & turning on the display.
+    Initialize,
+    /// Writing the display buffer.
+    WriteDisplayBuffer,
+    /// Reading back the display buffer.
+    ReadDisplayBuffer,
+    /// Setting the blink rate.
+    SetBlink,
+    /// Setting the brightness.
+    SetBrightness,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operation = match *self {
+            Operation::Initialize => "initialize",
+            Operation::WriteDisplayBuffer => "write display buffer",
+            Operation::ReadDisplayBuffer => "read display buffer",
+            Operation::SetBlink => "set blink",
+            Operation::SetBrightness => "set brightness",
+        };
+
+        write!(f, "{}", operation)
+    }
+}
+
+/// The error returned by the displays, wrapping the error of the I2C device.
+///
+/// # Examples
+///
+/// ```
+/// extern crate ht16k33;
+/// extern crate led_bargraph;
+///
+/// use ht16k33::i2c_mock::I2cMockError;
+/// use led_bargraph::{Error, Operation};
+/// # fn main() {
+///
+/// let error = Error::I2c {
+///     operation: Operation::SetBlink,
+///     register: 0x80,
+///     attempts: 1,
+///     source: I2cMockError,
+/// };
+///
+/// assert_eq!(Some(Operation::SetBlink), error.operation());
+/// assert_eq!(
+///     "Failed to [set blink] at register [0x80] after [1] attempt(s): I2cMockError",
+///     error.to_string()
+/// );
+///
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error<E> {
+    /// An I2C transaction with the `HT16K33` device failed.
+    I2c {
+        /// The operation that failed.
+        operation: Operation,
+        /// The device register (command byte) being accessed.
+        register: u8,
+        /// How many times the transaction was attempted.
+        attempts: u32,
+        /// The error returned by the I2C device.
+        source: E,
+    },
+    /// An argument was out of range, see
+    /// [Bargraph::try_update()](struct.Bargraph.html#method.try_update).
+    InvalidArgument {
+        /// The name of the argument.
+        name: &'static str,
+        /// The value of the argument.
+        value: f32,
+        /// The smallest valid value.
+        min: f32,
+        /// The largest valid value.
+        max: f32,
+    },
+    /// Switching the power pin failed, see
+    /// [Bargraph::set_power_pin()](struct.Bargraph.html#method.set_power_pin).
+    Power {
+        /// Whether the power was being switched on.
+        on: bool,
+        /// The error returned by the pin.
+        source: String,
+    },
+}
+
+impl<E> Error<E> {
+    /// The operation that failed, if the error came from the device.
+    pub fn operation(&self) -> Option<Operation> {
+        match *self {
+            Error::I2c { operation, .. } => Some(operation),
+            Error::InvalidArgument { .. } | Error::Power { .. } => None,
+        }
+    }
+
+    // Check that the argument `name` is within `min` to `max`, a `NaN` never is.
+    pub(crate) fn check_argument<V>(
+        name: &'static str,
+        value: V,
+        min: V,
+        max: V,
+    ) -> Result<(), Self>
+    where
+        V: Into<f32>,
+    {
+        let (value, min, max) = (value.into(), min.into(), max.into());
+
+        if !(min <= value && value <= max) {
+            return Err(Error::InvalidArgument {
+                name,
+                value,
+                min,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Wrap an I2C error from a single attempt at `operation` on `register`, for `map_err()`.
+    pub(crate) fn i2c(operation: Operation, register: u8) -> impl FnOnce(E) -> Self {
+        move |source| Error::I2c {
+            operation,
+            register,
+            attempts: 1,
+            source,
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::I2c {
+                operation,
+                register,
+                attempts,
+                ref source,
+            } => write!(
+                f,
+                "Failed to [{}] at register [{:#04x}] after [{}] attempt(s): {:?}",
+                operation, register, attempts, source
+            ),
+            Error::InvalidArgument {
+                name,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "Invalid {} [{}], expected [{}] to [{}]",
+                name,
+                Number(value),
+                Number(min),
+                Number(max)
+            ),
+            Error::Power { on, ref source } => write!(
+                f,
+                "Failed to switch the power [{}]: {}",
+                if on { "on" } else { "off" },
+                source
+            ),
+        }
+    }
+}
+
+impl<E: fmt::Debug> error::Error for Error<E> {}
+
+// Display whole numbers like integers, & the others in full precision, e.g. `1.1754944e-38`
+// instead of `0.000000000000000000000000000000000000011754944`.
+struct Number(f32);
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.fract() == 0.0 && self.0.abs() < 1e16 {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "{:?}", self.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn i2c() {
+        let error = Error::i2c(Operation::WriteDisplayBuffer, 0x00)("bus error");
+
+        assert_eq!(
+            Error::I2c {
+                operation: Operation::WriteDisplayBuffer,
+                register: 0x00,
+                attempts: 1,
+                source: "bus error",
+            },
+            error
+        );
+        assert_eq!(Some(Operation::WriteDisplayBuffer), error.operation());
+    }
+
+    #[test]
+    fn display() {
+        let error = Error::i2c(Operation::SetBrightness, 0xE0)("bus error");
+
+        assert_eq!(
+            "Failed to [set brightness] at register [0xe0] after [1] attempt(s): \"bus error\"",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn check_argument() {
+        assert_eq!(Ok(()), Error::<()>::check_argument("range", 1u8, 1, 24));
+        assert_eq!(Ok(()), Error::<()>::check_argument("range", 24u8, 1, 24));
+
+        let error = Error::<()>::check_argument("range", 0u8, 1, 24).unwrap_err();
+        assert_eq!(None, error.operation());
+        assert_eq!("Invalid range [0], expected [1] to [24]", error.to_string());
+
+        let error = Error::<()>::check_argument("range", f32::NAN, 0.5, 100.0).unwrap_err();
+        assert_eq!(
+            "Invalid range [NaN], expected [0.5] to [100]",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn power() {
+        let error = Error::<()>::Power {
+            on: false,
+            source: "()".to_string(),
+        };
+
+        assert_eq!(None, error.operation());
+        assert_eq!("Failed to switch the power [off]: ()", error.to_string());
+    }
+}
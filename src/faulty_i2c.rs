@@ -0,0 +1,244 @@
+//! # Faulty I2C
+//!
+//! An I2C device wrapper that fails some of the transactions on purpose, for checking
+//! how a deployment copes with a misbehaving bus before it happens for real.
+use hal::blocking::i2c::{Read, Write, WriteRead};
+
+use std::error;
+use std::fmt::{self, Debug};
+
+use animation::xorshift;
+
+// The first of the pseudo-random numbers deciding which transactions fail.
+const FAULT_SEED: u32 = 0x9E37_79B9;
+
+/// The error of a [FaultyI2c](struct.FaultyI2c.html) transaction.
+#[derive(Debug, PartialEq)]
+pub enum FaultyI2cError<E> {
+    /// The transaction was failed on purpose, without reaching the I2C device.
+    Injected,
+    /// The I2C device failed the transaction.
+    I2c(E),
+}
+
+impl<E> fmt::Display for FaultyI2cError<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FaultyI2cError::Injected => write!(f, "Injected I2C error"),
+            FaultyI2cError::I2c(ref e) => write!(f, "I2C error: {:?}", e),
+        }
+    }
+}
+
+impl<E> error::Error for FaultyI2cError<E> where E: Debug {}
+
+/// Wraps an I2C device, failing a fraction of the transactions with
+/// [FaultyI2cError::Injected](enum.FaultyI2cError.html#variant.Injected).
+///
+/// The transactions that fail are picked pseudo-randomly from a seed, the same
+/// transactions fail every time for the same seed so that a failure can be reproduced.
+pub struct FaultyI2c<T> {
+    i2c: T,
+    rate: f32,
+    random: u32,
+    injected: u64,
+}
+
+impl<T> FaultyI2c<T> {
+    /// Create a FaultyI2c wrapping an I2C device, with a fixed seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C device to fail the transactions of.
+    /// * `rate` - The fraction of the transactions to fail, from `0.0` (none) to `1.0`
+    ///   (all).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rate isn't from `0.0` to `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate ht16k33;
+    /// extern crate led_bargraph;
+    ///
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use led_bargraph::{Bargraph, FaultyI2c};
+    /// # fn main() {
+    ///
+    /// // Fail 5% of the transactions.
+    /// let i2c = FaultyI2c::new(I2cMock::new(None), 0.05);
+    /// let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// # }
+    /// ```
+    pub fn new(i2c: T, rate: f32) -> Self {
+        FaultyI2c::with_seed(i2c, rate, FAULT_SEED)
+    }
+
+    /// Create a FaultyI2c like [new()](#method.new), picking the transactions that
+    /// fail from the seed, e.g. the time so that each run fails differently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rate isn't from `0.0` to `1.0`.
+    pub fn with_seed(i2c: T, rate: f32, seed: u32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&rate),
+            "Rate [{}] must be from [0.0] to [1.0]",
+            rate
+        );
+
+        FaultyI2c {
+            i2c,
+            rate,
+            // A zero seed would only ever pick zero.
+            random: if seed == 0 { FAULT_SEED } else { seed },
+            injected: 0,
+        }
+    }
+
+    /// The number of transactions failed on purpose.
+    pub fn injected(&self) -> u64 {
+        self.injected
+    }
+
+    /// Destroy the FaultyI2c, returning the wrapped I2C device.
+    pub fn into_inner(self) -> T {
+        self.i2c
+    }
+
+    // Whether to fail the next transaction.
+    fn inject(&mut self) -> bool {
+        self.random = xorshift(self.random);
+
+        let inject = f64::from(self.random) < f64::from(self.rate) * (f64::from(u32::MAX) + 1.0);
+        if inject {
+            self.injected += 1;
+        }
+
+        inject
+    }
+}
+
+impl<T> Write for FaultyI2c<T>
+where
+    T: Write,
+{
+    type Error = FaultyI2cError<T::Error>;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        if self.inject() {
+            return Err(FaultyI2cError::Injected);
+        }
+
+        self.i2c.write(address, bytes).map_err(FaultyI2cError::I2c)
+    }
+}
+
+impl<T> WriteRead for FaultyI2c<T>
+where
+    T: WriteRead,
+{
+    type Error = FaultyI2cError<T::Error>;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        if self.inject() {
+            return Err(FaultyI2cError::Injected);
+        }
+
+        self.i2c
+            .write_read(address, bytes, buffer)
+            .map_err(FaultyI2cError::I2c)
+    }
+}
+
+impl<T> Read for FaultyI2c<T>
+where
+    T: Read,
+{
+    type Error = FaultyI2cError<T::Error>;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if self.inject() {
+            return Err(FaultyI2cError::Injected);
+        }
+
+        self.i2c.read(address, buffer).map_err(FaultyI2cError::I2c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    #[test]
+    fn never() {
+        let mut i2c = FaultyI2c::new(I2cMock::new(None), 0.0);
+
+        for _ in 0..100 {
+            i2c.write(ADDRESS, &[0x00, 0xFF]).unwrap();
+        }
+
+        assert_eq!(0, i2c.injected());
+        assert_eq!(0xFF, i2c.into_inner().data_values[0]);
+    }
+
+    #[test]
+    fn always() {
+        let mut i2c = FaultyI2c::new(I2cMock::new(None), 1.0);
+
+        let mut buffer = [0u8; 1];
+        match i2c.write_read(ADDRESS, &[0x00], &mut buffer) {
+            Err(FaultyI2cError::Injected) => {}
+            result => panic!("Expected an injected error, got {:?}", result),
+        }
+        assert_eq!(1, i2c.injected());
+    }
+
+    #[test]
+    fn rate() {
+        let mut i2c = FaultyI2c::new(I2cMock::new(None), 0.25);
+
+        let failed = (0..1000)
+            .filter(|_| i2c.write(ADDRESS, &[0x00, 0xFF]).is_err())
+            .count();
+
+        assert!((200..300).contains(&failed), "{} failed", failed);
+        assert_eq!(failed as u64, i2c.injected());
+    }
+
+    #[test]
+    fn with_seed() {
+        let failures = |seed| {
+            let mut i2c = FaultyI2c::with_seed(I2cMock::new(None), 0.5, seed);
+            (0..32)
+                .map(|_| i2c.write(ADDRESS, &[0x00]).is_err())
+                .collect::<Vec<bool>>()
+        };
+
+        assert_eq!(failures(7), failures(7));
+        assert_ne!(failures(7), failures(8));
+        assert_eq!(failures(FAULT_SEED), failures(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_rate() {
+        FaultyI2c::new(I2cMock::new(None), 1.5);
+    }
+}
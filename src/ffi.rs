@@ -0,0 +1,219 @@
+//! # FFI
+//!
+//! A C interface to the [Bargraph](../struct.Bargraph.html), enabled with the `ffi` feature.
+//!
+//! Build the shared library with:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! The matching header is `include/led_bargraph.h`, regenerate it after changing this module
+//! with `cbindgen --config cbindgen.toml --output include/led_bargraph.h src/ffi.rs`.
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use ht16k33::i2c_mock::I2cMock;
+
+#[cfg(target_os = "linux")]
+use linux_embedded_hal::I2cdev;
+
+use super::Bargraph as RustBargraph;
+
+/// Returned by the `bargraph_*` functions when they succeed.
+pub const BARGRAPH_OK: c_int = 0;
+/// Returned by the `bargraph_*` functions when they fail.
+pub const BARGRAPH_ERROR: c_int = -1;
+
+/// An opaque handle to a Bargraph, created with `bargraph_new()` and destroyed
+/// with `bargraph_free()`.
+pub struct FfiBargraph {
+    inner: Inner,
+}
+
+// The C interface can't be generic, so enumerate the supported I2C devices.
+enum Inner {
+    #[cfg(target_os = "linux")]
+    Linux(RustBargraph<I2cdev>),
+    Mock(RustBargraph<I2cMock>),
+}
+
+// Call the same method on whichever Bargraph is wrapped, mapping the result to
+// a C status code.
+macro_rules! status {
+    ($bargraph:expr, $method:ident($($arg:expr),*)) => {{
+        let result = match $bargraph.inner {
+            #[cfg(target_os = "linux")]
+            Inner::Linux(ref mut bargraph) => bargraph.$method($($arg),*).is_ok(),
+            Inner::Mock(ref mut bargraph) => bargraph.$method($($arg),*).is_ok(),
+        };
+
+        if result {
+            BARGRAPH_OK
+        } else {
+            BARGRAPH_ERROR
+        }
+    }};
+}
+
+/// Create & initialize a Bargraph.
+///
+/// Returns `NULL` if the I2C device can't be opened or initialized.
+///
+/// # Arguments
+///
+/// * `i2c_path` - Path to the I2C device (e.g. `/dev/i2c-1`), or `NULL` to use a mock
+///   I2C device, useful when no device is available.
+/// * `i2c_address` - Address of the I2C device (e.g. `0x70`).
+///
+/// # Safety
+///
+/// `i2c_path` must be `NULL` or a valid, NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn bargraph_new(
+    i2c_path: *const c_char,
+    i2c_address: u8,
+) -> *mut FfiBargraph {
+    let inner = if i2c_path.is_null() {
+        Inner::Mock(RustBargraph::new(I2cMock::new(None), i2c_address, None))
+    } else {
+        match open_i2c(CStr::from_ptr(i2c_path), i2c_address) {
+            Some(inner) => inner,
+            None => return ptr::null_mut(),
+        }
+    };
+
+    let mut bargraph = FfiBargraph { inner };
+
+    if status!(bargraph, initialize()) != BARGRAPH_OK {
+        return ptr::null_mut();
+    }
+
+    Box::into_raw(Box::new(bargraph))
+}
+
+#[cfg(target_os = "linux")]
+fn open_i2c(i2c_path: &CStr, i2c_address: u8) -> Option<Inner> {
+    let mut i2c = I2cdev::new(i2c_path.to_str().ok()?).ok()?;
+    i2c.set_slave_address(u16::from(i2c_address)).ok()?;
+
+    Some(Inner::Linux(RustBargraph::new(i2c, i2c_address, None)))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_i2c(_i2c_path: &CStr, _i2c_address: u8) -> Option<Inner> {
+    None
+}
+
+/// Update the Bargraph display, see [Bargraph::try_update()](../struct.Bargraph.html#method.try_update).
+///
+/// Returns `BARGRAPH_OK` on success, otherwise `BARGRAPH_ERROR`, including when `range`
+/// is `0` or greater than the display's resolution, or `value` is greater than `range`.
+///
+/// # Safety
+///
+/// `bargraph` must be `NULL` or a handle returned by `bargraph_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn bargraph_update(
+    bargraph: *mut FfiBargraph,
+    value: u8,
+    range: u8,
+) -> c_int {
+    match bargraph.as_mut() {
+        Some(bargraph) => status!(bargraph, try_update(value, range, false)),
+        None => BARGRAPH_ERROR,
+    }
+}
+
+/// Clear the Bargraph display.
+///
+/// Returns `BARGRAPH_OK` on success, otherwise `BARGRAPH_ERROR`.
+///
+/// # Safety
+///
+/// `bargraph` must be `NULL` or a handle returned by `bargraph_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn bargraph_clear(bargraph: *mut FfiBargraph) -> c_int {
+    match bargraph.as_mut() {
+        Some(bargraph) => status!(bargraph, clear()),
+        None => BARGRAPH_ERROR,
+    }
+}
+
+/// Destroy a Bargraph, the display is left as-is.
+///
+/// # Safety
+///
+/// `bargraph` must be `NULL` or a handle returned by `bargraph_new()`, and must not
+/// be used after it's freed.
+#[no_mangle]
+pub unsafe extern "C" fn bargraph_free(bargraph: *mut FfiBargraph) {
+    if !bargraph.is_null() {
+        drop(Box::from_raw(bargraph));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_and_free() {
+        unsafe {
+            let bargraph = bargraph_new(ptr::null(), 0);
+            assert!(!bargraph.is_null());
+
+            bargraph_free(bargraph);
+        }
+    }
+
+    #[test]
+    fn new_invalid_path() {
+        unsafe {
+            let bargraph = bargraph_new(b"/dev/does-not-exist\0".as_ptr() as *const c_char, 0);
+            assert!(bargraph.is_null());
+        }
+    }
+
+    #[test]
+    fn update() {
+        unsafe {
+            let bargraph = bargraph_new(ptr::null(), 0);
+            assert_eq!(BARGRAPH_OK, bargraph_update(bargraph, 5, 6));
+
+            bargraph_free(bargraph);
+        }
+    }
+
+    #[test]
+    fn update_invalid() {
+        unsafe {
+            let bargraph = bargraph_new(ptr::null(), 0);
+            assert_eq!(BARGRAPH_ERROR, bargraph_update(bargraph, 0, 0));
+            assert_eq!(BARGRAPH_ERROR, bargraph_update(bargraph, 5, 25));
+            assert_eq!(BARGRAPH_ERROR, bargraph_update(bargraph, 7, 6));
+
+            bargraph_free(bargraph);
+        }
+    }
+
+    #[test]
+    fn clear() {
+        unsafe {
+            let bargraph = bargraph_new(ptr::null(), 0);
+            assert_eq!(BARGRAPH_OK, bargraph_clear(bargraph));
+
+            bargraph_free(bargraph);
+        }
+    }
+
+    #[test]
+    fn null_handle() {
+        unsafe {
+            assert_eq!(BARGRAPH_ERROR, bargraph_update(ptr::null_mut(), 5, 6));
+            assert_eq!(BARGRAPH_ERROR, bargraph_clear(ptr::null_mut()));
+            bargraph_free(ptr::null_mut());
+        }
+    }
+}
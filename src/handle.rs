@@ -0,0 +1,191 @@
+//! # Handle
+//!
+//! Share one display between threads, e.g. an HTTP server & a sampler, by moving the
+//! [Bargraph](struct.Bargraph.html) to a background thread & sending it commands.
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::fmt::Debug;
+use std::sync::mpsc::{self, SendError, Sender};
+use std::thread::{self, JoinHandle};
+
+use super::{Bargraph, BRIGHTNESS_MAX};
+
+/// A command for the [Bargraph](struct.Bargraph.html) owned by a
+/// [BargraphHandle](struct.BargraphHandle.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// Show the value against the range, see [Bargraph::try_update()](struct.Bargraph.html#method.try_update).
+    SetValue {
+        /// How many values to fill, starting from `0`.
+        value: u8,
+        /// Total number of values to display.
+        range: u8,
+    },
+    /// Clear the display.
+    Clear,
+    /// Enable/Disable continuous blinking of the display.
+    Blink(bool),
+    /// Set the brightness of the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    Brightness(u8),
+}
+
+/// A cheap, cloneable & thread-safe handle to a [Bargraph](struct.Bargraph.html) running
+/// on a background thread.
+///
+/// Commands are applied in the order they're sent. Errors are logged by the background
+/// thread rather than returned, so a failing display never takes down its callers.
+///
+/// The background thread stops once every handle is dropped, returning the Bargraph.
+///
+/// # Examples
+///
+/// ```
+/// extern crate ht16k33;
+/// extern crate led_bargraph;
+///
+/// use ht16k33::i2c_mock::I2cMock;
+/// use led_bargraph::{Bargraph, BargraphHandle};
+/// use std::thread;
+/// # fn main() {
+///
+/// let bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+/// let (handle, thread) = BargraphHandle::spawn(bargraph);
+///
+/// let sampler = handle.clone();
+/// thread::spawn(move || sampler.set_value(3, 6).unwrap())
+///     .join()
+///     .unwrap();
+///
+/// handle.set_blink(true).unwrap();
+///
+/// drop(handle);
+/// let bargraph = thread.join().unwrap();
+///
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BargraphHandle {
+    sender: Sender<Command>,
+}
+
+impl BargraphHandle {
+    /// Move the Bargraph to a background thread, returning a handle to it & the thread.
+    ///
+    /// The display isn't initialized, initialize it before spawning.
+    pub fn spawn<I2C, E>(mut bargraph: Bargraph<I2C>) -> (Self, JoinHandle<Bargraph<I2C>>)
+    where
+        I2C: Write<Error = E> + WriteRead<Error = E> + Send + 'static,
+        E: Debug,
+    {
+        let (sender, receiver) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            for command in receiver {
+                trace!(bargraph.logger, "Received a command"; "command" => ?command);
+
+                let result = match command {
+                    Command::SetValue { value, range } => bargraph.try_update(value, range, false),
+                    Command::Clear => bargraph.clear(),
+                    Command::Blink(enabled) => bargraph.set_blink(enabled),
+                    Command::Brightness(brightness) => bargraph.set_brightness(brightness),
+                };
+
+                if let Err(e) = result {
+                    error!(bargraph.logger, "Failed to apply a command";
+                           "command" => ?command, "error" => %e);
+                }
+            }
+
+            bargraph
+        });
+
+        (BargraphHandle { sender }, thread)
+    }
+
+    /// Send a command to the Bargraph.
+    ///
+    /// # Errors
+    ///
+    /// Returns the command if the background thread has stopped.
+    pub fn send(&self, command: Command) -> Result<(), SendError<Command>> {
+        self.sender.send(command)
+    }
+
+    /// Show the value against the range, see [Command::SetValue](enum.Command.html#variant.SetValue).
+    pub fn set_value(&self, value: u8, range: u8) -> Result<(), SendError<Command>> {
+        self.send(Command::SetValue { value, range })
+    }
+
+    /// Clear the display.
+    pub fn clear(&self) -> Result<(), SendError<Command>> {
+        self.send(Command::Clear)
+    }
+
+    /// Enable/Disable continuous blinking of the display.
+    pub fn set_blink(&self, enabled: bool) -> Result<(), SendError<Command>> {
+        self.send(Command::Blink(enabled))
+    }
+
+    /// Set the brightness of the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range, here rather than on the background thread.
+    pub fn set_brightness(&self, brightness: u8) -> Result<(), SendError<Command>> {
+        assert!(
+            brightness <= BRIGHTNESS_MAX,
+            "Brightness [{}] must be less than or equal to [{}]",
+            brightness,
+            BRIGHTNESS_MAX
+        );
+
+        self.send(Command::Brightness(brightness))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use Frame;
+
+    const ADDRESS: u8 = 0;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn handle_is_send_sync() {
+        assert_send_sync::<BargraphHandle>();
+    }
+
+    #[test]
+    fn spawn() {
+        let bargraph = Bargraph::new(I2cMock::new(None), ADDRESS, None);
+        let (handle, thread) = BargraphHandle::spawn(bargraph);
+
+        let other = handle.clone();
+        other.set_value(3, 6).unwrap();
+        other.set_brightness(BRIGHTNESS_MAX).unwrap();
+
+        // Invalid values are logged, not applied.
+        handle.set_value(1, 0).unwrap();
+
+        drop(other);
+        drop(handle);
+
+        let bargraph = thread.join().unwrap();
+        assert_eq!(Some(Frame::from_value(3, 6)), bargraph.last_frame);
+        assert_eq!(BRIGHTNESS_MAX, bargraph.brightness());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_brightness_too_large() {
+        let bargraph = Bargraph::new(I2cMock::new(None), ADDRESS, None);
+        let (handle, _thread) = BargraphHandle::spawn(bargraph);
+
+        handle.set_brightness(BRIGHTNESS_MAX + 1).unwrap();
+    }
+}
@@ -1,27 +1,152 @@
 //! # Bargraph
 //!
 //! A library for the [Adafruit Bi-Color (Red/Green) 24-Bar Bargraph w/I2C Backpack Kit](https://www.adafruit.com/product/1721).
+//!
+//! The [SevenSegment](struct.SevenSegment.html), [AlphaNum4](struct.AlphaNum4.html) and
+//! [Matrix8x8](struct.Matrix8x8.html) displays, which use the same `HT16K33` backpack, are also
+//! supported.
+//!
+//! Logging is done with `slog`, which the `HT16K33` driver also uses. Enable the `tracing`
+//! feature to also emit `tracing` spans for `update`, `render`, `flush` & `show`, & to emit
+//! warnings, e.g. a value greater than its range, as `tracing` events instead.
 #![deny(missing_docs)]
+#[cfg(feature = "preview")]
 extern crate ansi_term;
 extern crate embedded_hal as hal;
 extern crate ht16k33;
-extern crate num_integer;
 
 #[macro_use]
 extern crate slog;
 extern crate slog_stdlog;
 
-use ansi_term::Colour::{Fixed, Green, Red, White, Yellow};
-use ansi_term::Style;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+#[cfg(all(feature = "ffi", target_os = "linux"))]
+extern crate linux_embedded_hal;
+
+#[cfg(test)]
+extern crate embedded_hal_mock;
+
+// Enter a `tracing` span until the end of the current scope, if the `tracing` feature
+// is enabled.
+macro_rules! span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+// Log a warning once: as a `tracing` event if the `tracing` feature is enabled,
+// otherwise to the `slog` logger.
+macro_rules! log_warn {
+    ($logger:expr, $message:expr; $($key:ident => $value:expr),* $(,)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($key = $value,)* $message);
+        #[cfg(not(feature = "tracing"))]
+        warn!($logger, $message; $(stringify!($key) => $value),*);
+    };
+}
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+mod alphanum;
+mod animation;
+#[cfg(feature = "sensors")]
+mod co2;
+mod color_map;
+mod color_scheme;
+mod display;
+#[cfg(feature = "emulator")]
+mod emulator;
+#[cfg(feature = "sensors")]
+mod environment;
+mod errors;
+#[cfg(feature = "emulator")]
+mod faulty_i2c;
+#[cfg(feature = "threads")]
+mod handle;
+pub mod mapping;
+mod matrix;
+mod metrics;
+mod outputs;
+#[cfg(feature = "pipeline")]
+mod pipeline;
+#[cfg(feature = "sensors")]
+mod power;
+#[cfg(feature = "preview")]
+mod preview;
+mod recovery;
+mod render;
+#[cfg(feature = "threads")]
+mod scheduler;
+mod seven_segment;
+mod simulator;
+mod sources;
+mod tracing_i2c;
+#[cfg(feature = "threads")]
+mod updater;
+
+pub use alphanum::{AlphaNum4, ALPHANUM_DIGITS};
+pub use animation::{Animation, ParseAnimationError, Pattern};
+#[cfg(feature = "sensors")]
+pub use co2::{Co2Error, Co2Sensor, Scd};
+pub use color_map::{ColorMap, ParseColorMapError};
+pub use color_scheme::{ColorScheme, Palette, Thresholds};
+pub use display::{brightness_from_percent, BlinkRate, Ht16k33Display, BRIGHTNESS_MAX};
+#[cfg(feature = "emulator")]
+pub use emulator::{Ht16k33Emulator, KEY_DATA_SIZE};
+#[cfg(feature = "sensors")]
+pub use environment::{Bme, Climate, EnvironmentError, EnvironmentSensor};
+pub use errors::{Error, Operation};
+#[cfg(feature = "emulator")]
+pub use faulty_i2c::{FaultyI2c, FaultyI2cError};
+#[cfg(feature = "threads")]
+pub use handle::{BargraphHandle, Command};
+pub use matrix::{Matrix8x8, MATRIX_SIZE};
+pub use metrics::Metrics;
+#[cfg(feature = "preview")]
+pub use outputs::TerminalRenderer;
+pub use outputs::{OutputError, Outputs};
+#[cfg(feature = "pipeline")]
+pub use pipeline::{Pipeline, Scale, Smooth, Threshold, Transform};
+#[cfg(feature = "sensors")]
+pub use power::{Electrical, Ina, PowerError, PowerMeter};
+#[cfg(feature = "preview")]
+pub use preview::{ColorSupport, TerminalSupport};
+pub use recovery::recover_bus;
+pub use render::{Frame, ParseFrameError, Render, Style};
+#[cfg(feature = "threads")]
+pub use scheduler::SchedulerConfig;
+pub use seven_segment::{SevenSegment, SEVEN_SEGMENT_DIGITS};
+pub use simulator::{CapturedFrame, SimulatorBackend};
+pub use sources::{Sample, Status, ValueSource};
+pub use tracing_i2c::TracingI2c;
+#[cfg(feature = "threads")]
+pub use updater::UpdaterConfig;
 
 use hal::blocking::i2c::{Write, WriteRead};
+use hal::digital::v2::OutputPin;
 
-use ht16k33::{Display, HT16K33};
+use ht16k33::{
+    Dimming, Display, DisplayDataAddress, LedLocation, Oscillator, ValidationError, HT16K33,
+};
 
-use num_integer::Integer;
+use metrics::MeteredI2c;
+use render::Look;
 
 use slog::Drain;
 
+use std::error;
+use std::fmt::{self, Debug};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 /// LED colors.
 pub enum LedColor {
@@ -35,12 +160,141 @@ pub enum LedColor {
     Yellow,
 }
 
+/// The lowercase name of the color, e.g. `green`.
+impl fmt::Display for LedColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            LedColor::Off => "off",
+            LedColor::Green => "green",
+            LedColor::Red => "red",
+            LedColor::Yellow => "yellow",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Parse the name of a color, ignoring case, or its first letter, e.g. `Green` or `g`.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate led_bargraph;
+/// # use led_bargraph::LedColor;
+/// # fn main() {
+///
+/// assert_eq!(Ok(LedColor::Yellow), "yellow".parse());
+/// assert_eq!(Ok(LedColor::Red), "R".parse());
+/// assert!("blue".parse::<LedColor>().is_err());
+///
+/// # }
+/// ```
+impl FromStr for LedColor {
+    type Err = ParseLedColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "o" => Ok(LedColor::Off),
+            "green" | "g" => Ok(LedColor::Green),
+            "red" | "r" => Ok(LedColor::Red),
+            "yellow" | "y" => Ok(LedColor::Yellow),
+            _ => Err(ParseLedColorError {
+                color: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// The error returned when a [LedColor](enum.LedColor.html) can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseLedColorError {
+    color: String,
+}
+
+impl fmt::Display for ParseLedColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid color [{}], expected off, green, red or yellow",
+            self.color
+        )
+    }
+}
+
+impl error::Error for ParseLedColorError {}
+
+#[cfg(feature = "preview")]
 const BARGRAPH_DISPLAY_CHAR: &str = "\u{258A}";
-const BARGRAPH_RESOLUTION: u8 = 24;
+
+/// The number of bars on the display.
+pub const BARGRAPH_RESOLUTION: u8 = 24;
+
+// How long the backpack is unpowered for when power cycling, to fully reset it.
+const POWER_OFF_DURATION: Duration = Duration::from_millis(100);
+
+// How long the backpack takes to start up once powered.
+const POWER_ON_DURATION: Duration = Duration::from_millis(10);
+
+// Drives the power pin high (`true`) or low, with the pin's error formatted.
+type PowerSwitch = Box<dyn FnMut(bool) -> Result<(), String> + Send>;
 
 /// The bargraph state.
 pub struct Bargraph<I2C> {
-    device: HT16K33<I2C>,
+    device: HT16K33<MeteredI2c<I2C>>,
+    metrics: Arc<Mutex<Metrics>>,
+    // The row & common of each bar's red LED.
+    mapping: [(u8, u8); BARGRAPH_RESOLUTION as usize],
+    // The red & green LEDs of each bar, validated from the mapping, starting from the bottom.
+    locations: [(LedLocation, LedLocation); BARGRAPH_RESOLUTION as usize],
+    // The number of bars in use, starting from the bottom.
+    resolution: u8,
+    // Whether the display is mounted upside down.
+    flipped: bool,
+    // Whether values fill from the top of the display down.
+    reversed: bool,
+    // The colors of the values drawn by `update()` & the other single value updates.
+    scheme: ColorScheme,
+    // The zones coloring the values drawn by `update()` instead of the scheme, if any.
+    thresholds: Option<Thresholds>,
+    // Colors each bar drawn by `update()` instead of the scheme & thresholds, if any.
+    // Shared with the pipelines of `pipeline()`.
+    palette: Option<Arc<dyn Palette + Send + Sync>>,
+    // How `update()` draws values.
+    style: Style,
+    // Whether `update_f32()` lights half bars.
+    half_steps: bool,
+    // The `(position, color, blink)` of each marker drawn over every frame.
+    markers: Vec<(u8, LedColor, bool)>,
+    // Whether `show()` emulates blinking, & when the display started blinking.
+    #[cfg(feature = "preview")]
+    emulate_blink: bool,
+    #[cfg(feature = "preview")]
+    blink_started: Instant,
+    // What the terminal of `show()` supports, detected each time if `None`.
+    #[cfg(feature = "preview")]
+    terminal: Option<TerminalSupport>,
+    // The blink state last written to the device, `None` if unknown.
+    blink: Option<bool>,
+    // Whether the display setup is known, it can't be read back from the device.
+    display_known: bool,
+    // The frame last written to the device, `None` if unknown.
+    last_frame: Option<Frame>,
+    // Whether the last rendered frame changed the display.
+    changed: bool,
+    // Whether to skip writing frames that are already displayed.
+    skip_unchanged: bool,
+    // Whether frames are kept until `present()`, & the frame waiting to be presented.
+    deferred: bool,
+    pending: Option<Frame>,
+    // Recovers the I2C bus after this many consecutive failures, & the failures so far.
+    bus_recovery: Option<(u32, Box<dyn FnMut() + Send>)>,
+    failures: u32,
+    // Switches the backpack's power on & off.
+    power: Option<PowerSwitch>,
+    // How long `initialize()` fades in the first frame for & to which brightness, &
+    // whether the first frame is still to be faded in.
+    fade_in: (Duration, u8),
+    fading_in: bool,
     logger: slog::Logger,
 }
 
@@ -95,10 +349,43 @@ where
         trace!(logger, "Constructing Bargraph");
 
         let ht16k33_logger = logger.new(o!("mod" => "HT16K33"));
+        let metrics = Arc::new(Mutex::new(Metrics::default()));
+        let i2c = MeteredI2c::new(i2c, metrics.clone());
         let ht16k33 = HT16K33::new(i2c, i2c_address, ht16k33_logger);
 
         Bargraph {
             device: ht16k33,
+            metrics,
+            mapping: mapping::builtin_table(),
+            locations: bar_locations(&mapping::builtin_table())
+                .expect("The built-in mapping is valid"),
+            resolution: BARGRAPH_RESOLUTION,
+            flipped: false,
+            reversed: false,
+            scheme: ColorScheme::default(),
+            thresholds: None,
+            palette: None,
+            style: Style::default(),
+            half_steps: false,
+            markers: Vec::new(),
+            #[cfg(feature = "preview")]
+            emulate_blink: false,
+            #[cfg(feature = "preview")]
+            blink_started: Instant::now(),
+            #[cfg(feature = "preview")]
+            terminal: None,
+            blink: None,
+            display_known: false,
+            last_frame: None,
+            changed: false,
+            skip_unchanged: false,
+            deferred: false,
+            pending: None,
+            bus_recovery: None,
+            failures: 0,
+            power: None,
+            fade_in: (Duration::from_secs(0), BRIGHTNESS_MAX),
+            fading_in: false,
             logger,
         }
     }
@@ -122,15 +409,80 @@ where
     ///
     /// # }
     /// ```
-    pub fn initialize(&mut self) -> Result<(), E> {
+    pub fn initialize(&mut self) -> Result<(), Error<E>> {
         trace!(self.logger, "initialize");
 
-        // Reset the display.
-        self.device.initialize()?;
+        // Reset the display, this turns it off.
+        self.blink = None;
+        self.last_frame = None;
+        self.fading_in = false;
+        self.device.initialize().map_err(Error::i2c(
+            Operation::Initialize,
+            Oscillator::COMMAND.bits(),
+        ))?;
+        self.display_known = true;
+
+        // The display is still off, so the first frame is faded in once rendered.
+        let (duration, brightness) = self.fade_in;
+        if duration > Duration::from_secs(0) {
+            self.write_brightness(0)?;
+            self.fading_in = true;
+        } else if brightness != BRIGHTNESS_MAX {
+            self.write_brightness(brightness)?;
+        }
 
         Ok(())
     }
 
+    /// Fade in the first frame rendered after [initialize()](#method.initialize),
+    /// ramping the brightness from `0` rather than starting at full brightness.
+    ///
+    /// Setting the brightness before the first frame cancels the fade.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How long to fade in for, `0` (the default) to not fade in.
+    /// * `brightness` - The brightness to fade in to, from `0` (dimmest) to
+    ///   [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html) (the default).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # use std::time::Duration;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_fade_in(Duration::from_millis(10), 8);
+    /// bargraph.initialize().unwrap();
+    ///
+    /// // Fades in to brightness `8`.
+    /// bargraph.update(3, 6, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_fade_in(&mut self, duration: Duration, brightness: u8) {
+        trace!(self.logger, "set_fade_in"; "duration" => ?duration, "brightness" => brightness);
+
+        assert!(
+            brightness <= BRIGHTNESS_MAX,
+            "Brightness [{}] must be less than or equal to [{}]",
+            brightness,
+            BRIGHTNESS_MAX
+        );
+
+        self.fade_in = (duration, brightness);
+    }
+
     /// Clear the Bargraph display.
     ///
     /// # Examples
@@ -149,11 +501,116 @@ where
     ///
     /// # }
     /// ```
-    pub fn clear(&mut self) -> Result<(), E> {
+    pub fn clear(&mut self) -> Result<(), Error<E>> {
         trace!(self.logger, "clear");
 
+        if self.deferred {
+            self.pending = Some(Frame::default());
+            return Ok(());
+        }
+
+        self.clear_display()
+    }
+
+    /// Dim the Bargraph display to `0` (dimmest) over the duration, then clear it &
+    /// restore the brightness.
+    ///
+    /// Unlike [clear()](#method.clear), the display is cleared immediately when
+    /// [set_deferred()](#method.set_deferred) is enabled, discarding any frame waiting
+    /// to be presented.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How long to fade out for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # use std::time::Duration;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update(3, 6, false).unwrap();
+    /// bargraph.clear_with_fade(Duration::from_millis(10)).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn clear_with_fade(&mut self, duration: Duration) -> Result<(), Error<E>> {
+        span!("clear_with_fade");
+        trace!(self.logger, "clear_with_fade"; "duration" => ?duration);
+
+        let brightness = self.brightness();
+
+        self.pending = None;
+        self.fade(0, duration)?;
+        self.clear_display()?;
+
+        self.write_brightness(brightness)
+    }
+
+    /// Play the animation on the Bargraph display, returning once its last frame has
+    /// been displayed for the interval.
+    ///
+    /// The last frame is left on the display, & frames are displayed immediately even
+    /// when [set_deferred()](#method.set_deferred) is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation` - The animation to play.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Animation, Bargraph, Pattern};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.play(&Animation::new(Pattern::Sweep)).unwrap();
+    /// bargraph.clear().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn play(&mut self, animation: &Animation) -> Result<(), Error<E>> {
+        span!("play");
+        trace!(self.logger, "play"; "animation" => ?animation);
+
+        for frame in animation.frames(self.resolution) {
+            self.render(&frame)?;
+            thread::sleep(animation.interval);
+        }
+
+        Ok(())
+    }
+
+    // How the values of `update()` & the other single value updates are drawn.
+    fn look(&self) -> Look<'_> {
+        Look {
+            scheme: &self.scheme,
+            thresholds: self.thresholds.as_ref(),
+            style: self.style,
+            palette: self.palette.as_deref(),
+        }
+    }
+
+    // Clear the display buffer & write it to the device.
+    fn clear_display(&mut self) -> Result<(), Error<E>> {
+        self.last_frame = None;
         self.device.clear_display_buffer();
-        self.device.write_display_buffer()
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
     }
 
     /// Update the Bargraph display, showing `range` total values with all values
@@ -187,30 +644,123 @@ where
     ///
     /// # }
     /// ```
-    pub fn update(&mut self, value: u8, range: u8, show: bool) -> Result<(), E> {
+    pub fn update(&mut self, value: u8, range: u8, show: bool) -> Result<(), Error<E>> {
+        span!("update", value, range, blink = value > range);
         trace!(self.logger, "update");
 
-        // Reset the display in preparation for the update.
-        self.device.clear_display_buffer();
+        if value > range {
+            log_warn!(self.logger, "Value is greater than range, setting display to blink";
+                      value => value, range => range);
+        }
 
-        let mut blink = false;
-        let mut clamped_value = value;
+        let look = self.look();
+        let frame = Frame::within(self.resolution, |bars| {
+            render::fill_look(bars, value, range, &look)
+        });
+        self.render_values(frame)?;
 
-        if value > range {
-            warn!(self.logger, "Value is greater than range, setting display to blink";
-                  "value" => value, "range" => range);
-            clamped_value = range;
-            blink = true;
+        if show {
+            self.show()?;
         }
 
-        for current_value in 1..=range {
-            let fill = current_value <= clamped_value;
-            self.update_value(current_value - 1, range, fill);
+        Ok(())
+    }
+
+    /// Update the Bargraph display like [update()](#method.update), treating
+    /// out-of-range arguments as errors instead of blinking or panicking.
+    ///
+    /// Returns [Error::InvalidArgument](enum.Error.html#variant.InvalidArgument),
+    /// without changing the display, if `range` is `0` or greater than
+    /// the [resolution()](#method.resolution), or if `value` is greater than `range`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, Error};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.try_update(5, 6, false).unwrap();
+    ///
+    /// match bargraph.try_update(7, 6, false) {
+    ///     Err(Error::InvalidArgument { name, .. }) => assert_eq!("value", name),
+    ///     _ => panic!("Expected an invalid value"),
+    /// }
+    ///
+    /// # }
+    /// ```
+    pub fn try_update(&mut self, value: u8, range: u8, show: bool) -> Result<(), Error<E>> {
+        Error::check_argument("range", range, 1, self.resolution)?;
+        Error::check_argument("value", value, 0, range)?;
+
+        self.update(value, range, show)
+    }
+
+    /// Update the Bargraph display with a value against a range that needn't be
+    /// whole, e.g. a sensor reading of `3.7` of `5.0`, filling its fraction of the
+    /// [resolution()](#method.resolution) rounded to the nearest bar, see
+    /// [update_sample()](#method.update_sample).
+    ///
+    /// Negative values are drawn empty, values more than half a bar over the range fill
+    /// the display & blink. With [set_half_steps()](#method.set_half_steps) the value
+    /// is rounded to the nearest half bar instead, & overflows a quarter bar over.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to display.
+    /// * `range` - The value of a full display.
+    /// * `show` - Whether to also show the display on-screen.
+    ///
+    /// Returns [Error::InvalidArgument](enum.Error.html#variant.InvalidArgument),
+    /// without changing the display, if `range` isn't a positive, finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update_f32(3.7, 5.0, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn update_f32(&mut self, value: f32, range: f32, show: bool) -> Result<(), Error<E>> {
+        Error::check_argument("range", range, f32::MIN_POSITIVE, f32::MAX)?;
+
+        let resolution = self.resolution;
+        if !self.half_steps {
+            return self.update_sample(&Sample::new(value / range), resolution, None, show);
         }
 
-        self.device.write_display_buffer()?;
+        trace!(self.logger, "update_f32"; "value" => value, "range" => range);
+
+        // Half bars up to a whole bar past the display, which blinks.
+        let halves = (value / range * f32::from(resolution) * 2.0)
+            .max(0.0)
+            .round()
+            .min(f32::from(resolution) * 2.0 + 2.0) as u8;
+
+        let look = self.look();
+        let frame = Frame::within(resolution, |bars| {
+            render::fill_half_steps(bars, halves, &look)
+        });
+        if frame.blink {
+            log_warn!(self.logger, "Value is greater than range, setting display to blink";
+                      value => value, range => range);
+        }
 
-        self.set_blink(blink)?;
+        self.render_values(frame)?;
 
         if show {
             self.show()?;
@@ -219,11 +769,13 @@ where
         Ok(())
     }
 
-    /// Enable/Disable continuous blinking of the Bargraph display.
+    /// Update the Bargraph display with a percentage, from `0.0` (empty) to `100.0`
+    /// (full), see [update_f32()](#method.update_f32).
     ///
     /// # Arguments
     ///
-    /// * `enabled` - Whether to enabled blinking or not.
+    /// * `percent` - The percentage to display.
+    /// * `show` - Whether to also show the display on-screen.
     ///
     /// # Examples
     ///
@@ -237,22 +789,23 @@ where
     /// # let address: u8 = 0;
     ///
     /// let mut bargraph = Bargraph::new(i2c, address, None);
-    /// bargraph.set_blink(true).unwrap();
+    /// bargraph.update_percent(62.5, false).unwrap();
     ///
     /// # }
     /// ```
-    pub fn set_blink(&mut self, enabled: bool) -> Result<(), E> {
-        // TODO Add support for different blink speeds.
-        trace!(self.logger, "set_blink"; "enabled" => enabled);
-
-        if enabled {
-            self.device.set_display(Display::ONE_HZ)
-        } else {
-            self.device.set_display(Display::ON)
-        }
+    pub fn update_percent(&mut self, percent: f32, show: bool) -> Result<(), Error<E>> {
+        self.update_f32(percent, 100.0, show)
     }
 
-    /// Show the current bargraph display on-screen.
+    /// Update the Bargraph display like [update()](#method.update), coloring the
+    /// filled values by how full the display is, see
+    /// [Frame::from_colored_value()](struct.Frame.html#method.from_colored_value).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How many values to fill, starting from `0`.
+    /// * `range` - Total number of values to display.
+    /// * `colors` - The color of each percentage of the range.
     ///
     /// # Examples
     ///
@@ -260,299 +813,2650 @@ where
     /// # extern crate ht16k33;
     /// # extern crate led_bargraph;
     /// # use ht16k33::i2c_mock::I2cMock;
-    /// # use led_bargraph::Bargraph;
+    /// # use led_bargraph::{Bargraph, ColorMap};
     /// # fn main() {
     /// # let mut i2c = I2cMock::new(None);
     /// # let address: u8 = 0;
     ///
+    /// let colors: ColorMap = "0:green,70:yellow,90:red".parse().unwrap();
+    ///
     /// let mut bargraph = Bargraph::new(i2c, address, None);
-    /// bargraph.show().unwrap();
+    /// bargraph.update_colored(8, 10, &colors, false).unwrap();
     ///
     /// # }
     /// ```
-    pub fn show(&mut self) -> Result<(), E> {
-        trace!(self.logger, "show");
+    pub fn update_colored(
+        &mut self,
+        value: u8,
+        range: u8,
+        colors: &ColorMap,
+        show: bool,
+    ) -> Result<(), Error<E>> {
+        span!("update_colored", value, range, blink = value > range);
+        trace!(self.logger, "update_colored"; "colors" => ?colors);
 
-        // Read & retrieve the buffer values from the device.
-        self.device.read_display_buffer()?;
-        let &buffer = self.device.display_buffer();
+        if value > range {
+            log_warn!(self.logger, "Value is greater than range, setting display to blink";
+                      value => value, range => range);
+        }
 
-        let display = self.device.display();
+        self.render_values(Frame::within(self.resolution, |bars| {
+            render::fill_colored(bars, value, range, colors)
+        }))?;
 
-        // Convert the buffer values for display as LEDs.
-        let mut leds = [LedColor::Off; BARGRAPH_RESOLUTION as usize];
+        if show {
+            self.show()?;
+        }
 
-        // The Adafruit bargraph only utilizes the first 6 rows:
-        //
-        // 6 rows x 8 commons == 48 LEDs == 24 bars * 2 colors
-        //
-        // As each row represents 8 of the 48 LEDs, many of the indexes will empty. Need to merge
-        // each row together to get the complete display. When merging, if both red & green LEDs
-        // are enabled, then update them to be yellow.
-        for (row, common) in buffer.iter().enumerate().take(6) {
-            if *display == Display::OFF {
-                trace!(
-                    self.logger,
-                    "Display is off, don't attempt retrieve/merge the LED bars"
-                );
-                break;
-            }
+        Ok(())
+    }
 
-            let bars = self.row_common_to_bars(row as u8, common.bits());
-
-            for index in 0..bars.len() {
-                if let Some(color) = bars[index] {
-                    match leds[index] {
-                        LedColor::Green => {
-                            if color == LedColor::Red {
-                                leds[index] = LedColor::Yellow;
-                            }
-                        }
-                        LedColor::Red => {
-                            if color == LedColor::Green {
-                                leds[index] = LedColor::Yellow;
-                            }
-                        }
-                        LedColor::Off => {
-                            leds[index] = color;
-                        }
-                        LedColor::Yellow => {
-                            // Do nothing.
-                        }
-                    }
-                }
-            }
+    /// Update the Bargraph display with a signed value growing from the center of the
+    /// display, up for positive values & down for negative values, see
+    /// [Frame::from_signed_value()](struct.Frame.html#method.from_signed_value).
+    ///
+    /// Each half of the display is drawn like [update()](#method.update) from the
+    /// center, in the [style](#method.set_style) & with the
+    /// [thresholds](#method.set_thresholds) of the value's magnitude, or the
+    /// [palette](#method.set_palette) with the bars numbered from the center. If the
+    /// value is outside of the range then the display blinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How many values to fill, up (positive) or down (negative) from the
+    ///   center.
+    /// * `range` - Total number of values to display each side of the center.
+    /// * `show` - Whether to also show the display on-screen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than half of the
+    /// [resolution()](#method.resolution).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // The audio balance, slightly to the left.
+    /// bargraph.update_signed(-2, 12, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn update_signed(&mut self, value: i8, range: u8, show: bool) -> Result<(), Error<E>> {
+        span!("update_signed", value, range);
+        trace!(self.logger, "update_signed");
+
+        let resolution = self.resolution;
+        let look = self.look();
+        let frame = Frame::within(resolution, |bars| {
+            render::fill_signed(bars, value, range, &look)
+        });
+        if frame.blink {
+            log_warn!(self.logger, "Value is outside of the range, setting display to blink";
+                      value => value, range => range);
         }
-        debug!(self.logger, "bars"; "colors" => format!("{:#?}", leds));
 
-        // Display the LEDs.
-        self.display_ascii_bargraph(&leds, *display);
+        self.render_values(frame)?;
+
+        if show {
+            self.show()?;
+        }
 
         Ok(())
     }
 
-    // Enable/disable the fill for a `value` on the Bargraph display.
-    //
-    // # Arguments
-    //
-    // * `value` - Which value to fill.
-    // * `range` - The total range of the display (for calculating the value size).
-    // * `fill` - Whether to fill (true) the value or only display its header.
-    //
-    // # Notes
-    //
-    // Value `0` is at the bottom of the display (lowest value).
-    fn update_value(&mut self, value: u8, range: u8, fill: bool) {
-        trace!(self.logger, "update_value"; "value" => value, "range" => range, "fill" => fill);
-
-        // Calculate the size of the value.
-        let value_size = BARGRAPH_RESOLUTION / range;
+    /// Update the Bargraph display with two values against the same range, drawing
+    /// one with the red LEDs & the other with the green LEDs, see
+    /// [Frame::from_channels()](struct.Frame.html#method.from_channels).
+    ///
+    /// The colors are the channels themselves, so the color scheme,
+    /// [style](#method.set_style), [thresholds](#method.set_thresholds) &
+    /// [palette](#method.set_palette) don't apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `red_value` - The value to display in red.
+    /// * `green_value` - The value to display in green.
+    /// * `range` - The range of the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update_channels(7, 3, 12, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn update_channels(
+        &mut self,
+        red_value: u8,
+        green_value: u8,
+        range: u8,
+        show: bool,
+    ) -> Result<(), Error<E>> {
+        span!("update_channels", red_value, green_value, range);
+        trace!(self.logger, "update_channels");
+
+        let frame = Frame::within(self.resolution, |bars| {
+            render::fill_channels(bars, red_value, green_value, range)
+        });
+        if frame.blink {
+            log_warn!(self.logger, "A value is greater than the range, setting display to blink";
+                      red_value => red_value, green_value => green_value, range => range);
+        }
 
-        let start_bar = value * value_size;
-        let end_bar = start_bar + value_size - 1;
+        self.render_values(frame)?;
 
-        // Fill in the value.
-        for current_bar in start_bar..end_bar {
-            let fill_color = if fill {
-                LedColor::Yellow
-            } else {
-                LedColor::Off
-            };
-            self.update_bar(current_bar, fill_color);
+        if show {
+            self.show()?;
         }
 
-        // Color the "top" bar of the value.
-        let fill_color = if fill { LedColor::Red } else { LedColor::Green };
-        self.update_bar(end_bar, fill_color);
+        Ok(())
     }
 
-    // Set the bar to the desired color.
-    //
+    /// Set the color of each bar from the bottom of the display, for patterns that
+    /// aren't a value within a range, see [Frame](struct.Frame.html).
+    ///
+    /// Bars without a color are turned off & the display stops blinking.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - The color of each bar, starting from the bottom.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // Alternate red & green bars.
+    /// let colors = [LedColor::Red, LedColor::Green].iter().cycle().cloned();
+    /// bargraph.set_from_iter(colors).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_from_iter<I>(&mut self, colors: I) -> Result<(), Error<E>>
+    where
+        I: IntoIterator<Item = LedColor>,
+    {
+        trace!(self.logger, "set_from_iter");
+
+        self.submit(colors.into_iter().collect())
+    }
+
+    /// Set the color of a single bar, keeping the other bars of the last frame drawn
+    /// by this Bargraph, e.g. to draw a marker over a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `bar` - The bar, starting from `0` at the bottom of the display.
+    /// * `color` - The color of the bar.
+    ///
+    /// Returns [Error::InvalidArgument](enum.Error.html#variant.InvalidArgument),
+    /// without changing the display, if `bar` isn't one of the bars in use, see
+    /// [resolution()](#method.resolution).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update(6, 12, false).unwrap();
+    ///
+    /// // Mark the target value.
+    /// bargraph.set_bar(19, LedColor::Red).unwrap();
+    ///
+    /// # }
+    /// ```
+    #[allow(clippy::disallowed_names)]
+    pub fn set_bar(&mut self, bar: u8, color: LedColor) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_bar"; "bar" => bar, "color" => %color);
+
+        Error::check_argument("bar", bar, 0, self.resolution - 1)?;
+
+        let mut frame = self.pending.or(self.last_frame).unwrap_or_default();
+        frame.bars[bar as usize] = color;
+
+        self.submit(frame)
+    }
+
+    /// Pin a marker over every frame drawn afterwards, e.g. a threshold or yesterday's
+    /// peak on top of a live value. Markers are kept by [update()](#method.update) &
+    /// the other drawing methods until [clear_markers()](#method.clear_markers), but
+    /// aren't drawn until the next frame. [clear()](#method.clear) clears them from
+    /// the display too, until the next frame.
+    ///
+    /// A marker replaces any marker already at the position. The `HT16K33` can only blink
+    /// the whole display, so a blinking marker blinks every bar. Markers beyond a
+    /// [resolution()](#method.resolution) reduced later aren't drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The bar of the marker, starting from `0` at the bottom of the
+    ///   display.
+    /// * `color` - The color of the marker.
+    /// * `blink` - Whether to blink the display while the marker is drawn.
+    ///
+    /// Returns [Error::InvalidArgument](enum.Error.html#variant.InvalidArgument),
+    /// without adding the marker, if `position` isn't one of the bars in use, see
+    /// [resolution()](#method.resolution).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // Mark the threshold of the values.
+    /// bargraph.add_marker(17, LedColor::Red, false).unwrap();
+    ///
+    /// for value in 3..6 {
+    ///     bargraph.update(value, 12, false).unwrap();
+    /// }
+    ///
+    /// # }
+    /// ```
+    pub fn add_marker(
+        &mut self,
+        position: u8,
+        color: LedColor,
+        blink: bool,
+    ) -> Result<(), Error<E>> {
+        trace!(self.logger, "add_marker";
+               "position" => position, "color" => %color, "blink" => blink);
+
+        Error::check_argument("position", position, 0, self.resolution - 1)?;
+
+        self.markers.retain(|&(marked, _, _)| marked != position);
+        self.markers.push((position, color, blink));
+
+        Ok(())
+    }
+
+    /// Remove all the markers from [add_marker()](#method.add_marker), from the next
+    /// frame drawn.
+    pub fn clear_markers(&mut self) {
+        trace!(self.logger, "clear_markers");
+
+        self.markers.clear();
+    }
+
+    /// Update the Bargraph display with several values side-by-side, see
+    /// [Frame::from_segments()](struct.Frame.html#method.from_segments).
+    ///
+    /// The whole display is written at once, so the segments always change together.
+    ///
+    /// Returns [Error::InvalidArgument](enum.Error.html#variant.InvalidArgument),
+    /// without changing the display, if there are no segments or more segments than
+    /// the [resolution()](#method.resolution), or if a segment's range is `0` or
+    /// greater than its number of bars.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - The `(value, range)` of each segment, starting from the bottom.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update_segments(&[(3, 12), (7, 12)], false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn update_segments(&mut self, segments: &[(u8, u8)], show: bool) -> Result<(), Error<E>> {
+        span!("update_segments", segments = ?segments);
+        trace!(self.logger, "update_segments"; "segments" => ?segments);
+
+        // Saturate, so that too many segments is never mistaken for a few.
+        let count = segments.len().min(usize::from(u8::MAX)) as u8;
+        Error::check_argument("segments", count, 1, self.resolution)?;
+        let segment_size = self.resolution / count;
+        for &(_, range) in segments {
+            Error::check_argument("range", range, 1, segment_size)?;
+        }
+
+        let look = self.look();
+        let frame = Frame::within(self.resolution, |bars| {
+            render::fill_segments(bars, segments, &look)
+        });
+        if frame.blink {
+            log_warn!(self.logger, "A value is greater than its range, setting display to blink";
+                      segments => format!("{:?}", segments));
+        }
+
+        self.render_values(frame)?;
+
+        if show {
+            self.show()?;
+        }
+
+        Ok(())
+    }
+
+    /// Swap which end of the display is bar `0`, for a display that's mounted upside
+    /// down. Everything drawn afterwards, including [show()](#method.show), uses the
+    /// new orientation.
+    ///
+    /// # Arguments
+    ///
+    /// * `flipped` - Whether bar `0` is at the top of the physical display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_flipped(true);
+    ///
+    /// # }
+    /// ```
+    pub fn set_flipped(&mut self, flipped: bool) {
+        trace!(self.logger, "set_flipped"; "flipped" => flipped);
+
+        if flipped != self.flipped {
+            self.flipped = flipped;
+
+            // The displayed frame is now in the other orientation.
+            self.last_frame = None;
+        }
+    }
+
+    /// Set the colors used to draw values by [update()](#method.update) &
+    /// [update_segments()](#method.update_segments).
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - The colors of each value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, ColorScheme, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // A red-only alert bar.
+    /// bargraph.set_color_scheme(ColorScheme {
+    ///     fill: LedColor::Red,
+    ///     head: LedColor::Red,
+    ///     ..ColorScheme::default()
+    /// });
+    /// bargraph.update(24, 24, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
+        trace!(self.logger, "set_color_scheme"; "scheme" => ?scheme);
+
+        self.scheme = scheme;
+    }
+
+    /// Color the values drawn by [update()](#method.update),
+    /// [update_sample()](#method.update_sample), [update_f32()](#method.update_f32),
+    /// [update_segments()](#method.update_segments) & [pipeline()](#method.pipeline)
+    /// green, yellow or red by how full the display is, instead of with the fill & head
+    /// of the color scheme. A [ColorMap](struct.ColorMap.html) passed to them takes
+    /// precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `thresholds` - The warning & critical thresholds, `None` to draw with the
+    ///   color scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, Thresholds};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // A CPU gauge turning red when busy.
+    /// bargraph.set_thresholds(Thresholds {
+    ///     warn: Some(70),
+    ///     crit: Some(90),
+    /// });
+    /// bargraph.update(23, 24, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_thresholds<T>(&mut self, thresholds: T)
+    where
+        T: Into<Option<Thresholds>>,
+    {
+        let thresholds = thresholds.into();
+        trace!(self.logger, "set_thresholds"; "thresholds" => ?thresholds);
+
+        self.thresholds = thresholds;
+    }
+
+    /// Color each bar drawn by [update()](#method.update),
+    /// [update_sample()](#method.update_sample), [update_f32()](#method.update_f32),
+    /// [update_segments()](#method.update_segments) & [pipeline()](#method.pipeline)
+    /// with the palette, instead of with the color scheme & thresholds, e.g. for a
+    /// gradient. A [ColorMap](struct.ColorMap.html) passed to them takes precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette` - The color of each bar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // Green filled bars, with the top half in red.
+    /// bargraph.set_palette(|bar: u8, filled: bool, _range: u8| match filled {
+    ///     true if bar >= 12 => LedColor::Red,
+    ///     true => LedColor::Green,
+    ///     false => LedColor::Off,
+    /// });
+    /// bargraph.update(18, 24, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_palette<P>(&mut self, palette: P)
+    where
+        P: Palette + Send + Sync + 'static,
+    {
+        trace!(self.logger, "set_palette");
+
+        self.palette = Some(Arc::new(palette));
+    }
+
+    /// Set how [update()](#method.update), [update_sample()](#method.update_sample),
+    /// [update_f32()](#method.update_f32), [update_segments()](#method.update_segments)
+    /// & [pipeline()](#method.pipeline) draw values, filling every value up to the
+    /// value (the default) or lighting only the value, see [Style](enum.Style.html). A
+    /// [ColorMap](struct.ColorMap.html) passed to them always fills.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - How values are drawn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, Style};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // A VU meter's needle.
+    /// bargraph.set_style(Style::Dot);
+    /// bargraph.update(17, 24, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_style(&mut self, style: Style) {
+        trace!(self.logger, "set_style"; "style" => ?style);
+
+        self.style = style;
+    }
+
+    /// Show the values of [update_f32()](#method.update_f32) &
+    /// [update_percent()](#method.update_percent) to the nearest half bar, doubling
+    /// the display's resolution: a value between two bars lights the bar above the
+    /// whole bars in the scheme's fill color, yellow by default. The whole bars are
+    /// drawn like [update()](#method.update), with the style, thresholds & palette.
+    /// The half bar takes the fill color of the thresholds' zone, or the palette's
+    /// color for a filled bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `half_steps` - Whether to light half bars, off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_half_steps(true);
+    ///
+    /// // 7.5 bars.
+    /// bargraph.update_f32(7.5, 24.0, false).unwrap();
+    /// assert_eq!(LedColor::Yellow, bargraph.last_frame().unwrap().bars[7]);
+    ///
+    /// # }
+    /// ```
+    pub fn set_half_steps(&mut self, half_steps: bool) {
+        trace!(self.logger, "set_half_steps"; "half_steps" => half_steps);
+
+        self.half_steps = half_steps;
+    }
+
+    /// Stop coloring the bars with the palette of
+    /// [set_palette()](#method.set_palette), drawing with the color scheme again.
+    pub fn clear_palette(&mut self) {
+        trace!(self.logger, "clear_palette");
+
+        self.palette = None;
+    }
+
+    /// Replace the built-in mapping of the Adafruit backpack, for hand-wired displays
+    /// or boards with different routing.
+    ///
+    /// # Arguments
+    ///
+    /// * `mapping` - The row & common of each bar's red LED, starting from the bottom.
+    ///   The green LED is on the next row, see [mapping](mapping/index.html).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, & keeps the current mapping, if a row or common doesn't exist
+    /// on the `HT16K33`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{mapping, Bargraph};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // The first 2 bars are wired the wrong way around.
+    /// let mut table = mapping::builtin_table();
+    /// table.swap(0, 1);
+    /// bargraph.set_mapping(&table).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_mapping(
+        &mut self,
+        mapping: &[(u8, u8); BARGRAPH_RESOLUTION as usize],
+    ) -> Result<(), ValidationError> {
+        trace!(self.logger, "set_mapping"; "mapping" => ?mapping);
+
+        self.locations = bar_locations(mapping)?;
+        self.mapping = *mapping;
+
+        // The displayed frame was drawn with the old mapping.
+        self.last_frame = None;
+
+        Ok(())
+    }
+
+    /// The row & common of each bar's red LED, see [set_mapping()](#method.set_mapping).
+    pub fn mapping(&self) -> &[(u8, u8); BARGRAPH_RESOLUTION as usize] {
+        &self.mapping
+    }
+
+    /// Fill values from the top of the display down, see [Frame::reverse()](struct.Frame.html#method.reverse).
+    ///
+    /// Only the values drawn by the `update` methods are reversed, e.g. not the colors
+    /// set by [set_from_iter()](#method.set_from_iter). Segments are reversed as a whole,
+    /// so the first segment is at the top.
+    ///
+    /// # Arguments
+    ///
+    /// * `reversed` - Whether values fill from the top down.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_reversed(true);
+    ///
+    /// # }
+    /// ```
+    pub fn set_reversed(&mut self, reversed: bool) {
+        trace!(self.logger, "set_reversed"; "reversed" => reversed);
+
+        self.reversed = reversed;
+    }
+
+    /// Set how many bars are in use, starting from the bottom of the display, e.g. for
+    /// a partially populated or custom-built display. The values drawn by the `update`
+    /// methods are scaled to these bars & the bars above are off.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` - The number of bars, from `1` to [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // Only 12 bars are fitted, showing 6 values of 2 bars each.
+    /// bargraph.set_resolution(12);
+    /// bargraph.update(3, 6, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_resolution(&mut self, resolution: u8) {
+        trace!(self.logger, "set_resolution"; "resolution" => resolution);
+
+        assert!(
+            resolution > 0 && resolution <= BARGRAPH_RESOLUTION,
+            "Resolution [{}] must be between [1] and [{}]",
+            resolution,
+            BARGRAPH_RESOLUTION
+        );
+
+        if resolution != self.resolution {
+            self.resolution = resolution;
+
+            // The displayed frame was drawn for the other resolution.
+            self.last_frame = None;
+        }
+    }
+
+    /// The number of bars in use, see [set_resolution()](#method.set_resolution).
+    pub fn resolution(&self) -> u8 {
+        self.resolution
+    }
+
+    /// Skip writing a frame to the device when it's the same as the frame already
+    /// displayed, so repeatedly updating with the same value doesn't cause any I2C
+    /// traffic.
+    ///
+    /// Only frames written by this Bargraph are known, so the first frame after
+    /// creating, initializing or clearing the display is always written.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to skip unchanged frames or not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_skip_unchanged(true);
+    ///
+    /// bargraph.update(5, 6, false).unwrap();
+    /// assert!(bargraph.changed_since_last_flush());
+    ///
+    /// // Nothing is written.
+    /// bargraph.update(5, 6, false).unwrap();
+    /// assert!(!bargraph.changed_since_last_flush());
+    ///
+    /// # }
+    /// ```
+    pub fn set_skip_unchanged(&mut self, enabled: bool) {
+        trace!(self.logger, "set_skip_unchanged"; "enabled" => enabled);
+
+        self.skip_unchanged = enabled;
+    }
+
+    /// Whether the last rendered frame was different to the frame already displayed,
+    /// see [set_skip_unchanged()](#method.set_skip_unchanged).
+    ///
+    /// It's `false` until a frame is rendered & `true` whenever the displayed frame
+    /// is unknown.
+    pub fn changed_since_last_flush(&self) -> bool {
+        self.changed
+    }
+
+    /// The frame last written to the display, `None` if it's unknown, e.g. after
+    /// initializing or clearing the display, or a failed write.
+    pub fn last_frame(&self) -> Option<Frame> {
+        self.last_frame
+    }
+
+    /// Assume the display is already showing the frame, including its blink state,
+    /// e.g. one written by an earlier process, so that
+    /// [set_skip_unchanged()](#method.set_skip_unchanged) skips writing it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame the display is showing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, Frame};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_skip_unchanged(true);
+    /// bargraph.assume_frame(Frame::from_value(5, 6));
+    ///
+    /// // Nothing is written.
+    /// bargraph.update(5, 6, false).unwrap();
+    /// assert!(!bargraph.changed_since_last_flush());
+    ///
+    /// # }
+    /// ```
+    pub fn assume_frame(&mut self, frame: Frame) {
+        trace!(self.logger, "assume_frame"; "frame" => %frame, "blink" => frame.blink);
+
+        self.last_frame = Some(frame);
+        self.blink = Some(frame.blink);
+    }
+
+    /// Draw off-screen, keeping the frames of the `update` methods,
+    /// [set_from_iter()](#method.set_from_iter), [set_bar()](#method.set_bar) &
+    /// [clear()](#method.clear) without writing to the device until
+    /// [present()](#method.present) is called.
+    ///
+    /// A display composed in several steps is then never seen half-drawn. Each step
+    /// draws over the frame of the previous step.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether frames are kept until `present()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, LedColor};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_deferred(true);
+    ///
+    /// bargraph.update(6, 12, false).unwrap();
+    /// bargraph.set_bar(19, LedColor::Red).unwrap();
+    ///
+    /// // Both steps are displayed together.
+    /// bargraph.present().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_deferred(&mut self, enabled: bool) {
+        trace!(self.logger, "set_deferred"; "enabled" => enabled);
+
+        self.deferred = enabled;
+    }
+
+    /// Write the frame kept by [set_deferred()](#method.set_deferred) to the device, if
+    /// there is one.
+    ///
+    /// The frame is kept if it fails, so presenting can be retried.
+    pub fn present(&mut self) -> Result<(), Error<E>> {
+        span!("present");
+        trace!(self.logger, "present");
+
+        if let Some(frame) = self.pending {
+            self.render(&frame)?;
+            self.pending = None;
+        }
+
+        Ok(())
+    }
+
+    /// Recover the I2C bus after repeated failures to write the display, e.g. when
+    /// the device is holding the bus after being interrupted mid-transaction.
+    ///
+    /// Once `failures` frames in a row fail to render, `recover` is called &
+    /// the display is re-initialized. The frame's error is still returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `failures` - The consecutive failures before recovering, at least `1`.
+    /// * `recover` - Releases the bus, e.g. with [recover_bus()](fn.recover_bus.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failures` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_bus_recovery(3, || {
+    ///     // Switch SCL to a GPIO pin & call `recover_bus()`.
+    /// });
+    ///
+    /// # }
+    /// ```
+    pub fn set_bus_recovery<F>(&mut self, failures: u32, recover: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        trace!(self.logger, "set_bus_recovery"; "failures" => failures);
+
+        assert!(failures > 0, "Failures [{}] must be at least [1]", failures);
+
+        self.bus_recovery = Some((failures, Box::new(recover)));
+        self.failures = 0;
+    }
+
+    /// Gate the backpack's power through a GPIO pin, driven high to power it.
+    ///
+    /// Enables [power_cycle()](#method.power_cycle) for hard resets, &
+    /// [power_off()](#method.power_off) for standby without any power draw.
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - The pin switching the backpack's power supply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate embedded_hal;
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use embedded_hal::digital::v2::OutputPin;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # struct Pin;
+    /// # impl OutputPin for Pin {
+    /// #     type Error = ();
+    /// #     fn set_low(&mut self) -> Result<(), ()> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), ()> { Ok(()) }
+    /// # }
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    /// # let pin = Pin;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_power_pin(pin);
+    ///
+    /// // Reset the backpack & re-initialize the display.
+    /// bargraph.power_cycle().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_power_pin<P>(&mut self, mut pin: P)
+    where
+        P: OutputPin + Send + 'static,
+        P::Error: Debug,
+    {
+        trace!(self.logger, "set_power_pin");
+
+        self.power = Some(Box::new(move |on| {
+            let result = if on { pin.set_high() } else { pin.set_low() };
+            result.map_err(|e| format!("{:?}", e))
+        }));
+    }
+
+    /// Switch off the backpack's power, see [set_power_pin()](#method.set_power_pin).
+    ///
+    /// Switch it back on with [power_on()](#method.power_on).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no power pin.
+    pub fn power_off(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "power_off");
+
+        // The device forgets everything when it's unpowered.
+        self.blink = None;
+        self.last_frame = None;
+        self.switch_power(false)
+    }
+
+    /// Switch on the backpack's power & initialize the display, see
+    /// [set_power_pin()](#method.set_power_pin).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no power pin.
+    pub fn power_on(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "power_on");
+
+        self.switch_power(true)?;
+        thread::sleep(POWER_ON_DURATION);
+
+        self.initialize()
+    }
+
+    /// Reset the backpack by switching its power off & on, then initialize the
+    /// display, see [set_power_pin()](#method.set_power_pin).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no power pin.
+    pub fn power_cycle(&mut self) -> Result<(), Error<E>> {
+        span!("power_cycle");
+        trace!(self.logger, "power_cycle");
+
+        self.power_off()?;
+        thread::sleep(POWER_OFF_DURATION);
+
+        self.power_on()
+    }
+
+    // Drive the power pin.
+    fn switch_power(&mut self, on: bool) -> Result<(), Error<E>> {
+        let power = self
+            .power
+            .as_mut()
+            .expect("A power pin must be set, see `set_power_pin()`");
+
+        power(on).map_err(|source| Error::Power { on, source })
+    }
+
+    // Write the display buffer & the blink state of the frame to the device.
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), Error<E>> {
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))?;
+
+        // Avoid an extra I2C write for every update when the blink state is unchanged.
+        if self.blink != Some(frame.blink) {
+            self.set_blink(frame.blink)?;
+        }
+
+        Ok(())
+    }
+
+    // Count the consecutive failures to write a frame, recovering the bus when there
+    // are too many.
+    fn count_failure(&mut self, failed: bool) {
+        if !failed {
+            self.failures = 0;
+            return;
+        }
+
+        self.failures += 1;
+
+        let threshold = match self.bus_recovery {
+            Some((threshold, _)) => threshold,
+            None => return,
+        };
+
+        if self.failures < threshold {
+            return;
+        }
+
+        warn!(self.logger, "Recovering the I2C bus"; "failures" => self.failures);
+        self.failures = 0;
+
+        if let Some((_, ref mut recover)) = self.bus_recovery {
+            recover();
+        }
+
+        self.metrics.lock().unwrap().bus_recoveries += 1;
+
+        if let Err(e) = self.initialize() {
+            // The error itself can't be logged without `E: Debug`.
+            error!(self.logger, "Failed to re-initialize after recovering the I2C bus";
+                   "operation" => ?e.operation());
+        }
+    }
+
+    /// Enable/Disable continuous blinking of the Bargraph display.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to enabled blinking or not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_blink(true).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        // TODO Add support for different blink speeds.
+        trace!(self.logger, "set_blink"; "enabled" => enabled);
+
+        let display = if enabled {
+            Display::ONE_HZ
+        } else {
+            Display::ON
+        };
+
+        #[cfg(feature = "preview")]
+        let was_blinking = self.blink == Some(true);
+
+        // Forget the blink state if it fails, it's unknown what the device is doing.
+        self.blink = None;
+        self.last_frame = None;
+        self.device
+            .set_display(display)
+            .map_err(Error::i2c(Operation::SetBlink, Display::COMMAND.bits()))?;
+        self.blink = Some(enabled);
+        self.display_known = true;
+
+        #[cfg(feature = "preview")]
+        {
+            if enabled && !was_blinking {
+                self.blink_started = Instant::now();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the display buffer to the Bargraph display.
+    ///
+    /// [update()](#method.update) already writes the display, this is only needed
+    /// when driving the Bargraph through the [Ht16k33Display](trait.Ht16k33Display.html) trait.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Result<(), Error<E>> {
+        span!("flush");
+        trace!(self.logger, "flush");
+
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Set the brightness of the Bargraph display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_brightness(8).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_brightness"; "brightness" => brightness);
+
+        assert!(
+            brightness <= BRIGHTNESS_MAX,
+            "Brightness [{}] must be less than or equal to [{}]",
+            brightness,
+            BRIGHTNESS_MAX
+        );
+
+        self.fading_in = false;
+        self.write_brightness(brightness)
+    }
+
+    // Write the brightness to the device.
+    fn write_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        self.device
+            .set_dimming(Dimming::from_bits_truncate(brightness))
+            .map_err(Error::i2c(
+                Operation::SetBrightness,
+                Dimming::COMMAND.bits(),
+            ))
+    }
+
+    // Step the brightness from the current brightness to `brightness` over the duration.
+    fn fade(&mut self, brightness: u8, duration: Duration) -> Result<(), Error<E>> {
+        let current = self.brightness();
+        let levels: Vec<u8> = if current <= brightness {
+            (current + 1..=brightness).collect()
+        } else {
+            (brightness..current).rev().collect()
+        };
+
+        let steps = levels.len().max(1) as u32;
+        for level in levels {
+            thread::sleep(duration / steps);
+            self.write_brightness(level)?;
+        }
+
+        Ok(())
+    }
+
+    /// The blink rate last written to the display.
+    ///
+    /// The `HT16K33` can't be read back, so this is the state cached by the driver.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, BlinkRate};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_blink(true).unwrap();
+    ///
+    /// assert_eq!(BlinkRate::OneHz, bargraph.blink_rate());
+    ///
+    /// # }
+    /// ```
+    pub fn blink_rate(&self) -> BlinkRate {
+        display::blink_rate(*self.device.display())
+    }
+
+    /// The brightness last written to the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// The `HT16K33` can't be read back, so this is the state cached by the driver.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_brightness(3).unwrap();
+    ///
+    /// assert_eq!(3, bargraph.brightness());
+    ///
+    /// # }
+    /// ```
+    pub fn brightness(&self) -> u8 {
+        // The brightness is the low bits, the high bits are the dimming command.
+        self.device.dimming().bits() & BRIGHTNESS_MAX
+    }
+
+    /// Destroy the Bargraph, returning the I2C device.
+    ///
+    /// The display is left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// i2c = bargraph.destroy();
+    ///
+    /// # }
+    /// ```
+    pub fn destroy(self) -> I2C {
+        trace!(self.logger, "destroy");
+
+        self.device.destroy().into_inner()
+    }
+
+    /// Retrieve the current metrics, counted since the Bargraph was created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update(5, 6, false).unwrap();
+    ///
+    /// let metrics = bargraph.metrics();
+    /// assert_eq!(1, metrics.frames);
+    ///
+    /// # }
+    /// ```
+    pub fn metrics(&self) -> Metrics {
+        *self.metrics.lock().unwrap()
+    }
+
+    /// Emulate blinking in [show()](#method.show), for terminals that ignore the ANSI
+    /// blink attribute.
+    ///
+    /// The bars are shown on or off depending on where the display is in its blink
+    /// cycle, so calling [show()](#method.show) repeatedly animates the blinking at the
+    /// device's blink rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to emulate blinking or not.
+    #[cfg(feature = "preview")]
+    pub fn set_emulate_blink(&mut self, enabled: bool) {
+        trace!(self.logger, "set_emulate_blink"; "enabled" => enabled);
+
+        self.emulate_blink = enabled;
+    }
+
+    /// Override what the terminal of [show()](#method.show) supports, rather than
+    /// detecting it each time, e.g. to force ASCII for a serial console.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - What the terminal supports, `None` to detect it.
+    #[cfg(feature = "preview")]
+    pub fn set_terminal<T>(&mut self, terminal: T)
+    where
+        T: Into<Option<TerminalSupport>>,
+    {
+        self.terminal = terminal.into();
+        trace!(self.logger, "set_terminal"; "terminal" => ?self.terminal);
+    }
+
+    /// Show the current bargraph display on-screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.show().unwrap();
+    ///
+    /// # }
+    /// ```
+    #[cfg(feature = "preview")]
+    pub fn show(&mut self) -> Result<(), Error<E>> {
+        span!("show");
+        trace!(self.logger, "show");
+
+        let (leds, display) = self.read_leds()?;
+
+        // Only the bars in use are shown.
+        let bars = &leds[..self.resolution as usize];
+        debug!(self.logger, "bars"; "colors" => format!("{:#?}", bars));
+
+        // Display the LEDs.
+        if self.emulate_blink {
+            let elapsed = self.blink_started.elapsed();
+            preview::print_leds_at(
+                &[bars],
+                BARGRAPH_DISPLAY_CHAR,
+                display,
+                elapsed,
+                self.terminal,
+            );
+        } else {
+            preview::print_leds(&[bars], BARGRAPH_DISPLAY_CHAR, display, self.terminal);
+        }
+
+        Ok(())
+    }
+
+    // Without the `preview` feature there's nowhere to show the display, so the `show`
+    // of an update is ignored.
+    #[cfg(not(feature = "preview"))]
+    fn show(&mut self) -> Result<(), Error<E>> {
+        Ok(())
+    }
+
+    /// Read the bars in use back from the device, from the bottom of the display, e.g.
+    /// to check what another process displayed.
+    ///
+    /// Whether the display blinks can't be read back, so the frame only blinks if this
+    /// Bargraph set it blinking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// let frame = bargraph.read_frame().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn read_frame(&mut self) -> Result<Frame, Error<E>> {
+        trace!(self.logger, "read_frame");
+
+        let (leds, _) = self.read_leds()?;
+        let resolution = self.resolution as usize;
+
+        let mut frame = Frame::default();
+        frame.bars[..resolution].copy_from_slice(&leds[..resolution]);
+        frame.blink = self.blink == Some(true);
+
+        Ok(frame)
+    }
+
+    // Read the color of each bar back from the device, with the display setup it's
+    // shown with.
+    fn read_leds(
+        &mut self,
+    ) -> Result<([LedColor; BARGRAPH_RESOLUTION as usize], Display), Error<E>> {
+        // Read & retrieve the buffer values from the device.
+        self.device.read_display_buffer().map_err(Error::i2c(
+            Operation::ReadDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))?;
+        let &buffer = self.device.display_buffer();
+
+        // A display this Bargraph hasn't set up, e.g. one that wasn't initialized, is
+        // shown as it's read back without blinking.
+        let display = if self.display_known {
+            *self.device.display()
+        } else {
+            Display::ON
+        };
+
+        // Convert the buffer values for display as LEDs, looking up each bar's red & green
+        // LEDs in the buffer. If both LEDs are enabled, then the bar is yellow.
+        let mut leds = [LedColor::Off; BARGRAPH_RESOLUTION as usize];
+
+        if display == Display::OFF {
+            trace!(
+                self.logger,
+                "Display is off, don't attempt retrieve/merge the LED bars"
+            );
+        } else {
+            let enabled =
+                |location: LedLocation| buffer[location.row_as_index()].contains(location.common);
+
+            for (bar, led) in leds.iter_mut().enumerate() {
+                let (red_led, green_led) = self.bar_location(bar);
+                *led = match (enabled(red_led), enabled(green_led)) {
+                    (true, true) => LedColor::Yellow,
+                    (true, false) => LedColor::Red,
+                    (false, true) => LedColor::Green,
+                    (false, false) => LedColor::Off,
+                };
+            }
+        }
+
+        Ok((leds, display))
+    }
+
+    // Render a frame of values, filling from the top of the display if reversed.
+    fn render_values(&mut self, mut frame: Frame) -> Result<(), Error<E>> {
+        if self.reversed {
+            frame.bars[..self.resolution as usize].reverse();
+        }
+
+        self.submit(frame)
+    }
+
+    // Render the frame with the markers over it, or keep it for `present()` if drawing
+    // off-screen.
+    fn submit(&mut self, mut frame: Frame) -> Result<(), Error<E>> {
+        let resolution = self.resolution;
+        for &(position, color, blink) in self.markers.iter() {
+            if position >= resolution {
+                continue;
+            }
+
+            frame.bars[position as usize] = color;
+            frame.blink |= blink;
+        }
+
+        if self.deferred {
+            self.pending = Some(frame);
+            return Ok(());
+        }
+
+        self.render(&frame)
+    }
+
+    // The red & green LEDs of the bar, from the top of the bars in use if it's flipped.
+    fn bar_location(&self, bar: usize) -> (LedLocation, LedLocation) {
+        let resolution = self.resolution as usize;
+
+        if self.flipped && bar < resolution {
+            self.locations[resolution - 1 - bar]
+        } else {
+            self.locations[bar]
+        }
+    }
+
+    // Set the bar to the desired color.
+    //
     // The buffer must be later written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
     // for the change to be displayed.
     //
+    // This is called for every bar of every update, so it doesn't log.
+    //
     // # Arguments
     //
     // * `bar- A value from `0` to `23`.
     // * `color` - A valid color.
-    #[allow(clippy::blacklisted_name)]
+    #[allow(clippy::disallowed_names)]
     fn update_bar(&mut self, bar: u8, color: LedColor) {
-        trace!(self.logger, "update_bar"; "bar" => bar, "color" => format!("{:?}", color));
+        let (red_led, green_led) = self.bar_location(bar as usize);
+
+        let red_enabled = color == LedColor::Red || color == LedColor::Yellow;
+        let green_enabled = color == LedColor::Green || color == LedColor::Yellow;
+
+        self.device.update_display_buffer(red_led, red_enabled);
+        self.device.update_display_buffer(green_led, green_enabled);
+    }
+}
+
+// Validate the red & green LED locations of each bar of the mapping. They're needed for
+// every update, so are only validated once & the LED path can't fail.
+fn bar_locations(
+    mapping: &[(u8, u8); BARGRAPH_RESOLUTION as usize],
+) -> Result<[(LedLocation, LedLocation); BARGRAPH_RESOLUTION as usize], ValidationError> {
+    let mut locations =
+        [(LedLocation::default(), LedLocation::default()); BARGRAPH_RESOLUTION as usize];
+
+    for (location, &(row, common)) in locations.iter_mut().zip(mapping.iter()) {
+        *location = (
+            LedLocation::new(row, common)?,
+            LedLocation::new(row.saturating_add(1), common)?,
+        );
+    }
+
+    Ok(locations)
+}
+
+impl<I2C, E> Render for Bargraph<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), Error<E>> {
+        span!("render", blink = frame.blink);
+        trace!(self.logger, "render"; "frame" => %frame, "blink" => frame.blink);
+
+        self.changed = self.last_frame != Some(*frame);
+        if !self.changed && self.skip_unchanged {
+            trace!(self.logger, "Skipping unchanged frame");
+            return Ok(());
+        }
+
+        let start = Instant::now();
+
+        // Every LED is set, so there's no need to clear the display buffer first.
+        for (bar, color) in frame.bars.iter().enumerate() {
+            self.update_bar(bar as u8, *color);
+        }
+
+        // Forget the frame if it fails, it's unknown what the device is displaying.
+        self.last_frame = None;
+        let result = self.write_frame(frame);
+        self.count_failure(result.is_err());
+        result?;
+
+        if self.fading_in {
+            self.fading_in = false;
+            let (duration, brightness) = self.fade_in;
+            self.fade(brightness, duration)?;
+        }
+
+        self.last_frame = Some(*frame);
+
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.frames += 1;
+        metrics.last_update_latency = Some(start.elapsed());
+
+        Ok(())
+    }
+}
+
+impl<I2C, E> Ht16k33Display for Bargraph<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn initialize(&mut self) -> Result<(), Error<E>> {
+        Bargraph::initialize(self)
+    }
+
+    fn clear(&mut self) -> Result<(), Error<E>> {
+        Bargraph::clear(self)
+    }
+
+    fn flush(&mut self) -> Result<(), Error<E>> {
+        Bargraph::flush(self)
+    }
+
+    fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        Bargraph::set_blink(self, enabled)
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        Bargraph::set_brightness(self, brightness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    #[test]
+    fn led_color_round_trip() {
+        for color in [
+            LedColor::Off,
+            LedColor::Green,
+            LedColor::Red,
+            LedColor::Yellow,
+        ]
+        .iter()
+        {
+            assert_eq!(Ok(*color), color.to_string().parse());
+            assert_eq!(Ok(*color), color.to_string().to_uppercase().parse());
+            assert_eq!(Ok(*color), color.to_string()[..1].parse());
+        }
+    }
+
+    #[test]
+    fn led_color_invalid() {
+        for s in ["", "blue", "gr", "off "].iter() {
+            assert!(s.parse::<LedColor>().is_err(), "{}", s);
+        }
+
+        assert_eq!(
+            "Invalid color [blue], expected off, green, red or yellow",
+            "blue".parse::<LedColor>().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn new() {
+        let i2c = I2cMock::new(None);
+        let _bargraph = Bargraph::new(i2c, ADDRESS, None);
+    }
+
+    #[test]
+    fn initialize() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.initialize().unwrap();
+    }
+
+    #[test]
+    fn clear() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.initialize().unwrap();
+
+        bargraph.clear().unwrap();
+    }
+
+    #[test]
+    fn update() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.initialize().unwrap();
+
+        bargraph.update(5, 6, false).unwrap();
+    }
+
+    #[test]
+    fn try_update() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.try_update(6, 6, false).unwrap();
+        assert_eq!(Some(Frame::from_value(6, 6)), bargraph.last_frame);
+
+        for &(value, range) in [(7, 6), (0, 0), (1, 25)].iter() {
+            match bargraph.try_update(value, range, false) {
+                Err(Error::InvalidArgument { .. }) => {}
+                result => panic!("Expected an invalid argument, got {:?}", result),
+            }
+        }
+
+        // The display is unchanged.
+        assert_eq!(Some(Frame::from_value(6, 6)), bargraph.last_frame);
+    }
+
+    #[test]
+    fn update_f32() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+
+        // 3.7 of 5.0 is 8.88 of 12 bars, each value is a single bar.
+        bargraph.update_f32(3.7, 5.0, false).unwrap();
+        assert_eq!(
+            "RRRRRRRRRGGG............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.update_f32(-1.0, 5.0, false).unwrap();
+        assert_eq!(
+            "GGGGGGGGGGGG............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.update_f32(5.5, 5.0, false).unwrap();
+        assert!(bargraph.last_frame().unwrap().blink);
+    }
+
+    #[test]
+    fn update_f32_half_steps() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+        bargraph.set_half_steps(true);
+
+        // 3.7 of 5.0 is 8.88 of 12 bars, nearest to 9 bars.
+        bargraph.update_f32(3.7, 5.0, false).unwrap();
+        assert_eq!(
+            "RRRRRRRRRGGG............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        // 3.5 of 5.0 is 8.4 of 12 bars, nearest to 8.5 bars.
+        bargraph.update_f32(3.5, 5.0, false).unwrap();
+        assert_eq!(
+            "RRRRRRRRYGGG............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.update_f32(5.2, 5.0, false).unwrap();
+        assert!(bargraph.last_frame().unwrap().blink);
+    }
+
+    #[test]
+    fn update_f32_half_steps_style() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+        bargraph.set_half_steps(true);
+
+        // 8.5 bars, the dot of the 8th bar & the half bar above it.
+        bargraph.set_style(Style::Dot);
+        bargraph.update_f32(3.5, 5.0, false).unwrap();
+        assert_eq!(
+            ".......RY...............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        // In the zone of the whole bars.
+        bargraph.set_style(Style::Bar);
+        bargraph.set_thresholds(Thresholds {
+            warn: Some(50),
+            crit: Some(90),
+        });
+        bargraph.update_f32(3.5, 5.0, false).unwrap();
+        assert_eq!(
+            "YYYYYYYYYGGG............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.set_palette(|bar: u8, filled: bool, _range: u8| match filled {
+            true if bar >= 8 => LedColor::Red,
+            true => LedColor::Green,
+            false => LedColor::Off,
+        });
+        bargraph.update_f32(3.5, 5.0, false).unwrap();
+        assert_eq!(
+            "GGGGGGGGR...............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn update_percent() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
 
-        let (row, common) = self.bar_to_row_common(bar);
+        bargraph.update_percent(62.5, false).unwrap();
+        let frame = bargraph.last_frame().unwrap();
+
+        bargraph.update_f32(15.0, 24.0, false).unwrap();
+        assert_eq!(frame, bargraph.last_frame().unwrap());
+        assert!(!frame.blink);
+
+        bargraph.update_percent(110.0, false).unwrap();
+        assert!(bargraph.last_frame().unwrap().blink);
+    }
+
+    #[test]
+    fn update_percent_style() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        // Drawn like `update()`, as a dot.
+        bargraph.set_style(Style::Dot);
+        bargraph.update_percent(50.0, false).unwrap();
+        assert_eq!(
+            "...........R............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.set_palette(|bar: u8, filled: bool, _range: u8| match filled {
+            true if bar >= 12 => LedColor::Red,
+            true => LedColor::Green,
+            false => LedColor::Off,
+        });
+        bargraph.update_percent(50.0, false).unwrap();
+        assert_eq!(
+            "...........G............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.set_style(Style::Bar);
+        bargraph.update_percent(75.0, false).unwrap();
+        assert_eq!(
+            "GGGGGGGGGGGGRRRRRR......",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn update_f32_invalid_range() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.update_f32(3.7, 5.0, false).unwrap();
+        let frame = bargraph.last_frame();
+
+        for &range in [0.0, -5.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY].iter() {
+            match bargraph.update_f32(1.0, range, false) {
+                Err(Error::InvalidArgument { name, .. }) => assert_eq!("range", name),
+                result => panic!("Expected an invalid range, got {:?}", result),
+            }
+            assert_eq!(frame, bargraph.last_frame());
+        }
+    }
+
+    #[test]
+    fn set_blink() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.initialize().unwrap();
+
+        bargraph.set_blink(true).unwrap();
+        bargraph.set_blink(false).unwrap();
+    }
+
+    #[test]
+    fn show() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.initialize().unwrap();
+
+        bargraph.show().unwrap();
+    }
+
+    #[test]
+    fn read_frame() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.initialize().unwrap();
+        bargraph.set_flipped(true);
+        bargraph.set_resolution(12);
+
+        bargraph.update(7, 6, false).unwrap();
+        assert_eq!(bargraph.last_frame(), Some(bargraph.read_frame().unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "preview")]
+    fn show_emulate_blink() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_emulate_blink(true);
+
+        bargraph.update(25, 24, false).unwrap();
+        let blink_started = bargraph.blink_started;
+        bargraph.show().unwrap();
+
+        // Still blinking, the blink cycle continues.
+        bargraph.update(26, 24, false).unwrap();
+        assert_eq!(blink_started, bargraph.blink_started);
+    }
+
+    #[test]
+    fn render() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.render(&Frame::from_value(25, 24)).unwrap();
+
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0xFF, buffer[*row].bits());
+            assert_eq!(0x00, buffer[*row + 1].bits());
+        }
+        assert_eq!(Display::ONE_HZ, *bargraph.device.display());
+    }
 
-        let red_led = ht16k33::LedLocation::new(row, common).unwrap();
-        let green_led = ht16k33::LedLocation::new(row + 1, common).unwrap();
+    #[test]
+    fn set_from_iter() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
 
-        let red_enabled = color == LedColor::Red || color == LedColor::Yellow;
-        let green_enabled = color == LedColor::Green || color == LedColor::Yellow;
+        bargraph.update(25, 24, false).unwrap();
+        bargraph.set_from_iter(vec![LedColor::Green; 12]).unwrap();
 
-        self.device.update_display_buffer(red_led, red_enabled);
-        self.device.update_display_buffer(green_led, green_enabled);
+        // The bottom half is green, the top half is off.
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0x00, buffer[*row].bits());
+            assert_eq!(0x0F, buffer[*row + 1].bits());
+        }
+        assert_eq!(Display::ON, *bargraph.device.display());
     }
 
-    // This transform follows the layout of the Adafruit bargraph backpack.
-    #[allow(clippy::blacklisted_name)]
-    fn bar_to_row_common(&self, bar: u8) -> (u8, u8) {
-        let (count, remainder) = bar.div_mod_floor(&12);
-        let (mut row, mut common) = remainder.div_mod_floor(&4);
-        row *= 2;
-        common += count * 4;
+    #[test]
+    fn update_segments() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
 
-        trace!(self.logger, "bar_to_row_common"; "bar" => bar, "row" => row, "common" => common);
+        bargraph
+            .update_segments(&[(12, 12), (0, 12)], false)
+            .unwrap();
 
-        (row, common)
+        // The bottom half is red, the top half is green.
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0x0F, buffer[*row].bits());
+            assert_eq!(0xF0, buffer[*row + 1].bits());
+        }
+        assert_eq!(Display::ON, *bargraph.device.display());
     }
 
-    // For the given row & common determine the bar #'s and whether they're off, or enabled as red
-    // or green. Each common "value" represents the state of 8 LEDs.
-    //
-    // The row determines if it's red (even) or green (odd).
-    //
-    // The bits of the common determine which commons are enabled.
-    //
-    // There are 2 LEDs per bar (1x red, 1x green), these bar #'s need to merged with the bar
-    // #'s from other rows to determine if actual bar # is lit or not.
-    //
-    // This transform follows the layout of the Adafruit bargraph backpack.
-    fn row_common_to_bars(
-        &self,
-        row_in: u8,
-        common_in: u8,
-    ) -> [Option<LedColor>; BARGRAPH_RESOLUTION as usize] {
-        let mut bars = [None; BARGRAPH_RESOLUTION as usize];
-
-        let (row, green) = row_in.div_mod_floor(&2);
-
-        for position in 0..ht16k33::COMMONS_SIZE {
-            let check = 1 << position;
-
-            let (count, common) = (position as u8).div_mod_floor(&4);
-            let remainder = row * 4 + common;
-            #[allow(clippy::blacklisted_name)]
-            let bar = count * 12 + remainder;
-            let enabled = check == common_in & check;
-
-            if enabled {
-                bars[bar as usize] = if green == 1 {
-                    Some(LedColor::Green)
-                } else {
-                    Some(LedColor::Red)
-                };
-            } else {
-                bars[bar as usize] = Some(LedColor::Off);
+    #[test]
+    fn update_segments_invalid() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.update_segments(&[(6, 6)], false).unwrap();
+
+        // No segments, 25 segments of a single bar, & a range larger than the 12 bars
+        // of each of two segments.
+        let too_many = [(0, 1); BARGRAPH_RESOLUTION as usize + 1];
+        for segments in [&[][..], &too_many[..], &[(1, 12), (1, 13)][..]].iter() {
+            match bargraph.update_segments(segments, false) {
+                Err(Error::InvalidArgument { .. }) => {}
+                result => panic!("Expected an invalid argument, got {:?}", result),
             }
         }
 
-        trace!(self.logger, "row_common_to_bars"; "row" => row_in, "common" => format!("{:#010b}", common_in), "bars" => format!("{:?}", bars));
+        // The display is unchanged.
+        assert_eq!(Some(Frame::from_value(6, 6)), bargraph.last_frame);
+
+        // Fewer bars leave room for fewer segments.
+        bargraph.set_resolution(6);
+        match bargraph.update_segments(&[(0, 1); 7], false) {
+            Err(Error::InvalidArgument { name, .. }) => assert_eq!("segments", name),
+            result => panic!("Expected too many segments, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn update_colored() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        let colors = ColorMap::new(vec![(0, LedColor::Green), (50, LedColor::Red)]);
+        bargraph.update_colored(2, 2, &colors, false).unwrap();
+
+        // The bottom half is green, the top half is red.
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0xF0, buffer[*row].bits());
+            assert_eq!(0x0F, buffer[*row + 1].bits());
+        }
+    }
+
+    #[test]
+    fn update_channels() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.update_channels(24, 12, 24, false).unwrap();
+
+        // Every red LED is on, only the bottom half of the green LEDs are on.
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0xFF, buffer[*row].bits());
+            assert_eq!(0x0F, buffer[*row + 1].bits());
+        }
+        assert_eq!(Display::ON, *bargraph.device.display());
+    }
+
+    #[test]
+    fn update_channels_look() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.update_channels(6, 3, 12, false).unwrap();
+        let frame = bargraph.last_frame();
+
+        // The channels are drawn the same whatever the look of the values.
+        bargraph.set_style(Style::Dot);
+        bargraph.set_thresholds(Thresholds {
+            warn: Some(50),
+            crit: Some(90),
+        });
+        bargraph.set_palette(|_bar: u8, _filled: bool, _range: u8| LedColor::Yellow);
+        bargraph.update_channels(6, 3, 12, false).unwrap();
+        assert_eq!(frame, bargraph.last_frame());
+    }
+
+    #[test]
+    fn set_flipped() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_flipped(true);
+
+        bargraph.update(1, 24, false).unwrap();
+
+        // Bar 0 is the last bar of the physical display.
+        let buffer = bargraph.device.display_buffer();
+        assert_eq!(0x80, buffer[4].bits());
+        assert_eq!(0x7F, buffer[5].bits());
+
+        bargraph.show().unwrap();
+    }
+
+    #[test]
+    fn set_color_scheme() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.set_color_scheme(ColorScheme {
+            fill: LedColor::Green,
+            head: LedColor::Green,
+            ..ColorScheme::default()
+        });
+        bargraph.update(24, 24, false).unwrap();
+
+        // Every bar is green.
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0x00, buffer[*row].bits());
+            assert_eq!(0xFF, buffer[*row + 1].bits());
+        }
+    }
+
+    #[test]
+    fn set_thresholds() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_thresholds(Thresholds {
+            warn: Some(50),
+            crit: Some(90),
+        });
+
+        bargraph.update(6, 12, false).unwrap();
+        assert_eq!(
+            "YYYYYYYYYYYY.G.G.G.G.G.G",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.set_thresholds(None);
+        bargraph.update(6, 12, false).unwrap();
+        assert_eq!(
+            "YRYRYRYRYRYR.G.G.G.G.G.G",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn update_signed() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+
+        bargraph.update_signed(-1, 3, false).unwrap();
+        assert_eq!(
+            "G.G.RY.G.G.G............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+        assert_eq!(Some(false), bargraph.blink);
 
-        bars
+        bargraph.update_signed(4, 3, false).unwrap();
+        assert_eq!(
+            "G.G.G.YRYRYR............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+        assert_eq!(Some(true), bargraph.blink);
     }
 
-    // Unicode box-drawing characters: https://en.wikipedia.org/wiki/Box-drawing_character
-    fn display_ascii_bargraph(&self, leds: &[LedColor], display: Display) {
-        println!(
-            "{corner_top_left}{line}{corner_top_right}",
-            corner_top_left = White.paint("\u{2554}"),
-            line = White.paint(
-                std::iter::repeat("\u{2550}")
-                    .take(leds.len() as usize)
-                    .collect::<String>()
-            ),
-            corner_top_right = White.paint("\u{2557}")
+    #[test]
+    fn update_signed_look() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+
+        bargraph.set_style(Style::Dot);
+        bargraph.update_signed(-2, 3, false).unwrap();
+        assert_eq!(
+            "..RY....................",
+            bargraph.last_frame().unwrap().to_string()
         );
 
-        print!("{side}", side = White.paint("\u{2551}"),);
+        // In the zone of the value's magnitude.
+        bargraph.set_style(Style::Bar);
+        bargraph.set_thresholds(Thresholds {
+            warn: Some(50),
+            crit: Some(90),
+        });
+        bargraph.update_signed(2, 3, false).unwrap();
+        assert_eq!(
+            "G.G.G.YYYY.G............",
+            bargraph.last_frame().unwrap().to_string()
+        );
 
-        for led in leds.iter() {
-            let mut style = Style::new();
+        // The palette's bars are numbered from the center.
+        bargraph.set_palette(|bar: u8, filled: bool, _range: u8| match filled {
+            true if bar >= 2 => LedColor::Red,
+            true => LedColor::Green,
+            false => LedColor::Off,
+        });
+        bargraph.update_signed(-3, 3, false).unwrap();
+        assert_eq!(
+            "RRRRGG..................",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
 
-            if display == Display::HALF_HZ
-                || display == Display::ONE_HZ
-                || display == Display::TWO_HZ
-            {
-                style = style.blink();
-            }
+    #[test]
+    fn set_style() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.set_style(Style::Dot);
+        bargraph.update(4, 12, false).unwrap();
+        assert_eq!(
+            "......YR................",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.set_palette(|_bar: u8, filled: bool, _range: u8| match filled {
+            true => LedColor::Green,
+            false => LedColor::Off,
+        });
+        bargraph.update(4, 12, false).unwrap();
+        assert_eq!(
+            "......GG................",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn set_palette() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.set_palette(|bar: u8, filled: bool, _range: u8| match filled {
+            true if bar >= 6 => LedColor::Red,
+            true => LedColor::Green,
+            false => LedColor::Yellow,
+        });
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(
+            "GGGGGGRRRRRRYYYYYYYYYYYY",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        bargraph.clear_palette();
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(
+            "YYYRYYYRYYYR...G...G...G",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn set_mapping() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        let mut table = mapping::builtin_table();
+        table.swap(0, 23);
+        bargraph.set_mapping(&table).unwrap();
+        assert_eq!(&table, bargraph.mapping());
+
+        bargraph.update(1, 24, false).unwrap();
+
+        // Bar 0 is wired to the last bar of the built-in mapping.
+        let buffer = bargraph.device.display_buffer();
+        assert_eq!(0x80, buffer[4].bits());
+        assert_eq!(0x7F, buffer[5].bits());
+    }
+
+    #[test]
+    fn set_mapping_flipped() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        let mut table = mapping::builtin_table();
+        table.swap(0, 23);
+        bargraph.set_mapping(&table).unwrap();
+        bargraph.set_flipped(true);
+
+        bargraph.update(1, 24, false).unwrap();
+
+        // Bar 0 is the last bar of the mapping, wired to the first bar.
+        let buffer = bargraph.device.display_buffer();
+        assert_eq!(0x01, buffer[0].bits());
+        assert_eq!(0xFE, buffer[1].bits());
+    }
+
+    #[test]
+    fn set_mapping_invalid() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        let mut table = mapping::builtin_table();
+        table[0] = (15, 0);
+        assert!(bargraph.set_mapping(&table).is_err());
+
+        table[0] = (0, 8);
+        assert!(bargraph.set_mapping(&table).is_err());
+
+        assert_eq!(&mapping::builtin_table(), bargraph.mapping());
+    }
+
+    #[test]
+    fn set_reversed() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_reversed(true);
 
-            let mut color = match led {
-                LedColor::Green => style.fg(Green),
-                LedColor::Red => style.fg(Red),
-                LedColor::Yellow => style.fg(Yellow),
-                LedColor::Off => style.fg(Fixed(238)), // Dark grey.
-            };
+        bargraph.update(12, 24, false).unwrap();
 
-            print!("{}", color.paint(BARGRAPH_DISPLAY_CHAR));
+        // The top half is red, the bottom half is green.
+        let buffer = bargraph.device.display_buffer();
+        for row in [0, 2, 4].iter() {
+            assert_eq!(0xF0, buffer[*row].bits());
+            assert_eq!(0x0F, buffer[*row + 1].bits());
         }
 
-        println!("{side}", side = White.paint("\u{2551}"),);
+        // Reversed twice is the original orientation.
+        bargraph.set_flipped(true);
+        bargraph.update(12, 24, false).unwrap();
+        let buffer = bargraph.device.display_buffer();
+        assert_eq!(0x0F, buffer[0].bits());
+    }
+
+    #[test]
+    fn set_resolution() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+        assert_eq!(12, bargraph.resolution());
+
+        // The bars above the resolution are off.
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(
+            "YRYRYR.G.G.G............",
+            bargraph.last_frame.unwrap().to_string()
+        );
 
-        println!(
-            "{corner_bottom_left}{line}{corner_bottom_right}",
-            corner_bottom_left = White.paint("\u{255A}"),
-            line = White.paint(
-                std::iter::repeat("\u{2550}")
-                    .take(leds.len() as usize)
-                    .collect::<String>()
-            ),
-            corner_bottom_right = White.paint("\u{255D}")
+        // Only the bars in use are reversed.
+        bargraph.set_reversed(true);
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(
+            "G.G.G.RYRYRY............",
+            bargraph.last_frame.unwrap().to_string()
         );
+
+        // Bar 0 is the top bar in use.
+        bargraph.set_reversed(false);
+        bargraph.set_flipped(true);
+        bargraph.update(1, 12, false).unwrap();
+        let buffer = bargraph.device.display_buffer();
+        assert_eq!(0x08, buffer[4].bits());
+        assert_eq!(0x07, buffer[5].bits());
+
+        assert!(bargraph.try_update(1, 13, false).is_err());
+
+        bargraph.show().unwrap();
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use ht16k33::i2c_mock::I2cMock;
+    #[test]
+    #[should_panic]
+    fn set_resolution_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
 
-    const ADDRESS: u8 = 0;
+        bargraph.set_resolution(BARGRAPH_RESOLUTION + 1);
+    }
 
     #[test]
-    fn new() {
+    fn changed_since_last_flush() {
         let i2c = I2cMock::new(None);
-        let _bargraph = Bargraph::new(i2c, ADDRESS, None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        assert!(!bargraph.changed_since_last_flush());
+
+        bargraph.update(3, 6, false).unwrap();
+        assert!(bargraph.changed_since_last_flush());
+
+        bargraph.update(3, 6, false).unwrap();
+        assert!(!bargraph.changed_since_last_flush());
+
+        // Only the blink state changes.
+        bargraph.update(7, 6, false).unwrap();
+        bargraph.update(6, 6, false).unwrap();
+        assert!(bargraph.changed_since_last_flush());
+
+        // The displayed frame is unknown after clearing.
+        bargraph.clear().unwrap();
+        bargraph.update(6, 6, false).unwrap();
+        assert!(bargraph.changed_since_last_flush());
     }
 
     #[test]
-    fn initialize() {
+    fn skip_unchanged() {
         let i2c = I2cMock::new(None);
         let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
-        bargraph.initialize().unwrap();
+        bargraph.set_skip_unchanged(true);
+
+        bargraph.update(3, 6, false).unwrap();
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(1, bargraph.metrics().frames);
+
+        bargraph.set_skip_unchanged(false);
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(2, bargraph.metrics().frames);
     }
 
     #[test]
-    fn clear() {
+    fn set_bar() {
         let i2c = I2cMock::new(None);
         let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
-        bargraph.initialize().unwrap();
+
+        bargraph.update(6, 12, false).unwrap();
+        bargraph.set_bar(19, LedColor::Red).unwrap();
+        assert_eq!(
+            "YRYRYRYRYRYR.G.G.G.R.G.G",
+            bargraph.last_frame.unwrap().to_string()
+        );
+
+        // Only the bars in use can be set.
+        bargraph.set_resolution(12);
+        bargraph.update(6, 12, false).unwrap();
+        let frame = bargraph.last_frame;
+        match bargraph.set_bar(12, LedColor::Red) {
+            Err(Error::InvalidArgument { name, .. }) => assert_eq!("bar", name),
+            result => panic!("Expected an invalid bar, got {:?}", result),
+        }
+        assert_eq!(frame, bargraph.last_frame);
+    }
+
+    #[test]
+    fn add_marker() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.add_marker(19, LedColor::Red, false).unwrap();
+        bargraph.add_marker(2, LedColor::Green, false).unwrap();
+        bargraph.update(6, 12, false).unwrap();
+        assert_eq!(
+            "YRGRYRYRYRYR.G.G.G.R.G.G",
+            bargraph.last_frame.unwrap().to_string()
+        );
+
+        // Markers survive later updates, the latest marker at a position wins.
+        bargraph.add_marker(2, LedColor::Yellow, true).unwrap();
+        bargraph.update(1, 12, false).unwrap();
+        let frame = bargraph.last_frame.unwrap();
+        assert_eq!("YRYG.G.G.G.G.G.G.G.R.G.G", frame.to_string());
+        assert!(frame.blink);
+
+        bargraph.clear_markers();
+        bargraph.update(1, 12, false).unwrap();
+        assert_eq!(Some(Frame::from_value(1, 12)), bargraph.last_frame);
+    }
+
+    #[test]
+    fn add_marker_resolution() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.add_marker(19, LedColor::Red, true).unwrap();
+        bargraph.set_resolution(12);
+        bargraph.add_marker(11, LedColor::Yellow, false).unwrap();
+
+        // The marker beyond the bars in use isn't drawn.
+        bargraph.update(6, 12, false).unwrap();
+        let frame = bargraph.last_frame.unwrap();
+        assert_eq!("RRRRRRGGGGGY............", frame.to_string());
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn add_marker_beyond_resolution() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(12);
+        bargraph.add_marker(11, LedColor::Yellow, false).unwrap();
+
+        match bargraph.add_marker(12, LedColor::Red, false) {
+            Err(Error::InvalidArgument { name, .. }) => assert_eq!("position", name),
+            result => panic!("Expected an invalid position, got {:?}", result),
+        }
+        assert_eq!(vec![(11, LedColor::Yellow, false)], bargraph.markers);
+    }
+
+    #[test]
+    fn set_deferred() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_deferred(true);
 
         bargraph.clear().unwrap();
+        bargraph.update(6, 12, false).unwrap();
+        bargraph.set_bar(19, LedColor::Red).unwrap();
+        assert_eq!(0, bargraph.metrics().i2c_transactions);
+
+        bargraph.present().unwrap();
+        assert_eq!(1, bargraph.metrics().frames);
+        assert_eq!(
+            "YRYRYRYRYRYR.G.G.G.R.G.G",
+            bargraph.last_frame.unwrap().to_string()
+        );
+
+        // Nothing is pending.
+        bargraph.present().unwrap();
+        assert_eq!(1, bargraph.metrics().frames);
     }
 
     #[test]
-    fn update() {
+    fn metrics() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        assert_eq!(Metrics::default(), bargraph.metrics());
+
+        bargraph.update(5, 6, false).unwrap();
+        bargraph.update(6, 6, false).unwrap();
+
+        // Two display buffer writes & one blink command.
+        let metrics = bargraph.metrics();
+        assert_eq!(3, metrics.i2c_transactions);
+        assert_eq!(17 * 2 + 1, metrics.i2c_bytes);
+        assert_eq!(0, metrics.i2c_errors);
+        assert_eq!(2, metrics.frames);
+        assert!(metrics.last_update_latency.is_some());
+    }
+
+    #[test]
+    fn blink_rate() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.update(25, 24, false).unwrap();
+        assert_eq!(BlinkRate::OneHz, bargraph.blink_rate());
+
+        bargraph.update(24, 24, false).unwrap();
+        assert_eq!(BlinkRate::Off, bargraph.blink_rate());
+    }
+
+    #[test]
+    fn brightness() {
         let i2c = I2cMock::new(None);
         let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
         bargraph.initialize().unwrap();
+        assert_eq!(BRIGHTNESS_MAX, bargraph.brightness());
+
+        bargraph.set_brightness(0).unwrap();
+        assert_eq!(0, bargraph.brightness());
+    }
+
+    #[test]
+    fn set_brightness() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.set_brightness(0).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MIN, *bargraph.device.dimming());
+
+        bargraph.set_brightness(BRIGHTNESS_MAX).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MAX, *bargraph.device.dimming());
+    }
+
+    #[test]
+    fn clear_with_fade() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_brightness(6).unwrap();
+        bargraph.set_deferred(true);
+        bargraph.update(3, 6, false).unwrap();
+
+        bargraph.clear_with_fade(Duration::from_millis(1)).unwrap();
+
+        assert_eq!(None, bargraph.pending);
+        assert_eq!(None, bargraph.last_frame);
+        assert_eq!(6, bargraph.brightness());
+        for row in bargraph.device.display_buffer().iter() {
+            assert_eq!(0, row.bits());
+        }
+    }
+
+    #[test]
+    fn play() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_resolution(6);
+
+        let animation = Animation {
+            interval: Duration::from_millis(1),
+            ..Animation::new(Pattern::Fill)
+        };
+        bargraph.play(&animation).unwrap();
+
+        assert_eq!(6, bargraph.metrics().frames);
+        assert_eq!(
+            "GGGGGG..................",
+            bargraph.last_frame.unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn assume_frame() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_skip_unchanged(true);
+        bargraph.assume_frame(Frame::from_value(5, 6));
 
         bargraph.update(5, 6, false).unwrap();
+        assert_eq!(0, bargraph.metrics().i2c_transactions);
+
+        // Only the display buffer is written, the display is already on.
+        bargraph.update(4, 6, false).unwrap();
+        assert_eq!(1, bargraph.metrics().i2c_transactions);
+        assert_eq!(Some(Frame::from_value(4, 6)), bargraph.last_frame());
     }
 
     #[test]
-    fn set_blink() {
+    fn set_fade_in() {
         let i2c = I2cMock::new(None);
         let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_fade_in(Duration::from_millis(1), 8);
+
         bargraph.initialize().unwrap();
+        assert_eq!(0, bargraph.brightness());
 
-        bargraph.set_blink(true).unwrap();
-        bargraph.set_blink(false).unwrap();
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(8, bargraph.brightness());
+
+        // Only the first frame is faded in.
+        bargraph.set_brightness(2).unwrap();
+        bargraph.update(4, 6, false).unwrap();
+        assert_eq!(2, bargraph.brightness());
     }
 
     #[test]
-    fn show() {
+    fn set_fade_in_cancelled() {
         let i2c = I2cMock::new(None);
         let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+        bargraph.set_fade_in(Duration::from_millis(1), 8);
+
         bargraph.initialize().unwrap();
+        bargraph.set_brightness(4).unwrap();
+        bargraph.update(3, 6, false).unwrap();
+        assert_eq!(4, bargraph.brightness());
+    }
 
-        bargraph.show().unwrap();
+    #[test]
+    #[should_panic]
+    fn set_brightness_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut bargraph = Bargraph::new(i2c, ADDRESS, None);
+
+        bargraph.set_brightness(BRIGHTNESS_MAX + 1).unwrap();
     }
 }
@@ -0,0 +1,215 @@
+//! # Mapping
+//!
+//! The wiring of the Adafruit bargraph backpack, converting between bar numbers and
+//! the `HT16K33` rows & commons that drive their LEDs. This module only does
+//! arithmetic, it doesn't depend on any device or crate, so it can be reused by
+//! other drivers.
+//!
+//! Each bar has 2 LEDs: red on an even row and green on the following odd row,
+//! both on the same common. Only the first 6 rows are used:
+//!
+//! 6 rows x 8 commons == 48 LEDs == 24 bars * 2 colors
+//!
+//! # Round-trip
+//!
+//! For every bar from `0` to `23`, given `(row, common) = bar_to_row_common(bar)`:
+//!
+//! * `row_common_to_bar(row, common) == (bar, LedColor::Red)`
+//! * `row_common_to_bar(row + 1, common) == (bar, LedColor::Green)`
+//!
+//! and every row & common on the display maps to exactly one bar & color.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate led_bargraph;
+//!
+//! use led_bargraph::mapping;
+//! use led_bargraph::LedColor;
+//! # fn main() {
+//!
+//! let (row, common) = mapping::bar_to_row_common(13);
+//! assert_eq!((0, 5), (row, common));
+//!
+//! assert_eq!((13, LedColor::Red), mapping::row_common_to_bar(row, common));
+//! assert_eq!((13, LedColor::Green), mapping::row_common_to_bar(row + 1, common));
+//!
+//! # }
+//! ```
+use super::{LedColor, BARGRAPH_RESOLUTION};
+
+/// The number of `HT16K33` rows used by the display.
+pub const BARGRAPH_ROWS: u8 = 6;
+
+/// The number of `HT16K33` commons used by the display.
+pub const BARGRAPH_COMMONS: u8 = 8;
+
+/// Find the row & common of a bar's red LED, its green LED is on the next row.
+///
+/// # Panics
+///
+/// Panics if `bar` is out of range.
+#[allow(clippy::disallowed_names)]
+pub fn bar_to_row_common(bar: u8) -> (u8, u8) {
+    assert!(
+        bar < BARGRAPH_RESOLUTION,
+        "Bar [{}] must be less than [{}]",
+        bar,
+        BARGRAPH_RESOLUTION
+    );
+
+    let (count, remainder) = (bar / 12, bar % 12);
+    let (row, common) = (remainder / 4, remainder % 4);
+
+    (row * 2, common + count * 4)
+}
+
+/// Find the bar & color of the LED at a row & common, the inverse of
+/// [bar_to_row_common()](fn.bar_to_row_common.html).
+///
+/// The color is `LedColor::Red` for even rows and `LedColor::Green` for odd rows.
+///
+/// # Panics
+///
+/// Panics if `row` or `common` are out of range.
+#[allow(clippy::disallowed_names)]
+pub fn row_common_to_bar(row: u8, common: u8) -> (u8, LedColor) {
+    assert!(
+        row < BARGRAPH_ROWS && common < BARGRAPH_COMMONS,
+        "Row [{}] & common [{}] must be less than [{}] & [{}]",
+        row,
+        common,
+        BARGRAPH_ROWS,
+        BARGRAPH_COMMONS
+    );
+
+    let color = if row % 2 == 1 {
+        LedColor::Green
+    } else {
+        LedColor::Red
+    };
+
+    let bar = (common / 4) * 12 + (row / 2) * 4 + common % 4;
+
+    (bar, color)
+}
+
+/// The row & common of every bar's red LED, indexed by bar, see
+/// [Bargraph::set_mapping()](../struct.Bargraph.html#method.set_mapping).
+pub fn builtin_table() -> [(u8, u8); BARGRAPH_RESOLUTION as usize] {
+    let mut table = [(0, 0); BARGRAPH_RESOLUTION as usize];
+
+    for (bar, location) in table.iter_mut().enumerate() {
+        *location = bar_to_row_common(bar as u8);
+    }
+
+    table
+}
+
+/// For the given row & the state of its commons, determine which bars are off or
+/// enabled as red or green. The bars not on the row are `None`.
+///
+/// Each bit of `commons` represents the state of one common, e.g. the row's value in
+/// the display buffer. The bars need merging with the bars from the other rows to
+/// determine the actual color of each bar.
+///
+/// # Panics
+///
+/// Panics if `row` is out of range.
+pub fn row_common_to_bars(
+    row: u8,
+    commons: u8,
+) -> [Option<LedColor>; BARGRAPH_RESOLUTION as usize] {
+    let mut bars = [None; BARGRAPH_RESOLUTION as usize];
+
+    for common in 0..BARGRAPH_COMMONS {
+        let (bar, color) = row_common_to_bar(row, common);
+        let enabled = commons & (1 << common) != 0;
+
+        bars[bar as usize] = if enabled {
+            Some(color)
+        } else {
+            Some(LedColor::Off)
+        };
+    }
+
+    bars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bar_to_row_common_layout() {
+        assert_eq!((0, 0), bar_to_row_common(0));
+        assert_eq!((0, 3), bar_to_row_common(3));
+        assert_eq!((2, 0), bar_to_row_common(4));
+        assert_eq!((4, 3), bar_to_row_common(11));
+        assert_eq!((0, 4), bar_to_row_common(12));
+        assert_eq!((4, 7), bar_to_row_common(23));
+    }
+
+    #[test]
+    #[should_panic]
+    fn bar_to_row_common_too_large() {
+        bar_to_row_common(BARGRAPH_RESOLUTION);
+    }
+
+    #[test]
+    #[should_panic]
+    fn row_common_to_bar_row_too_large() {
+        row_common_to_bar(BARGRAPH_ROWS, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn row_common_to_bar_common_too_large() {
+        row_common_to_bar(0, BARGRAPH_COMMONS);
+    }
+
+    #[test]
+    fn round_trip() {
+        for bar in 0..BARGRAPH_RESOLUTION {
+            let (row, common) = bar_to_row_common(bar);
+
+            assert_eq!((bar, LedColor::Red), row_common_to_bar(row, common));
+            assert_eq!((bar, LedColor::Green), row_common_to_bar(row + 1, common));
+        }
+    }
+
+    #[test]
+    fn every_led_is_one_bar() {
+        let mut seen = [[false; 2]; BARGRAPH_RESOLUTION as usize];
+
+        for row in 0..BARGRAPH_ROWS {
+            for common in 0..BARGRAPH_COMMONS {
+                let (bar, color) = row_common_to_bar(row, common);
+                let index = if color == LedColor::Red { 0 } else { 1 };
+
+                assert!(!seen[bar as usize][index]);
+                seen[bar as usize][index] = true;
+            }
+        }
+
+        assert!(seen.iter().all(|bar| bar[0] && bar[1]));
+    }
+
+    #[test]
+    fn builtin_table_is_bar_to_row_common() {
+        for (bar, location) in builtin_table().iter().enumerate() {
+            assert_eq!(bar_to_row_common(bar as u8), *location);
+        }
+    }
+
+    #[test]
+    fn row_common_to_bars_merged() {
+        let bars = row_common_to_bars(3, 0b1000_0001);
+
+        assert_eq!(Some(LedColor::Green), bars[4]);
+        assert_eq!(Some(LedColor::Green), bars[19]);
+        assert_eq!(Some(LedColor::Off), bars[5]);
+        assert_eq!(None, bars[0]);
+        assert_eq!(8, bars.iter().filter(|bar| bar.is_some()).count());
+    }
+}
@@ -0,0 +1,718 @@
+//! # Matrix
+//!
+//! A driver for the Adafruit [Mini 8x8 LED Matrix w/I2C Backpack](https://www.adafruit.com/product/870)
+//! and [Bicolor 8x8 LED Square Pixel Matrix w/I2C Backpack](https://www.adafruit.com/product/902).
+use hal::blocking::i2c::{Write, WriteRead};
+
+use ht16k33::{Dimming, Display, DisplayDataAddress, LedLocation, Oscillator, HT16K33};
+
+use slog::Drain;
+
+use super::{Error, Ht16k33Display, Operation, BRIGHTNESS_MAX};
+
+#[cfg(feature = "preview")]
+use super::preview::{self, TerminalSupport};
+use super::LedColor;
+
+/// The number of pixels in each row & column of the matrix.
+pub const MATRIX_SIZE: u8 = 8;
+
+#[cfg(feature = "preview")]
+const MATRIX_DISPLAY_CHAR: &str = "\u{25CF}";
+
+/// The 8x8 matrix state.
+pub struct Matrix8x8<I2C> {
+    device: HT16K33<I2C>,
+    bicolor: bool,
+    // What the terminal of `show()` supports, detected each time if `None`.
+    #[cfg(feature = "preview")]
+    terminal: Option<TerminalSupport>,
+    logger: slog::Logger,
+}
+
+impl<I2C, E> Matrix8x8<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Create a single-color Matrix8x8 for display.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C device to communicate with the `HT16K33` chip.
+    /// * `i2c_address` - The I2C address of the `HT16K33` chip.
+    /// * `logger` - A logging instance.
+    ///
+    /// # Notes
+    ///
+    /// `logger = None` will log to the `slog-stdlog` drain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate ht16k33;
+    /// extern crate led_bargraph;
+    ///
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    ///
+    /// let mut i2c = I2cMock::new(None);
+    /// let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    ///
+    /// # }
+    /// ```
+    pub fn new<L>(i2c: I2C, i2c_address: u8, logger: L) -> Self
+    where
+        L: Into<Option<slog::Logger>>,
+    {
+        Self::with_colors(i2c, i2c_address, false, logger.into())
+    }
+
+    /// Create a bi-color (red/green) Matrix8x8 for display.
+    ///
+    /// See [new()](#method.new) for the arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new_bicolor(i2c, address, None);
+    ///
+    /// # }
+    /// ```
+    pub fn new_bicolor<L>(i2c: I2C, i2c_address: u8, logger: L) -> Self
+    where
+        L: Into<Option<slog::Logger>>,
+    {
+        Self::with_colors(i2c, i2c_address, true, logger.into())
+    }
+
+    fn with_colors(i2c: I2C, i2c_address: u8, bicolor: bool, logger: Option<slog::Logger>) -> Self {
+        let logger = logger.unwrap_or_else(|| slog::Logger::root(slog_stdlog::StdLog.fuse(), o!()));
+
+        trace!(logger, "Constructing Matrix8x8"; "bicolor" => bicolor);
+
+        let ht16k33_logger = logger.new(o!("mod" => "HT16K33"));
+        let ht16k33 = HT16K33::new(i2c, i2c_address, ht16k33_logger);
+
+        Matrix8x8 {
+            device: ht16k33,
+            bicolor,
+            #[cfg(feature = "preview")]
+            terminal: None,
+            logger,
+        }
+    }
+
+    /// Initialize the display & the connected `HT16K33` device, leaving the display
+    /// cleared and turned on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.initialize().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn initialize(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "initialize");
+
+        self.device.initialize().map_err(Error::i2c(
+            Operation::Initialize,
+            Oscillator::COMMAND.bits(),
+        ))?;
+        self.device
+            .set_display(Display::ON)
+            .map_err(Error::i2c(Operation::Initialize, Display::COMMAND.bits()))
+    }
+
+    /// Clear the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.clear().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn clear(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "clear");
+
+        self.device.clear_display_buffer();
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Set a pixel to the desired color.
+    ///
+    /// A single-color matrix turns the pixel on for any color other than `LedColor::Off`.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column, from `0` (left) to `7` (right).
+    /// * `y` - The row, from `0` (top) to `7` (bottom).
+    /// * `color` - A valid color.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` are out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{LedColor, Matrix8x8};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new_bicolor(i2c, address, None);
+    /// matrix.set_pixel(0, 0, LedColor::Red);
+    /// matrix.set_pixel(7, 7, LedColor::Yellow);
+    /// matrix.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_pixel(&mut self, x: u8, y: u8, color: LedColor) {
+        trace!(self.logger, "set_pixel"; "x" => x, "y" => y, "color" => format!("{:?}", color));
+
+        assert!(
+            x < MATRIX_SIZE && y < MATRIX_SIZE,
+            "Pixel ({}, {}) must be less than ({}, {})",
+            x,
+            y,
+            MATRIX_SIZE,
+            MATRIX_SIZE
+        );
+
+        if self.bicolor {
+            let (green_led, red_led) = Self::bicolor_locations(x, y);
+
+            let red_enabled = color == LedColor::Red || color == LedColor::Yellow;
+            let green_enabled = color == LedColor::Green || color == LedColor::Yellow;
+
+            self.device.update_display_buffer(red_led, red_enabled);
+            self.device.update_display_buffer(green_led, green_enabled);
+        } else {
+            let led = Self::mono_location(x, y);
+
+            self.device
+                .update_display_buffer(led, color != LedColor::Off);
+        }
+    }
+
+    /// Turn off a pixel, see [set_pixel()](#method.set_pixel).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.clear_pixel(3, 4);
+    /// matrix.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn clear_pixel(&mut self, x: u8, y: u8) {
+        self.set_pixel(x, y, LedColor::Off);
+    }
+
+    /// Draw a bitmap, pixels in the bitmap are set to `color` and the rest are
+    /// turned off.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `bitmap` - One byte per row from the top, the most significant bit is the
+    ///   left-most pixel.
+    /// * `color` - A valid color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{LedColor, Matrix8x8};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new_bicolor(i2c, address, None);
+    ///
+    /// let smile = [
+    ///     0b0011_1100,
+    ///     0b0100_0010,
+    ///     0b1010_0101,
+    ///     0b1000_0001,
+    ///     0b1010_0101,
+    ///     0b1001_1001,
+    ///     0b0100_0010,
+    ///     0b0011_1100,
+    /// ];
+    /// matrix.draw_bitmap(&smile, LedColor::Green);
+    /// matrix.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn draw_bitmap(&mut self, bitmap: &[u8; MATRIX_SIZE as usize], color: LedColor) {
+        trace!(self.logger, "draw_bitmap"; "bitmap" => format!("{:?}", bitmap),
+               "color" => format!("{:?}", color));
+
+        for (y, row) in bitmap.iter().enumerate() {
+            for x in 0..MATRIX_SIZE {
+                let enabled = row & (0x80 >> x) != 0;
+                let pixel_color = if enabled { color } else { LedColor::Off };
+
+                self.set_pixel(x, y as u8, pixel_color);
+            }
+        }
+    }
+
+    /// Write the pixels to the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "flush");
+
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Enable/Disable continuous blinking of the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.set_blink(true).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_blink"; "enabled" => enabled);
+
+        let display = if enabled {
+            Display::ONE_HZ
+        } else {
+            Display::ON
+        };
+
+        self.device
+            .set_display(display)
+            .map_err(Error::i2c(Operation::SetBlink, Display::COMMAND.bits()))
+    }
+
+    /// Set the brightness of the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.set_brightness(8).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_brightness"; "brightness" => brightness);
+
+        assert!(
+            brightness <= BRIGHTNESS_MAX,
+            "Brightness [{}] must be less than or equal to [{}]",
+            brightness,
+            BRIGHTNESS_MAX
+        );
+
+        self.device
+            .set_dimming(Dimming::from_bits_truncate(brightness))
+            .map_err(Error::i2c(
+                Operation::SetBrightness,
+                Dimming::COMMAND.bits(),
+            ))
+    }
+
+    /// Override what the terminal of [show()](#method.show) supports, rather than
+    /// detecting it each time, e.g. to force ASCII for a serial console.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - What the terminal supports, `None` to detect it.
+    #[cfg(feature = "preview")]
+    pub fn set_terminal<T>(&mut self, terminal: T)
+    where
+        T: Into<Option<TerminalSupport>>,
+    {
+        self.terminal = terminal.into();
+        trace!(self.logger, "set_terminal"; "terminal" => ?self.terminal);
+    }
+
+    /// Show the current matrix display on-screen.
+    ///
+    /// A single-color matrix is shown in red.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Matrix8x8;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut matrix = Matrix8x8::new(i2c, address, None);
+    /// matrix.show().unwrap();
+    ///
+    /// # }
+    /// ```
+    #[cfg(feature = "preview")]
+    pub fn show(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "show");
+
+        // Read & retrieve the buffer values from the device.
+        self.device.read_display_buffer().map_err(Error::i2c(
+            Operation::ReadDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))?;
+
+        let display = *self.device.display();
+
+        let mut pixels = [[LedColor::Off; MATRIX_SIZE as usize]; MATRIX_SIZE as usize];
+
+        if display == Display::OFF {
+            trace!(
+                self.logger,
+                "Display is off, don't attempt to retrieve the pixels"
+            );
+        } else {
+            for (y, row) in pixels.iter_mut().enumerate() {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    *pixel = self.pixel(x as u8, y as u8);
+                }
+            }
+        }
+
+        preview::print_leds(&pixels, MATRIX_DISPLAY_CHAR, display, self.terminal);
+
+        Ok(())
+    }
+
+    // Retrieve the color of a pixel from the display buffer.
+    #[cfg(any(feature = "preview", test))]
+    fn pixel(&self, x: u8, y: u8) -> LedColor {
+        let buffer = self.device.display_buffer();
+        let enabled = |led: LedLocation| buffer[led.row_as_index()].contains(led.common);
+
+        if self.bicolor {
+            let (green_led, red_led) = Self::bicolor_locations(x, y);
+
+            match (enabled(red_led), enabled(green_led)) {
+                (true, true) => LedColor::Yellow,
+                (true, false) => LedColor::Red,
+                (false, true) => LedColor::Green,
+                (false, false) => LedColor::Off,
+            }
+        } else if enabled(Self::mono_location(x, y)) {
+            LedColor::Red
+        } else {
+            LedColor::Off
+        }
+    }
+
+    // The columns are rotated by one.
+    //
+    // This transform follows the layout of the Adafruit 8x8 matrix backpack.
+    fn mono_location(x: u8, y: u8) -> LedLocation {
+        LedLocation::new(y * 2, (x + 7) % MATRIX_SIZE).unwrap()
+    }
+
+    // Each row has the green LEDs followed by the red LEDs.
+    //
+    // This transform follows the layout of the Adafruit bicolor 8x8 matrix backpack.
+    fn bicolor_locations(x: u8, y: u8) -> (LedLocation, LedLocation) {
+        let green_led = LedLocation::new(y * 2, x).unwrap();
+        let red_led = LedLocation::new(y * 2 + 1, x).unwrap();
+
+        (green_led, red_led)
+    }
+}
+
+impl<I2C, E> Ht16k33Display for Matrix8x8<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn initialize(&mut self) -> Result<(), Error<E>> {
+        Matrix8x8::initialize(self)
+    }
+
+    fn clear(&mut self) -> Result<(), Error<E>> {
+        Matrix8x8::clear(self)
+    }
+
+    fn flush(&mut self) -> Result<(), Error<E>> {
+        Matrix8x8::flush(self)
+    }
+
+    fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        Matrix8x8::set_blink(self, enabled)
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        Matrix8x8::set_brightness(self, brightness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    #[test]
+    fn new() {
+        let i2c = I2cMock::new(None);
+        let _matrix = Matrix8x8::new(i2c, ADDRESS, None);
+    }
+
+    #[test]
+    fn new_bicolor() {
+        let i2c = I2cMock::new(None);
+        let _matrix = Matrix8x8::new_bicolor(i2c, ADDRESS, None);
+    }
+
+    #[test]
+    fn initialize() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+        matrix.initialize().unwrap();
+
+        assert_eq!(Display::ON, *matrix.device.display());
+    }
+
+    #[test]
+    fn clear() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+        matrix.set_pixel(1, 1, LedColor::Red);
+
+        matrix.clear().unwrap();
+
+        for row in matrix.device.display_buffer().iter() {
+            assert_eq!(0, row.bits());
+        }
+    }
+
+    #[test]
+    fn set_pixel_mono() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+
+        matrix.set_pixel(0, 0, LedColor::Green);
+        matrix.set_pixel(1, 3, LedColor::Red);
+
+        let buffer = matrix.device.display_buffer();
+        assert_eq!(0b1000_0000, buffer[0].bits());
+        assert_eq!(0b0000_0001, buffer[6].bits());
+
+        assert_eq!(LedColor::Red, matrix.pixel(0, 0));
+        assert_eq!(LedColor::Red, matrix.pixel(1, 3));
+        assert_eq!(LedColor::Off, matrix.pixel(1, 0));
+    }
+
+    #[test]
+    fn set_pixel_bicolor() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new_bicolor(i2c, ADDRESS, None);
+
+        matrix.set_pixel(0, 0, LedColor::Green);
+        matrix.set_pixel(1, 0, LedColor::Red);
+        matrix.set_pixel(2, 0, LedColor::Yellow);
+
+        let buffer = matrix.device.display_buffer();
+        assert_eq!(0b0000_0101, buffer[0].bits());
+        assert_eq!(0b0000_0110, buffer[1].bits());
+
+        assert_eq!(LedColor::Green, matrix.pixel(0, 0));
+        assert_eq!(LedColor::Red, matrix.pixel(1, 0));
+        assert_eq!(LedColor::Yellow, matrix.pixel(2, 0));
+        assert_eq!(LedColor::Off, matrix.pixel(3, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_pixel_out_of_range() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+
+        matrix.set_pixel(8, 0, LedColor::Red);
+    }
+
+    #[test]
+    fn clear_pixel() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new_bicolor(i2c, ADDRESS, None);
+
+        matrix.set_pixel(4, 4, LedColor::Yellow);
+        matrix.clear_pixel(4, 4);
+
+        assert_eq!(LedColor::Off, matrix.pixel(4, 4));
+    }
+
+    #[test]
+    fn draw_bitmap() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new_bicolor(i2c, ADDRESS, None);
+
+        let mut bitmap = [0u8; MATRIX_SIZE as usize];
+        bitmap[0] = 0b1000_0001;
+        bitmap[7] = 0b0001_0000;
+        matrix.draw_bitmap(&bitmap, LedColor::Green);
+
+        for y in 0..MATRIX_SIZE {
+            for x in 0..MATRIX_SIZE {
+                let expected = match (x, y) {
+                    (0, 0) | (7, 0) | (3, 7) => LedColor::Green,
+                    _ => LedColor::Off,
+                };
+                assert_eq!(expected, matrix.pixel(x, y), "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn flush() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+        matrix.set_pixel(2, 2, LedColor::Red);
+
+        matrix.flush().unwrap();
+    }
+
+    #[test]
+    fn set_blink() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+
+        matrix.set_blink(true).unwrap();
+        assert_eq!(Display::ONE_HZ, *matrix.device.display());
+
+        matrix.set_blink(false).unwrap();
+        assert_eq!(Display::ON, *matrix.device.display());
+    }
+
+    #[test]
+    #[cfg(feature = "preview")]
+    fn show() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new_bicolor(i2c, ADDRESS, None);
+        matrix.initialize().unwrap();
+        matrix.set_pixel(2, 2, LedColor::Red);
+        matrix.flush().unwrap();
+
+        matrix.show().unwrap();
+    }
+
+    #[test]
+    fn set_brightness() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+
+        matrix.set_brightness(0).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MIN, *matrix.device.dimming());
+
+        matrix.set_brightness(BRIGHTNESS_MAX).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MAX, *matrix.device.dimming());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_brightness_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut matrix = Matrix8x8::new(i2c, ADDRESS, None);
+
+        matrix.set_brightness(BRIGHTNESS_MAX + 1).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+//! # Metrics
+//!
+//! Counters describing the work a display has done, for monitoring its I2C
+//! traffic & update performance.
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A snapshot of the counters, see [Bargraph::metrics()](struct.Bargraph.html#method.metrics).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Metrics {
+    /// The number of I2C transactions, including failed transactions.
+    pub i2c_transactions: u64,
+    /// The number of bytes written to & read from the I2C device.
+    pub i2c_bytes: u64,
+    /// The number of failed I2C transactions.
+    pub i2c_errors: u64,
+    /// The number of times the I2C bus was recovered, see
+    /// [Bargraph::set_bus_recovery()](struct.Bargraph.html#method.set_bus_recovery).
+    pub bus_recoveries: u64,
+    /// The number of frames rendered to the display.
+    pub frames: u64,
+    /// How long the most recent frame took to render, including the I2C writes.
+    pub last_update_latency: Option<Duration>,
+}
+
+// Counts the traffic of the wrapped I2C device into metrics shared with the
+// display that owns it.
+pub(crate) struct MeteredI2c<I2C> {
+    i2c: I2C,
+    metrics: Arc<Mutex<Metrics>>,
+}
+
+impl<I2C> MeteredI2c<I2C> {
+    pub(crate) fn new(i2c: I2C, metrics: Arc<Mutex<Metrics>>) -> Self {
+        MeteredI2c { i2c, metrics }
+    }
+
+    pub(crate) fn into_inner(self) -> I2C {
+        self.i2c
+    }
+
+    fn count<T, E>(&self, bytes: usize, result: &Result<T, E>) {
+        let mut metrics = self.metrics.lock().unwrap();
+
+        metrics.i2c_transactions += 1;
+        metrics.i2c_bytes += bytes as u64;
+        if result.is_err() {
+            metrics.i2c_errors += 1;
+        }
+    }
+}
+
+impl<I2C> Write for MeteredI2c<I2C>
+where
+    I2C: Write,
+{
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let result = self.i2c.write(address, bytes);
+        self.count(bytes.len(), &result);
+
+        result
+    }
+}
+
+impl<I2C> WriteRead for MeteredI2c<I2C>
+where
+    I2C: WriteRead,
+{
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let result = self.i2c.write_read(address, bytes, buffer);
+        self.count(bytes.len() + buffer.len(), &result);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    #[test]
+    fn count() {
+        let metrics = Arc::new(Mutex::new(Metrics::default()));
+        let mut i2c = MeteredI2c::new(I2cMock::new(None), metrics.clone());
+
+        i2c.write(ADDRESS, &[0x00, 0x01, 0x02]).unwrap();
+
+        let mut buffer = [0u8; 2];
+        i2c.write_read(ADDRESS, &[0x00], &mut buffer).unwrap();
+
+        let metrics = *metrics.lock().unwrap();
+        assert_eq!(2, metrics.i2c_transactions);
+        assert_eq!(6, metrics.i2c_bytes);
+        assert_eq!(0, metrics.i2c_errors);
+    }
+}
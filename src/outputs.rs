@@ -0,0 +1,265 @@
+//! # Outputs
+//!
+//! Render each frame to several outputs at once, e.g. the display, a live preview in
+//! the terminal & a recorder.
+#[cfg(feature = "preview")]
+use ht16k33::Display;
+
+#[cfg(feature = "preview")]
+use std::convert::Infallible;
+use std::error;
+use std::fmt;
+
+#[cfg(feature = "preview")]
+use super::{preview, TerminalSupport, BARGRAPH_DISPLAY_CHAR, BARGRAPH_RESOLUTION};
+use super::{Frame, Render};
+
+/// The error returned when an output of [Outputs](struct.Outputs.html) can't render
+/// a frame.
+pub type OutputError = Box<dyn error::Error + Send + Sync>;
+
+/// Renders every frame to each of its outputs, in the order they were added.
+///
+/// An output that fails doesn't stop the others rendering the frame, the first error
+/// is returned once they all have.
+///
+/// # Examples
+///
+/// ```
+/// extern crate ht16k33;
+/// extern crate led_bargraph;
+///
+/// use ht16k33::i2c_mock::I2cMock;
+/// use led_bargraph::{Bargraph, Frame, Outputs, Render, SimulatorBackend};
+/// # fn main() {
+///
+/// let mut bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+/// let mut recorder = SimulatorBackend::new();
+///
+/// {
+///     let mut outputs = Outputs::new();
+///     outputs.add(&mut bargraph);
+///     outputs.add(&mut recorder);
+///
+///     outputs.render(&Frame::from_value(3, 6)).unwrap();
+/// }
+///
+/// assert_eq!(bargraph.last_frame().as_ref(), recorder.last_frame());
+///
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Outputs<'a> {
+    outputs: Vec<Box<dyn Render<Error = OutputError> + 'a>>,
+}
+
+impl<'a> fmt::Debug for Outputs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Outputs")
+            .field("outputs", &self.outputs.len())
+            .finish()
+    }
+}
+
+impl<'a> Outputs<'a> {
+    /// Create Outputs without any outputs.
+    pub fn new() -> Self {
+        Outputs {
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Add an output after the existing outputs.
+    pub fn add<R>(&mut self, output: R)
+    where
+        R: Render + 'a,
+        R::Error: error::Error + Send + Sync + 'static,
+    {
+        self.outputs.push(Box::new(Boxed(output)));
+    }
+
+    /// How many outputs there are.
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Whether there aren't any outputs.
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+impl<'a> Render for Outputs<'a> {
+    type Error = OutputError;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), OutputError> {
+        let mut result = Ok(());
+
+        for output in self.outputs.iter_mut() {
+            let rendered = output.render(frame);
+            if result.is_ok() {
+                result = rendered;
+            }
+        }
+
+        result
+    }
+}
+
+// An output with its errors boxed, so that outputs with different errors can be kept
+// together.
+struct Boxed<R>(R);
+
+impl<R> Render for Boxed<R>
+where
+    R: Render,
+    R::Error: error::Error + Send + Sync + 'static,
+{
+    type Error = OutputError;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), OutputError> {
+        self.0.render(frame).map_err(|e| e.into())
+    }
+}
+
+/// Prints every frame on-screen, like [Bargraph::show()](struct.Bargraph.html#method.show)
+/// but without reading the display back from the device.
+#[cfg(feature = "preview")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerminalRenderer {
+    resolution: u8,
+    // What the terminal supports, detected each frame if `None`.
+    terminal: Option<TerminalSupport>,
+}
+
+#[cfg(feature = "preview")]
+impl Default for TerminalRenderer {
+    fn default() -> Self {
+        TerminalRenderer::new(BARGRAPH_RESOLUTION)
+    }
+}
+
+#[cfg(feature = "preview")]
+impl TerminalRenderer {
+    /// Create a TerminalRenderer printing the bars in use, from the bottom of the
+    /// display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    pub fn new(resolution: u8) -> Self {
+        assert!(
+            resolution > 0 && resolution <= BARGRAPH_RESOLUTION,
+            "Resolution [{}] must be between [1] and [{}]",
+            resolution,
+            BARGRAPH_RESOLUTION
+        );
+
+        TerminalRenderer {
+            resolution,
+            terminal: None,
+        }
+    }
+
+    /// Override what the terminal supports, rather than detecting it for each frame,
+    /// see [Bargraph::set_terminal()](struct.Bargraph.html#method.set_terminal).
+    pub fn set_terminal<T>(&mut self, terminal: T)
+    where
+        T: Into<Option<TerminalSupport>>,
+    {
+        self.terminal = terminal.into();
+    }
+}
+
+#[cfg(feature = "preview")]
+impl Render for TerminalRenderer {
+    type Error = Infallible;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), Infallible> {
+        // The same rate as `Bargraph::set_blink()`.
+        let display = if frame.blink {
+            Display::ONE_HZ
+        } else {
+            Display::ON
+        };
+
+        preview::print_leds(
+            &[&frame.bars[..self.resolution as usize]],
+            BARGRAPH_DISPLAY_CHAR,
+            display,
+            self.terminal,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use SimulatorBackend;
+
+    // Fails every frame.
+    struct Broken;
+
+    impl Render for Broken {
+        type Error = io::Error;
+
+        fn render(&mut self, _frame: &Frame) -> Result<(), io::Error> {
+            Err(io::Error::other("broken"))
+        }
+    }
+
+    #[test]
+    fn render_all() {
+        let mut first = SimulatorBackend::new();
+        let mut second = SimulatorBackend::new();
+        let frame = Frame::from_value(3, 6);
+
+        {
+            let mut outputs = Outputs::new();
+            assert!(outputs.is_empty());
+
+            outputs.add(&mut first);
+            outputs.add(&mut second);
+            assert_eq!(2, outputs.len());
+
+            outputs.render(&frame).unwrap();
+        }
+
+        assert_eq!(Some(&frame), first.last_frame());
+        assert_eq!(Some(&frame), second.last_frame());
+    }
+
+    #[test]
+    fn render_failed() {
+        let mut after = SimulatorBackend::new();
+
+        {
+            let mut outputs = Outputs::new();
+            outputs.add(Broken);
+            outputs.add(&mut after);
+
+            let error = outputs.render(&Frame::default()).unwrap_err();
+            assert_eq!("broken", error.to_string());
+        }
+
+        assert_eq!(1, after.frames().len());
+    }
+
+    #[test]
+    #[cfg(feature = "preview")]
+    fn terminal_renderer() {
+        TerminalRenderer::new(12)
+            .render(&Frame::from_value(25, 24))
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "preview")]
+    #[should_panic]
+    fn terminal_renderer_too_large() {
+        TerminalRenderer::new(BARGRAPH_RESOLUTION + 1);
+    }
+}
@@ -0,0 +1,569 @@
+//! # Pipeline
+//!
+//! Displaying a value is a pipeline: a source produces a [Sample](struct.Sample.html),
+//! e.g. a [ValueSource](trait.ValueSource.html) or a value received by a daemon, each
+//! [Transform](trait.Transform.html) of the [Pipeline](struct.Pipeline.html) processes
+//! it, & the [Frame](struct.Frame.html) of the result is displayed by anything
+//! implementing [Render](trait.Render.html).
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::render::{sample_frame, Look};
+use super::{
+    render, Bargraph, ColorMap, ColorScheme, Frame, Palette, Render, Sample, Status, Style,
+    Thresholds, BARGRAPH_RESOLUTION,
+};
+
+/// A step of a [Pipeline](struct.Pipeline.html), processing each sample before it's
+/// displayed.
+///
+/// Any `FnMut(Sample) -> Sample` closure is a Transform, e.g. for scripting the
+/// pipeline.
+pub trait Transform {
+    /// Process the sample, returning the sample for the next step.
+    fn apply(&mut self, sample: Sample) -> Sample;
+}
+
+impl<F> Transform for F
+where
+    F: FnMut(Sample) -> Sample,
+{
+    fn apply(&mut self, sample: Sample) -> Sample {
+        self(sample)
+    }
+}
+
+/// Convert readings from `min` (an empty display) to `max` (a full display) into the
+/// fraction of the display, e.g. a value received in units of the range.
+///
+/// Readings above `max` aren't clamped, so they overflow the display & blink.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale {
+    min: f32,
+    max: f32,
+}
+
+impl Scale {
+    /// Create a Scale from `min` to `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` isn't less than `max`.
+    pub fn new(min: f32, max: f32) -> Self {
+        assert!(min < max, "Scale [{}] must be less than [{}]", min, max);
+
+        Scale { min, max }
+    }
+}
+
+impl Transform for Scale {
+    fn apply(&mut self, sample: Sample) -> Sample {
+        Sample {
+            value: (sample.value - self.min) / (self.max - self.min),
+            ..sample
+        }
+    }
+}
+
+/// Smooth noisy samples with an exponential moving average, like the smoothing of
+/// [UpdaterConfig](struct.UpdaterConfig.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Smooth {
+    smoothing: f32,
+    average: Option<f32>,
+}
+
+impl Smooth {
+    /// Create a Smooth keeping `smoothing` of the average when a sample is processed,
+    /// from `0.0` (no smoothing) to just below `1.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the smoothing isn't from `0.0` to just below `1.0`.
+    pub fn new(smoothing: f32) -> Self {
+        assert!(
+            (0.0..1.0).contains(&smoothing),
+            "Smoothing [{}] must be from [0.0] to below [1.0]",
+            smoothing
+        );
+
+        Smooth {
+            smoothing,
+            average: None,
+        }
+    }
+}
+
+impl Transform for Smooth {
+    fn apply(&mut self, sample: Sample) -> Sample {
+        let value = match self.average {
+            Some(average) => average * self.smoothing + sample.value * (1.0 - self.smoothing),
+            None => sample.value,
+        };
+        self.average = Some(value);
+
+        Sample { value, ..sample }
+    }
+}
+
+/// Alert when the value reaches a fraction of the display, e.g. a temperature that
+/// needs attention. A sample that already has a status keeps it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    level: f32,
+}
+
+impl Threshold {
+    /// Create a Threshold alerting at or above the level.
+    pub fn new(level: f32) -> Self {
+        Threshold { level }
+    }
+}
+
+impl Transform for Threshold {
+    fn apply(&mut self, sample: Sample) -> Sample {
+        match sample.status {
+            None if sample.value >= self.level => Sample::with_status(sample.value, Status::Alert),
+            _ => sample,
+        }
+    }
+}
+
+/// The transforms between a source & the renderers, & how the samples are drawn as
+/// frames.
+///
+/// A Pipeline for a [Bargraph](struct.Bargraph.html) is created with
+/// [Bargraph::pipeline()](struct.Bargraph.html#method.pipeline), drawing the samples
+/// like [Bargraph::update_sample()](struct.Bargraph.html#method.update_sample).
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{LedColor, Pipeline, Render, Sample, Scale, SimulatorBackend, Smooth};
+/// # fn main() {
+///
+/// // Smooth readings in °C, from 10°C to 35°C.
+/// let mut pipeline = Pipeline::new(24);
+/// pipeline.add_transform(Scale::new(10.0, 35.0));
+/// pipeline.add_transform(Smooth::new(0.5));
+///
+/// let mut simulator = SimulatorBackend::new();
+/// for reading in [22.5, 35.0].iter() {
+///     pipeline.push(Sample::new(*reading), &mut simulator).unwrap();
+/// }
+///
+/// let frame = simulator.last_frame().unwrap();
+/// assert_eq!(LedColor::Red, frame.bars[17]);
+///
+/// # }
+/// ```
+pub struct Pipeline {
+    transforms: Vec<Box<dyn Transform + Send>>,
+    range: u8,
+    resolution: u8,
+    colors: Option<ColorMap>,
+    scheme: ColorScheme,
+    thresholds: Option<Thresholds>,
+    style: Style,
+    palette: Option<Arc<dyn Palette + Send + Sync>>,
+    reversed: bool,
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("transforms", &self.transforms.len())
+            .field("range", &self.range)
+            .field("resolution", &self.resolution)
+            .field("colors", &self.colors)
+            .field("scheme", &self.scheme)
+            .field("thresholds", &self.thresholds)
+            .field("style", &self.style)
+            .field("palette", &self.palette.is_some())
+            .field("reversed", &self.reversed)
+            .finish()
+    }
+}
+
+impl Pipeline {
+    /// Create a Pipeline without any transforms, drawing the samples against the range
+    /// on a full display with the default color scheme.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    pub fn new(range: u8) -> Self {
+        Pipeline::within(range, BARGRAPH_RESOLUTION)
+    }
+
+    // A pipeline drawing on the bars in use, from the bottom of the display.
+    fn within(range: u8, resolution: u8) -> Self {
+        assert!(
+            range > 0 && range <= resolution,
+            "Range [{}] must be between [1] and [{}]",
+            range,
+            resolution
+        );
+
+        Pipeline {
+            transforms: Vec::new(),
+            range,
+            resolution,
+            colors: None,
+            scheme: ColorScheme::default(),
+            thresholds: None,
+            style: Style::Bar,
+            palette: None,
+            reversed: false,
+        }
+    }
+
+    /// Add a transform after the existing transforms.
+    pub fn add_transform<T>(&mut self, transform: T)
+    where
+        T: Transform + Send + 'static,
+    {
+        self.transforms.push(Box::new(transform));
+    }
+
+    /// Color the filled values by how full the display is, rather than with the color
+    /// scheme, see [Frame::from_colored_value()](struct.Frame.html#method.from_colored_value).
+    pub fn set_colors(&mut self, colors: Option<ColorMap>) {
+        self.colors = colors;
+    }
+
+    /// Color the filled values green, yellow or red by how full the display is, rather
+    /// than with the fill & head of the color scheme, see
+    /// [Thresholds](struct.Thresholds.html). Colors set by
+    /// [set_colors()](#method.set_colors) take precedence.
+    pub fn set_thresholds(&mut self, thresholds: Option<Thresholds>) {
+        self.thresholds = thresholds;
+    }
+
+    /// Draw the filled values in the style, see [Style](enum.Style.html). Colors set by
+    /// [set_colors()](#method.set_colors) always fill.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// Color each bar with the palette, instead of with the color scheme & thresholds,
+    /// see [Palette](trait.Palette.html). Colors set by
+    /// [set_colors()](#method.set_colors) take precedence.
+    pub fn set_palette<P>(&mut self, palette: P)
+    where
+        P: Palette + Send + Sync + 'static,
+    {
+        self.palette = Some(Arc::new(palette));
+    }
+
+    // How the samples are drawn.
+    fn look(&self) -> Look<'_> {
+        Look {
+            scheme: &self.scheme,
+            thresholds: self.thresholds.as_ref(),
+            style: self.style,
+            palette: self.palette.as_deref(),
+        }
+    }
+
+    /// The range the samples are drawn against.
+    pub fn range(&self) -> u8 {
+        self.range
+    }
+
+    /// Process the sample with each transform in turn.
+    pub fn process(&mut self, sample: Sample) -> Sample {
+        self.transforms
+            .iter_mut()
+            .fold(sample, |sample, transform| transform.apply(sample))
+    }
+
+    /// The frame drawing the sample, filling its fraction of the range rounded to the
+    /// nearest value.
+    ///
+    /// The frame blinks if the value overflows, or the status is
+    /// [Alert](enum.Status.html#variant.Alert) or [Failed](enum.Status.html#variant.Failed).
+    pub fn frame(&self, sample: &Sample) -> Frame {
+        sample_frame(
+            sample,
+            self.range,
+            self.resolution,
+            self.colors.as_ref(),
+            &self.look(),
+            self.reversed,
+        )
+    }
+
+    /// The frame drawing the sample like [frame()](#method.frame), colored by how full
+    /// the display is, e.g. to show a failure in red.
+    pub fn frame_colored(&self, sample: &Sample, colors: &ColorMap) -> Frame {
+        sample_frame(
+            sample,
+            self.range,
+            self.resolution,
+            Some(colors),
+            &self.look(),
+            self.reversed,
+        )
+    }
+
+    /// The frame drawing the span of two samples as a band, with a third sample as a
+    /// marker, see [Frame::from_band()](struct.Frame.html#method.from_band). Each
+    /// sample is drawn at the top value that [frame()](#method.frame) would fill.
+    ///
+    /// The frame blinks if a value overflows, or the status of the marker is
+    /// [Alert](enum.Status.html#variant.Alert) or [Failed](enum.Status.html#variant.Failed).
+    ///
+    /// # Arguments
+    ///
+    /// * `low` - The bottom of the band.
+    /// * `high` - The top of the band.
+    /// * `marker` - The marker, e.g. the latest sample.
+    pub fn frame_band(&self, low: &Sample, high: &Sample, marker: &Sample) -> Frame {
+        let range = self.range;
+        let value = |sample: &Sample| {
+            let filled = (sample.value.max(0.0) * f32::from(range))
+                .round()
+                .min(f32::from(range) + 1.0) as u8;
+
+            filled.saturating_sub(1)
+        };
+
+        let mut frame = Frame::within(self.resolution, |bars| {
+            render::fill_band(bars, value(low), value(high), value(marker), range)
+        });
+
+        frame.blink |=
+            marker.status == Some(Status::Alert) || marker.status == Some(Status::Failed);
+
+        if self.reversed {
+            frame.bars[..self.resolution as usize].reverse();
+        }
+
+        frame
+    }
+
+    /// Process the sample & render its frame, returning the processed sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - The sample from the source.
+    /// * `renderer` - Where to display the frame.
+    pub fn push<R>(&mut self, sample: Sample, renderer: &mut R) -> Result<Sample, R::Error>
+    where
+        R: Render + ?Sized,
+    {
+        let sample = self.process(sample);
+
+        renderer.render(&self.frame(&sample))?;
+
+        Ok(sample)
+    }
+}
+
+impl<I2C, E> Bargraph<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Create a [Pipeline](struct.Pipeline.html) drawing the samples against the range
+    /// like this Bargraph, with its resolution, color scheme, thresholds, style, palette
+    /// & reversal.
+    ///
+    /// Frames from the Pipeline are rendered as-is, e.g. by
+    /// [render()](trait.Render.html#tymethod.render), settings changed later aren't
+    /// applied to them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than the [resolution()](#method.resolution).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, Sample, Smooth};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.set_reversed(true);
+    ///
+    /// let mut pipeline = bargraph.pipeline(12);
+    /// pipeline.add_transform(Smooth::new(0.8));
+    ///
+    /// pipeline.push(Sample::new(0.5), &mut bargraph).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn pipeline(&self, range: u8) -> Pipeline {
+        let mut pipeline = Pipeline::within(range, self.resolution);
+        pipeline.scheme = self.scheme;
+        pipeline.thresholds = self.thresholds;
+        pipeline.style = self.style;
+        pipeline.palette = self.palette.clone();
+        pipeline.reversed = self.reversed;
+
+        pipeline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use {LedColor, SimulatorBackend};
+
+    #[test]
+    fn scale() {
+        let mut scale = Scale::new(0.0, 10.0);
+
+        assert_eq!(Sample::new(0.7), scale.apply(Sample::new(7.0)));
+        assert_eq!(Sample::new(-0.1), scale.apply(Sample::new(-1.0)));
+        assert_eq!(
+            Sample::with_status(1.5, Status::Complete),
+            scale.apply(Sample::with_status(15.0, Status::Complete))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn scale_invalid() {
+        Scale::new(10.0, 10.0);
+    }
+
+    #[test]
+    fn smooth() {
+        let mut smooth = Smooth::new(0.5);
+
+        assert_eq!(Sample::new(0.8), smooth.apply(Sample::new(0.8)));
+        assert_eq!(Sample::new(0.4), smooth.apply(Sample::new(0.0)));
+        assert_eq!(Sample::new(0.2), smooth.apply(Sample::new(0.0)));
+    }
+
+    #[test]
+    fn threshold() {
+        let mut threshold = Threshold::new(0.75);
+
+        assert_eq!(Sample::new(0.5), threshold.apply(Sample::new(0.5)));
+        assert_eq!(
+            Sample::with_status(0.75, Status::Alert),
+            threshold.apply(Sample::new(0.75))
+        );
+        assert_eq!(
+            Sample::with_status(1.0, Status::Complete),
+            threshold.apply(Sample::with_status(1.0, Status::Complete))
+        );
+    }
+
+    #[test]
+    fn process() {
+        let mut pipeline = Pipeline::new(24);
+        pipeline.add_transform(Scale::new(0.0, 24.0));
+        pipeline.add_transform(|sample: Sample| Sample {
+            value: 1.0 - sample.value,
+            ..sample
+        });
+
+        assert_eq!(Sample::new(0.75), pipeline.process(Sample::new(6.0)));
+    }
+
+    #[test]
+    fn push() {
+        let mut pipeline = Pipeline::new(12);
+        pipeline.set_colors(Some(ColorMap::new(vec![(0, LedColor::Green)])));
+        let mut simulator = SimulatorBackend::new();
+
+        let sample = pipeline
+            .push(Sample::with_status(0.25, Status::Alert), &mut simulator)
+            .unwrap();
+
+        assert_eq!(Sample::with_status(0.25, Status::Alert), sample);
+        let mut expected =
+            Frame::from_colored_value(3, 12, &ColorMap::new(vec![(0, LedColor::Green)]));
+        expected.blink = true;
+        assert_eq!(Some(&expected), simulator.last_frame());
+    }
+
+    #[test]
+    fn frame_thresholds() {
+        let mut pipeline = Pipeline::new(6);
+        pipeline.set_thresholds(Some(Thresholds {
+            warn: Some(50),
+            crit: Some(80),
+        }));
+
+        assert_eq!(
+            "GGGGGGGG...G...G...G...G",
+            pipeline.frame(&Sample::new(0.3)).to_string()
+        );
+        assert_eq!(
+            "YYYYYYYYYYYY...G...G...G",
+            pipeline.frame(&Sample::new(0.5)).to_string()
+        );
+        assert_eq!(
+            "RRRRRRRRRRRRRRRRRRRR...G",
+            pipeline.frame(&Sample::new(0.8)).to_string()
+        );
+    }
+
+    #[test]
+    fn frame_band() {
+        let pipeline = Pipeline::new(6);
+
+        let frame = pipeline.frame_band(
+            &Sample::new(0.0),
+            &Sample::new(0.5),
+            &Sample::with_status(0.35, Status::Alert),
+        );
+
+        assert_eq!("GGGGRRRRGGGG............", frame.to_string());
+        assert!(frame.blink);
+    }
+
+    #[test]
+    fn bargraph_pipeline() {
+        let mut bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+        bargraph.set_resolution(4);
+        bargraph.set_reversed(true);
+
+        let mut pipeline = bargraph.pipeline(4);
+        pipeline.push(Sample::new(0.5), &mut bargraph).unwrap();
+
+        // Each value is a single bar, its top bar.
+        assert_eq!(
+            "GGRR....................",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn bargraph_pipeline_style() {
+        let mut bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+        bargraph.set_resolution(12);
+        bargraph.set_style(Style::Dot);
+
+        let mut pipeline = bargraph.pipeline(12);
+        pipeline.push(Sample::new(0.5), &mut bargraph).unwrap();
+        assert_eq!(
+            ".....R..................",
+            bargraph.last_frame().unwrap().to_string()
+        );
+
+        pipeline.set_palette(|_bar: u8, filled: bool, _range: u8| match filled {
+            true => LedColor::Yellow,
+            false => LedColor::Green,
+        });
+        pipeline.push(Sample::new(0.5), &mut bargraph).unwrap();
+        assert_eq!(
+            "GGGGGYGGGGGG............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! # Power
+//!
+//! The Texas Instruments INA219 & INA260 power monitors, read over the same I2C bus as
+//! the display, e.g. through `shared-bus`, as a [ValueSource](trait.ValueSource.html).
+use hal::blocking::i2c::WriteRead;
+
+use std::error;
+use std::fmt::{self, Debug};
+
+use super::{Sample, ValueSource};
+
+// The registers of the monitors, the INA219's "shunt voltage" is the INA260's current.
+const SHUNT_REGISTER: u8 = 0x01;
+const BUS_VOLTAGE_REGISTER: u8 = 0x02;
+
+/// The model of a [PowerMeter](struct.PowerMeter.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ina {
+    /// The INA219, measuring the voltage across an external shunt resistor.
+    Ina219,
+    /// The INA260, with an integrated 2mΩ shunt resistor.
+    Ina260,
+}
+
+/// What a [PowerMeter](struct.PowerMeter.html) displays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Electrical {
+    /// The current in A.
+    Current,
+    /// The power in W.
+    Power,
+}
+
+/// The error returned by a [PowerMeter](struct.PowerMeter.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum PowerError<E> {
+    /// An I2C transaction with the register failed.
+    I2c {
+        /// The register being read.
+        register: u8,
+        /// The error returned by the I2C device.
+        source: E,
+    },
+}
+
+impl<E> fmt::Display for PowerError<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PowerError::I2c {
+                register,
+                ref source,
+            } => write!(
+                f,
+                "Failed to read register [{:#04x}]: {:?}",
+                register, source
+            ),
+        }
+    }
+}
+
+impl<E> error::Error for PowerError<E> where E: Debug {}
+
+/// An INA219 or INA260 power monitor, with its default configuration. Its samples are
+/// the magnitude of the current or power against the maximum, greater values blink the
+/// display.
+///
+/// # Examples
+///
+/// ```
+/// extern crate embedded_hal_mock;
+/// extern crate led_bargraph;
+///
+/// use embedded_hal_mock::i2c::{Mock, Transaction};
+/// use led_bargraph::{Ina, PowerMeter, Sample, ValueSource};
+/// # fn main() {
+///
+/// // 10mV across the 0.1Ω shunt, 100mA.
+/// let i2c = Mock::new(&[
+///     Transaction::write_read(0x40, vec![0x01], vec![0x03, 0xE8]),
+///     Transaction::write_read(0x40, vec![0x02], vec![0x5D, 0xC2]),
+/// ]);
+///
+/// let mut meter = PowerMeter::new(i2c, 0x40, Ina::Ina219, 0.1, 0.5);
+/// assert_eq!(Ok(Some(Sample::new(0.2))), meter.poll());
+///
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PowerMeter<I2C> {
+    i2c: I2C,
+    address: u8,
+    model: Ina,
+    shunt_ohms: f64,
+    electrical: Electrical,
+    max: f64,
+}
+
+impl<I2C, E> PowerMeter<I2C>
+where
+    I2C: WriteRead<Error = E>,
+{
+    /// Create a PowerMeter, its samples are the current, see
+    /// [set_electrical()](#method.set_electrical).
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C bus of the monitor.
+    /// * `address` - The address of the monitor, e.g. `0x40`.
+    /// * `model` - The model of the monitor.
+    /// * `shunt_ohms` - The INA219's shunt resistor, ignored by the INA260.
+    /// * `max` - The current in A, or power in W, of a full display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shunt_ohms` or `max` isn't greater than `0`.
+    pub fn new(i2c: I2C, address: u8, model: Ina, shunt_ohms: f64, max: f64) -> Self {
+        assert!(
+            shunt_ohms > 0.0 && max > 0.0,
+            "Shunt [{}] & maximum [{}] must be greater than [0]",
+            shunt_ohms,
+            max
+        );
+
+        PowerMeter {
+            i2c,
+            address,
+            model,
+            shunt_ohms,
+            electrical: Electrical::Current,
+            max,
+        }
+    }
+
+    /// Set what the samples are, the current by default.
+    pub fn set_electrical(&mut self, electrical: Electrical) {
+        self.electrical = electrical;
+    }
+
+    /// Measure the current in A & the power in W, negative when the current flows
+    /// backwards through the shunt.
+    pub fn measure(&mut self) -> Result<(f64, f64), PowerError<E>> {
+        let shunt = self.read_register(SHUNT_REGISTER)?;
+        let bus = self.read_register(BUS_VOLTAGE_REGISTER)?;
+
+        let (amps, volts) = match self.model {
+            // 10µV & 4mV, the lowest 3 bits of the bus voltage are flags.
+            Ina::Ina219 => (
+                f64::from(shunt as i16) * 10e-6 / self.shunt_ohms,
+                f64::from(bus >> 3) * 4e-3,
+            ),
+            // 1.25mA & 1.25mV.
+            Ina::Ina260 => (f64::from(shunt as i16) * 1.25e-3, f64::from(bus) * 1.25e-3),
+        };
+
+        Ok((amps, amps * volts))
+    }
+
+    /// Destroy the PowerMeter, returning its I2C bus.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u16, PowerError<E>> {
+        let mut bytes = [0; 2];
+        self.i2c
+            .write_read(self.address, &[register], &mut bytes)
+            .map(|_| u16::from_be_bytes(bytes))
+            .map_err(|source| PowerError::I2c { register, source })
+    }
+}
+
+impl<I2C, E> ValueSource for PowerMeter<I2C>
+where
+    I2C: WriteRead<Error = E>,
+{
+    type Error = PowerError<E>;
+
+    fn poll(&mut self) -> Result<Option<Sample>, Self::Error> {
+        let (amps, watts) = self.measure()?;
+
+        let reading = match self.electrical {
+            Electrical::Current => amps,
+            Electrical::Power => watts,
+        };
+
+        Ok(Some(Sample::new((reading.abs() / self.max) as f32)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use embedded_hal_mock::MockError;
+
+    use std::io::ErrorKind;
+
+    const ADDRESS: u8 = 0x40;
+
+    // The register reads of `measurements` measurements.
+    fn registers(shunt: u16, bus: u16, measurements: usize) -> Vec<I2cTransaction> {
+        (0..measurements)
+            .flat_map(|_| {
+                vec![
+                    I2cTransaction::write_read(ADDRESS, vec![0x01], shunt.to_be_bytes().to_vec()),
+                    I2cTransaction::write_read(ADDRESS, vec![0x02], bus.to_be_bytes().to_vec()),
+                ]
+            })
+            .collect()
+    }
+
+    fn assert_near(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "{} != {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn ina219() {
+        // 10mV across 0.1Ω at 12V, with the "conversion ready" flag.
+        let i2c = I2cMock::new(&registers(1000, 3000 << 3 | 0b010, 3));
+        let mut meter = PowerMeter::new(i2c, ADDRESS, Ina::Ina219, 0.1, 2.0);
+
+        let (amps, watts) = meter.measure().unwrap();
+        assert_near(0.1, amps);
+        assert_near(1.2, watts);
+
+        assert_eq!(Ok(Some(Sample::new(0.05))), meter.poll());
+        meter.set_electrical(Electrical::Power);
+        assert_eq!(Ok(Some(Sample::new(0.6))), meter.poll());
+
+        meter.destroy().done();
+    }
+
+    #[test]
+    fn ina219_shunt() {
+        // The same 10mV across a 0.01Ω shunt is 1A.
+        let i2c = I2cMock::new(&registers(1000, 3000 << 3, 1));
+        let mut meter = PowerMeter::new(i2c, ADDRESS, Ina::Ina219, 0.01, 2.0);
+
+        let (amps, watts) = meter.measure().unwrap();
+        assert_near(1.0, amps);
+        assert_near(12.0, watts);
+
+        meter.destroy().done();
+    }
+
+    #[test]
+    fn ina260() {
+        // -100mA at 5V, the shunt is ignored.
+        let i2c = I2cMock::new(&registers((-80i16) as u16, 4000, 3));
+        let mut meter = PowerMeter::new(i2c, ADDRESS, Ina::Ina260, 0.1, 0.4);
+
+        let (amps, watts) = meter.measure().unwrap();
+        assert_near(-0.1, amps);
+        assert_near(-0.5, watts);
+
+        // The magnitude, overflowing the maximum power.
+        assert_eq!(Ok(Some(Sample::new(0.25))), meter.poll());
+        meter.set_electrical(Electrical::Power);
+        assert_eq!(Ok(Some(Sample::new(1.25))), meter.poll());
+
+        meter.destroy().done();
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_max() {
+        PowerMeter::new(I2cMock::new(&[]), ADDRESS, Ina::Ina260, 0.1, 0.0);
+    }
+
+    #[test]
+    fn errors() {
+        let i2c = I2cMock::new(&[
+            I2cTransaction::write_read(ADDRESS, vec![0x01], vec![0, 0]),
+            I2cTransaction::write_read(ADDRESS, vec![0x02], vec![0, 0])
+                .with_error(MockError::Io(ErrorKind::Other)),
+        ]);
+        let mut meter = PowerMeter::new(i2c, ADDRESS, Ina::Ina219, 0.1, 1.0);
+
+        let error = meter.poll().unwrap_err();
+        assert_eq!(
+            "Failed to read register [0x02]: Io(Other)",
+            error.to_string()
+        );
+
+        meter.destroy().done();
+    }
+}
@@ -0,0 +1,345 @@
+//! # Preview
+//!
+//! Show the LEDs of a display on-screen, using ANSI colors & box-drawing characters
+//! when the terminal supports them.
+use ansi_term::Colour::{Black, Fixed, Green, Red, White, Yellow};
+use ansi_term::Style;
+
+use ht16k33::Display;
+
+use std::env;
+use std::io::{self, IsTerminal};
+use std::time::Duration;
+
+use super::display::blink_rate;
+use super::LedColor;
+
+/// The colors a terminal supports, see [TerminalSupport](struct.TerminalSupport.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSupport {
+    /// No ANSI codes, e.g. a pipe or `NO_COLOR`, the LEDs are shown as the letter of
+    /// their color.
+    None,
+    /// The 8 basic ANSI colors.
+    Basic,
+    /// The 256 extended ANSI colors.
+    Extended,
+}
+
+/// What the terminal showing a preview supports, e.g. for
+/// [Bargraph::set_terminal()](struct.Bargraph.html#method.set_terminal).
+///
+/// Unless it's overridden, it's detected each time the display is shown, see
+/// [detect()](#method.detect).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerminalSupport {
+    /// Whether the box & the LEDs are drawn with Unicode characters, otherwise ASCII.
+    pub unicode: bool,
+    /// The colors of the LEDs.
+    pub colors: ColorSupport,
+}
+
+impl TerminalSupport {
+    /// Detect what the terminal on stdout supports.
+    ///
+    /// Colors are only used on a terminal, not a pipe, & not when `NO_COLOR` is set or
+    /// `TERM` is `dumb`. The extended colors are used when `TERM` has `256color` or
+    /// `COLORTERM` is set. Unicode is used when the locale, from `LC_ALL`, `LC_CTYPE` or
+    /// `LANG`, is UTF-8.
+    pub fn detect() -> Self {
+        TerminalSupport::from_env(io::stdout().is_terminal(), |name| env::var(name).ok())
+    }
+
+    // Detect the support from the environment variables of `var`.
+    fn from_env<F>(terminal: bool, var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let set = |name| var(name).filter(|value| !value.is_empty());
+
+        let term = set("TERM").unwrap_or_default();
+        let colors = if !terminal || set("NO_COLOR").is_some() || term == "dumb" {
+            ColorSupport::None
+        } else if term.contains("256color") || set("COLORTERM").is_some() {
+            ColorSupport::Extended
+        } else {
+            ColorSupport::Basic
+        };
+
+        // The first locale variable set wins, like `setlocale()`.
+        let locale = set("LC_ALL")
+            .or_else(|| set("LC_CTYPE"))
+            .or_else(|| set("LANG"))
+            .unwrap_or_default()
+            .to_uppercase();
+        let unicode = locale.contains("UTF-8") || locale.contains("UTF8");
+
+        TerminalSupport { unicode, colors }
+    }
+}
+
+// The LED drawn when the terminal doesn't support Unicode.
+const ASCII_LED_CHAR: &str = "#";
+
+// The time for one on & off cycle of a blinking display, `None` if it isn't blinking.
+fn blink_period(display: Display) -> Option<Duration> {
+    blink_rate(display).period()
+}
+
+// Whether a display that started blinking `elapsed` ago is in the on half of its cycle.
+pub(crate) fn blink_visible(display: Display, elapsed: Duration) -> bool {
+    match blink_period(display) {
+        Some(period) => elapsed.as_nanos() % period.as_nanos() < period.as_nanos() / 2,
+        None => true,
+    }
+}
+
+// Print rows of LEDs inside a box, each LED is printed using `led_char`. The terminal
+// is detected if it's `None`.
+//
+// Unicode box-drawing characters: https://en.wikipedia.org/wiki/Box-drawing_character
+pub(crate) fn print_leds<R>(
+    rows: &[R],
+    led_char: &str,
+    display: Display,
+    terminal: Option<TerminalSupport>,
+) where
+    R: AsRef<[LedColor]>,
+{
+    let terminal = terminal.unwrap_or_else(TerminalSupport::detect);
+    print!("{}", format_leds(rows, led_char, display, None, terminal));
+}
+
+// Print rows of LEDs like `print_leds()`, emulating blinking for terminals that ignore
+// the ANSI blink attribute. The LEDs are off for the off half of the blink cycle, at
+// `elapsed` since the display started blinking.
+pub(crate) fn print_leds_at<R>(
+    rows: &[R],
+    led_char: &str,
+    display: Display,
+    elapsed: Duration,
+    terminal: Option<TerminalSupport>,
+) where
+    R: AsRef<[LedColor]>,
+{
+    let terminal = terminal.unwrap_or_else(TerminalSupport::detect);
+    print!(
+        "{}",
+        format_leds(rows, led_char, display, Some(elapsed), terminal)
+    );
+}
+
+fn format_leds<R>(
+    rows: &[R],
+    led_char: &str,
+    display: Display,
+    elapsed: Option<Duration>,
+    terminal: TerminalSupport,
+) -> String
+where
+    R: AsRef<[LedColor]>,
+{
+    let width = rows.first().map_or(0, |row| row.as_ref().len());
+
+    let paint = |style: Style, text: &str| match terminal.colors {
+        ColorSupport::None => text.to_string(),
+        ColorSupport::Basic | ColorSupport::Extended => style.paint(text).to_string(),
+    };
+    let frame = White.normal();
+
+    let (top_left, top_right, bottom_left, bottom_right, line, side) = if terminal.unicode {
+        (
+            "\u{2554}", "\u{2557}", "\u{255A}", "\u{255D}", "\u{2550}", "\u{2551}",
+        )
+    } else {
+        ("+", "+", "+", "+", "-", "|")
+    };
+
+    let mut leds = paint(frame, top_left) + &paint(frame, &line.repeat(width));
+    leds += &paint(frame, top_right);
+    leds += "\n";
+
+    let mut style = Style::new();
+
+    let visible = match elapsed {
+        Some(elapsed) => blink_visible(display, elapsed),
+        None => {
+            if blink_period(display).is_some() {
+                style = style.blink();
+            }
+            true
+        }
+    };
+
+    for row in rows {
+        leds += &paint(frame, side);
+
+        for led in row.as_ref().iter() {
+            let led = if visible { led } else { &LedColor::Off };
+
+            leds += &match terminal.colors {
+                // Without colors, the LEDs are told apart by their letter.
+                ColorSupport::None => match led {
+                    LedColor::Green => "G",
+                    LedColor::Red => "R",
+                    LedColor::Yellow => "Y",
+                    LedColor::Off => ".",
+                }
+                .to_string(),
+                ColorSupport::Basic | ColorSupport::Extended => {
+                    let color = match led {
+                        LedColor::Green => style.fg(Green),
+                        LedColor::Red => style.fg(Red),
+                        LedColor::Yellow => style.fg(Yellow),
+                        LedColor::Off if terminal.colors == ColorSupport::Extended => {
+                            style.fg(Fixed(238)) // Dark grey.
+                        }
+                        LedColor::Off => style.fg(Black).bold(), // Bright black, i.e. grey.
+                    };
+
+                    let led_char = if terminal.unicode {
+                        led_char
+                    } else {
+                        ASCII_LED_CHAR
+                    };
+                    paint(color, led_char)
+                }
+            };
+        }
+
+        leds += &paint(frame, side);
+        leds += "\n";
+    }
+
+    leds += &paint(frame, bottom_left);
+    leds += &paint(frame, &line.repeat(width));
+    leds += &paint(frame, bottom_right);
+    leds += "\n";
+
+    leds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blink_period_rates() {
+        assert_eq!(None, blink_period(Display::ON));
+        assert_eq!(None, blink_period(Display::OFF));
+        assert_eq!(Some(Duration::from_secs(2)), blink_period(Display::HALF_HZ));
+        assert_eq!(Some(Duration::from_secs(1)), blink_period(Display::ONE_HZ));
+        assert_eq!(
+            Some(Duration::from_millis(500)),
+            blink_period(Display::TWO_HZ)
+        );
+    }
+
+    #[test]
+    fn blink_visible_phases() {
+        assert!(blink_visible(Display::ON, Duration::from_millis(700)));
+
+        assert!(blink_visible(Display::ONE_HZ, Duration::from_millis(0)));
+        assert!(blink_visible(Display::ONE_HZ, Duration::from_millis(499)));
+        assert!(!blink_visible(Display::ONE_HZ, Duration::from_millis(500)));
+        assert!(!blink_visible(Display::ONE_HZ, Duration::from_millis(999)));
+        assert!(blink_visible(Display::ONE_HZ, Duration::from_millis(1000)));
+
+        assert!(!blink_visible(Display::TWO_HZ, Duration::from_millis(300)));
+        assert!(blink_visible(Display::HALF_HZ, Duration::from_millis(900)));
+    }
+
+    #[test]
+    fn detect_terminal() {
+        let detect = |terminal, vars: &[(&str, &str)]| {
+            TerminalSupport::from_env(terminal, |name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+
+        let support = |unicode, colors| TerminalSupport { unicode, colors };
+
+        assert_eq!(
+            support(true, ColorSupport::Extended),
+            detect(true, &[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")])
+        );
+        assert_eq!(
+            support(false, ColorSupport::Basic),
+            detect(true, &[("TERM", "vt100"), ("LANG", "C")])
+        );
+        assert_eq!(
+            support(true, ColorSupport::Extended),
+            detect(true, &[("COLORTERM", "truecolor"), ("LC_CTYPE", "C.utf8")])
+        );
+
+        // The first locale variable set wins.
+        assert_eq!(
+            support(false, ColorSupport::Basic),
+            detect(true, &[("LC_ALL", "POSIX"), ("LANG", "en_US.UTF-8")])
+        );
+
+        // No colors on a pipe, a dumb terminal or with `NO_COLOR`.
+        let none = support(false, ColorSupport::None);
+        assert_eq!(none, detect(false, &[("TERM", "xterm-256color")]));
+        assert_eq!(none, detect(true, &[("TERM", "dumb")]));
+        assert_eq!(
+            none,
+            detect(true, &[("TERM", "xterm-256color"), ("NO_COLOR", "1")])
+        );
+    }
+
+    #[test]
+    fn format_leds_plain() {
+        let leds = [
+            LedColor::Green,
+            LedColor::Yellow,
+            LedColor::Red,
+            LedColor::Off,
+        ];
+        let plain = TerminalSupport {
+            unicode: false,
+            colors: ColorSupport::None,
+        };
+
+        assert_eq!(
+            "+----+\n|GYR.|\n+----+\n",
+            format_leds(&[leds], "\u{258A}", Display::ON, None, plain)
+        );
+
+        // Emulated blinking is off for the second half of the cycle.
+        assert_eq!(
+            "+----+\n|....|\n+----+\n",
+            format_leds(
+                &[leds],
+                "\u{258A}",
+                Display::ONE_HZ,
+                Some(Duration::from_millis(700)),
+                plain
+            )
+        );
+
+        let unicode = TerminalSupport {
+            unicode: true,
+            ..plain
+        };
+        assert_eq!(
+            "\u{2554}\u{2550}\u{2557}\n\u{2551}G\u{2551}\n\u{255A}\u{2550}\u{255D}\n",
+            format_leds(&[[LedColor::Green]], "\u{258A}", Display::ON, None, unicode)
+        );
+    }
+
+    #[test]
+    fn format_leds_basic() {
+        let basic = TerminalSupport {
+            unicode: false,
+            colors: ColorSupport::Basic,
+        };
+
+        let leds = format_leds(&[[LedColor::Red]], "\u{258A}", Display::ON, None, basic);
+        assert!(leds.contains(&Red.paint("#").to_string()));
+        assert!(!leds.contains("\u{258A}"));
+        assert!(!leds.contains("38;5;"));
+    }
+}
@@ -0,0 +1,137 @@
+//! # Recovery
+//!
+//! Release an I2C bus held low by a device that was interrupted mid-transaction, by
+//! clocking SCL until the device lets go of SDA.
+use hal::blocking::delay::DelayUs;
+use hal::digital::v2::OutputPin;
+
+// Enough clock pulses for the device to finish sending a byte & its acknowledge.
+const RECOVERY_PULSES: u8 = 9;
+
+// Half of a 100kHz clock period.
+const RECOVERY_HALF_PERIOD_US: u16 = 5;
+
+/// Toggle the SCL line of a stuck I2C bus to release the device holding it.
+///
+/// The pin must drive SCL directly, so the I2C peripheral has to release the line
+/// first, e.g. by switching the pin to GPIO mode. SCL is left high.
+///
+/// Use it with [Bargraph::set_bus_recovery()](struct.Bargraph.html#method.set_bus_recovery)
+/// to recover automatically after repeated failures.
+///
+/// # Errors
+///
+/// Returns the error of the pin if it can't be driven.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate embedded_hal;
+/// # extern crate led_bargraph;
+/// # use embedded_hal::blocking::delay::DelayUs;
+/// # use embedded_hal::digital::v2::OutputPin;
+/// # use std::convert::Infallible;
+/// use led_bargraph::recover_bus;
+/// # struct Pin;
+/// # impl OutputPin for Pin {
+/// #     type Error = Infallible;
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # struct Delay;
+/// # impl DelayUs<u16> for Delay {
+/// #     fn delay_us(&mut self, _us: u16) {}
+/// # }
+/// # fn main() {
+/// # let mut scl = Pin;
+/// # let mut delay = Delay;
+///
+/// recover_bus(&mut scl, &mut delay).unwrap();
+///
+/// # }
+/// ```
+pub fn recover_bus<P, D>(scl: &mut P, delay: &mut D) -> Result<(), P::Error>
+where
+    P: OutputPin,
+    D: DelayUs<u16>,
+{
+    for _ in 0..RECOVERY_PULSES {
+        scl.set_low()?;
+        delay.delay_us(RECOVERY_HALF_PERIOD_US);
+        scl.set_high()?;
+        delay.delay_us(RECOVERY_HALF_PERIOD_US);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Records the levels driven on the pin, & fails after a number of them.
+    struct Pin {
+        levels: Vec<bool>,
+        fail_after: usize,
+    }
+
+    impl OutputPin for Pin {
+        type Error = ();
+
+        fn set_low(&mut self) -> Result<(), ()> {
+            self.set(false)
+        }
+
+        fn set_high(&mut self) -> Result<(), ()> {
+            self.set(true)
+        }
+    }
+
+    impl Pin {
+        fn set(&mut self, level: bool) -> Result<(), ()> {
+            if self.levels.len() == self.fail_after {
+                return Err(());
+            }
+
+            self.levels.push(level);
+            Ok(())
+        }
+    }
+
+    struct Delay {
+        total_us: u32,
+    }
+
+    impl DelayUs<u16> for Delay {
+        fn delay_us(&mut self, us: u16) {
+            self.total_us += u32::from(us);
+        }
+    }
+
+    #[test]
+    fn recover_bus() {
+        let mut scl = Pin {
+            levels: Vec::new(),
+            fail_after: usize::MAX,
+        };
+        let mut delay = Delay { total_us: 0 };
+
+        super::recover_bus(&mut scl, &mut delay).unwrap();
+
+        let pulses: Vec<bool> = (0..9).flat_map(|_| vec![false, true]).collect();
+        assert_eq!(pulses, scl.levels);
+        assert_eq!(90, delay.total_us);
+    }
+
+    #[test]
+    fn recover_bus_error() {
+        let mut scl = Pin {
+            levels: Vec::new(),
+            fail_after: 3,
+        };
+        let mut delay = Delay { total_us: 0 };
+
+        assert_eq!(Err(()), super::recover_bus(&mut scl, &mut delay));
+        assert_eq!(3, scl.levels.len());
+    }
+}
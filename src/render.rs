@@ -0,0 +1,1244 @@
+//! # Render
+//!
+//! A [Frame](struct.Frame.html) is the complete state of the bargraph display,
+//! calculated independently of any device. Anything implementing
+//! [Render](trait.Render.html) can display it, the [Bargraph](struct.Bargraph.html)
+//! renders to the `HT16K33` backpack & the [SimulatorBackend](struct.SimulatorBackend.html)
+//! records it.
+use std::error;
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use super::{
+    ColorMap, ColorScheme, LedColor, Palette, Sample, Status, Thresholds, BARGRAPH_RESOLUTION,
+};
+
+/// How [Bargraph::update()](struct.Bargraph.html#method.update) draws a value, see
+/// [Bargraph::set_style()](struct.Bargraph.html#method.set_style).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Style {
+    /// Every value up to the value is filled, the default.
+    #[default]
+    Bar,
+    /// Only the value itself is lit, like the needle of a meter.
+    Dot,
+}
+
+/// The state of every bar on the display.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frame {
+    /// The color of each bar, from the bottom (`0`) to the top of the display.
+    pub bars: [LedColor; BARGRAPH_RESOLUTION as usize],
+    /// Whether the display is blinking.
+    pub blink: bool,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Frame {
+            bars: [LedColor::Off; BARGRAPH_RESOLUTION as usize],
+            blink: false,
+        }
+    }
+}
+
+impl Frame {
+    /// Calculate the frame showing `range` total values with all values from `0`
+    /// to `value` filled, see [Bargraph::update()](struct.Bargraph.html#method.update).
+    ///
+    /// Filled values are yellow with a red top bar, unfilled values only have a
+    /// green top bar, see [from_value_with()](#method.from_value_with) for other colors.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How many values to fill, starting from `0`.
+    /// * `range` - Total number of values to display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let frame = Frame::from_value(1, 12);
+    ///
+    /// assert_eq!(LedColor::Yellow, frame.bars[0]);
+    /// assert_eq!(LedColor::Red, frame.bars[1]);
+    /// assert_eq!(LedColor::Off, frame.bars[2]);
+    /// assert_eq!(LedColor::Green, frame.bars[3]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_value(value: u8, range: u8) -> Self {
+        Frame::from_value_with(value, range, &ColorScheme::default())
+    }
+
+    /// Calculate the frame like [from_value()](#method.from_value), drawing the values
+    /// with the colors of the scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How many values to fill, starting from `0`.
+    /// * `range` - Total number of values to display.
+    /// * `scheme` - The colors of each value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    pub fn from_value_with(value: u8, range: u8, scheme: &ColorScheme) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_values(bars, value, range, scheme)
+        })
+    }
+
+    /// Calculate the frame showing `range` total values with all values from `0`
+    /// to `value` filled, colored by how full the display is.
+    ///
+    /// Each filled value takes the color of where it starts, as a percentage of the
+    /// range, unfilled values are off.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How many values to fill, starting from `0`.
+    /// * `range` - Total number of values to display.
+    /// * `colors` - The color of each percentage of the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{ColorMap, Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let colors: ColorMap = "0:green,50:red".parse().unwrap();
+    /// let frame = Frame::from_colored_value(3, 4, &colors);
+    ///
+    /// assert_eq!(LedColor::Green, frame.bars[0]);
+    /// assert_eq!(LedColor::Green, frame.bars[6]);
+    /// assert_eq!(LedColor::Red, frame.bars[12]);
+    /// assert_eq!(LedColor::Off, frame.bars[18]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_colored_value(value: u8, range: u8, colors: &ColorMap) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_colored(bars, value, range, colors)
+        })
+    }
+
+    /// Calculate the frame showing a signed value from the center of the display, e.g.
+    /// an audio balance or a temperature change. Positive values fill up from the
+    /// center & negative values fill down, each half showing `range` total values.
+    ///
+    /// If the value is outside of the range then the display blinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How many values to fill, up (positive) or down (negative) from the
+    ///   center.
+    /// * `range` - Total number of values to display each side of the center.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than half of [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let frame = Frame::from_signed_value(-1, 12);
+    ///
+    /// assert_eq!(LedColor::Red, frame.bars[11]);
+    /// assert_eq!(LedColor::Green, frame.bars[12]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_signed_value(value: i8, range: u8) -> Self {
+        Frame::from_signed_value_with(value, range, &ColorScheme::default())
+    }
+
+    /// Calculate the frame like [from_signed_value()](#method.from_signed_value),
+    /// drawing the values with the colors of the scheme.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than half of [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    pub fn from_signed_value_with(value: i8, range: u8, scheme: &ColorScheme) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_signed(bars, value, range, &Look::new(scheme))
+        })
+    }
+
+    /// Calculate the frame showing `range` total values with only the value lit, like
+    /// the needle of a meter, see [Style::Dot](enum.Style.html#variant.Dot). Nothing is
+    /// lit for `0`.
+    ///
+    /// If the value is greater than the range then the top value is lit & the display
+    /// blinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to light, starting from `1`.
+    /// * `range` - Total number of values to display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let frame = Frame::from_dot(3, 12);
+    ///
+    /// assert_eq!(LedColor::Off, frame.bars[3]);
+    /// assert_eq!(LedColor::Yellow, frame.bars[4]);
+    /// assert_eq!(LedColor::Red, frame.bars[5]);
+    /// assert_eq!(LedColor::Off, frame.bars[6]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_dot(value: u8, range: u8) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_dot(bars, value, range, &ColorScheme::default())
+        })
+    }
+
+    /// Calculate the frame showing several values side-by-side, the bars are split
+    /// equally between the segments from the bottom of the display.
+    ///
+    /// Each segment is filled like [from_value()](#method.from_value) within its own
+    /// bars. If any segment's value is greater than its range then the whole display
+    /// blinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - The `(value, range)` of each segment, starting from the bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no segments or more segments than bars, or a segment's range
+    /// is `0` or greater than its number of bars.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// // Two segments of 12 bars each.
+    /// let frame = Frame::from_segments(&[(3, 12), (12, 12)]);
+    ///
+    /// assert_eq!(LedColor::Red, frame.bars[2]);
+    /// assert_eq!(LedColor::Green, frame.bars[3]);
+    /// assert_eq!(LedColor::Red, frame.bars[23]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_segments(segments: &[(u8, u8)]) -> Self {
+        Frame::from_segments_with(segments, &ColorScheme::default())
+    }
+
+    /// Calculate the frame like [from_segments()](#method.from_segments), drawing the
+    /// values with the colors of the scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - The `(value, range)` of each segment, starting from the bottom.
+    /// * `scheme` - The colors of each value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no segments or more segments than bars, or a segment's range
+    /// is `0` or greater than its number of bars.
+    pub fn from_segments_with(segments: &[(u8, u8)], scheme: &ColorScheme) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_segments(bars, segments, &Look::new(scheme))
+        })
+    }
+
+    /// Calculate the frame showing two values against the same range, one on the red
+    /// LEDs & the other on the green LEDs, e.g. download vs. upload throughput.
+    ///
+    /// All the bars of each filled value are lit, the bars filled by both values are
+    /// yellow. If either value is greater than the range then the display blinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `red_value` - How many values to fill with red, starting from `0`.
+    /// * `green_value` - How many values to fill with green, starting from `0`.
+    /// * `range` - Total number of values to display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let frame = Frame::from_channels(2, 1, 24);
+    ///
+    /// assert_eq!(LedColor::Yellow, frame.bars[0]);
+    /// assert_eq!(LedColor::Red, frame.bars[1]);
+    /// assert_eq!(LedColor::Off, frame.bars[2]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_channels(red_value: u8, green_value: u8, range: u8) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_channels(bars, red_value, green_value, range)
+        })
+    }
+
+    /// Calculate the frame showing a span of `range` total values as a green band, with
+    /// a single value on it as a red marker, e.g. the day's lowest & highest
+    /// temperatures & the current temperature.
+    ///
+    /// The values are positions in the range, `0` is the bottom value & `range - 1`
+    /// the top. The marker doesn't have to be within the band. If any value is
+    /// greater than the top of the range then it's drawn at the top & the display
+    /// blinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `low` - The bottom value of the band.
+    /// * `high` - The top value of the band.
+    /// * `marker` - The value of the marker.
+    /// * `range` - Total number of values to display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than [BARGRAPH_RESOLUTION](constant.BARGRAPH_RESOLUTION.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let frame = Frame::from_band(2, 8, 5, 12);
+    ///
+    /// assert_eq!(LedColor::Off, frame.bars[3]);
+    /// assert_eq!(LedColor::Green, frame.bars[4]);
+    /// assert_eq!(LedColor::Red, frame.bars[10]);
+    /// assert_eq!(LedColor::Green, frame.bars[17]);
+    /// assert_eq!(LedColor::Off, frame.bars[18]);
+    ///
+    /// # }
+    /// ```
+    pub fn from_band(low: u8, high: u8, marker: u8, range: u8) -> Self {
+        Frame::within(BARGRAPH_RESOLUTION, |bars| {
+            fill_band(bars, low, high, marker, range)
+        })
+    }
+
+    /// Reverse the order of the bars, e.g. so that values fill from the top of the
+    /// display down.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate led_bargraph;
+    /// # use led_bargraph::{Frame, LedColor};
+    /// # fn main() {
+    ///
+    /// let mut frame = Frame::from_value(1, 24);
+    /// frame.reverse();
+    ///
+    /// assert_eq!(LedColor::Red, frame.bars[23]);
+    /// assert_eq!(LedColor::Green, frame.bars[0]);
+    ///
+    /// # }
+    /// ```
+    pub fn reverse(&mut self) {
+        self.bars.reverse();
+    }
+
+    // Calculate the frame on only the bottom `resolution` bars, the bars above are off.
+    // `fill` colors the bars, returning whether the display blinks.
+    pub(crate) fn within<F>(resolution: u8, fill: F) -> Self
+    where
+        F: FnOnce(&mut [LedColor]) -> bool,
+    {
+        let mut bars = [LedColor::Off; BARGRAPH_RESOLUTION as usize];
+
+        let blink = fill(&mut bars[..resolution as usize]);
+
+        Frame { bars, blink }
+    }
+}
+
+/// A compact form of the bars, one character per bar from the bottom of the display:
+/// `R`ed, `Y`ellow, `G`reen or `.` for off. The blink state isn't included.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate led_bargraph;
+/// # use led_bargraph::Frame;
+/// # fn main() {
+///
+/// assert_eq!("YYYRYYYR...G...G", &Frame::from_value(2, 6).to_string()[..16]);
+///
+/// # }
+/// ```
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for bar in self.bars.iter() {
+            let c = match *bar {
+                LedColor::Off => '.',
+                LedColor::Green => 'G',
+                LedColor::Red => 'R',
+                LedColor::Yellow => 'Y',
+            };
+
+            write!(f, "{}", c)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Collect the colors of the bars from the bottom of the display, any bars without a
+/// color are off & any extra colors are ignored. The display doesn't blink.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate led_bargraph;
+/// # use led_bargraph::{Frame, LedColor};
+/// # fn main() {
+///
+/// let frame: Frame = (0..4)
+///     .map(|bar| if bar % 2 == 0 { LedColor::Red } else { LedColor::Green })
+///     .collect();
+///
+/// assert_eq!(LedColor::Green, frame.bars[3]);
+/// assert_eq!(LedColor::Off, frame.bars[4]);
+///
+/// # }
+/// ```
+impl FromIterator<LedColor> for Frame {
+    fn from_iter<I>(colors: I) -> Self
+    where
+        I: IntoIterator<Item = LedColor>,
+    {
+        let mut frame = Frame::default();
+
+        for (bar, color) in frame.bars.iter_mut().zip(colors) {
+            *bar = color;
+        }
+
+        frame
+    }
+}
+
+/// Copy the colors of the bars from the bottom of the display, see
+/// `FromIterator`.
+impl<'a> From<&'a [LedColor]> for Frame {
+    fn from(colors: &'a [LedColor]) -> Self {
+        colors.iter().cloned().collect()
+    }
+}
+
+/// The error returned when a [Frame](struct.Frame.html) can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseFrameError {
+    frame: String,
+}
+
+impl fmt::Display for ParseFrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid frame [{}], expected [{}] of `R`, `Y`, `G` or `.`",
+            self.frame, BARGRAPH_RESOLUTION
+        )
+    }
+}
+
+impl error::Error for ParseFrameError {}
+
+/// Parse the compact form of the bars written by `Display`. The display doesn't blink.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate led_bargraph;
+/// # use led_bargraph::Frame;
+/// # fn main() {
+///
+/// let frame = Frame::from_value(2, 6);
+///
+/// assert_eq!(Ok(frame), frame.to_string().parse());
+///
+/// # }
+/// ```
+impl FromStr for Frame {
+    type Err = ParseFrameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseFrameError {
+            frame: s.to_string(),
+        };
+
+        let colors = s
+            .chars()
+            .map(|c| match c {
+                '.' => Some(LedColor::Off),
+                'G' => Some(LedColor::Green),
+                'R' => Some(LedColor::Red),
+                'Y' => Some(LedColor::Yellow),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+
+        if colors.len() != BARGRAPH_RESOLUTION as usize {
+            return Err(invalid());
+        }
+
+        Ok(colors.into_iter().collect())
+    }
+}
+
+// Enable the LEDs of a single color for all values from `0` to `value` of `range`
+// total values, returning whether `value` overflowed the range.
+fn fill_channel(leds: &mut [bool], value: u8, range: u8) -> bool {
+    assert!(
+        range > 0 && range as usize <= leds.len(),
+        "Range [{}] must be between [1] and [{}]",
+        range,
+        leds.len()
+    );
+
+    let overflow = value > range;
+    let clamped_value = if overflow { range } else { value };
+
+    // Calculate the size of each value.
+    let value_size = leds.len() / range as usize;
+
+    for led in &mut leds[..clamped_value as usize * value_size] {
+        *led = true;
+    }
+
+    overflow
+}
+
+// Fill the bars to show `range` total values with all values from `0` to `value`
+// filled, returning whether `value` overflowed the range.
+pub(crate) fn fill_values(
+    bars: &mut [LedColor],
+    value: u8,
+    range: u8,
+    scheme: &ColorScheme,
+) -> bool {
+    assert!(
+        range > 0 && range as usize <= bars.len(),
+        "Range [{}] must be between [1] and [{}]",
+        range,
+        bars.len()
+    );
+
+    let overflow = value > range;
+    let clamped_value = if overflow { range } else { value };
+
+    // Calculate the size of each value.
+    let value_size = bars.len() / range as usize;
+
+    for current_value in 0..range as usize {
+        let fill = current_value < clamped_value as usize;
+
+        let start_bar = current_value * value_size;
+        let end_bar = start_bar + value_size - 1;
+
+        // Fill in the value.
+        for bar in &mut bars[start_bar..end_bar] {
+            *bar = if fill { scheme.fill } else { LedColor::Off };
+        }
+
+        // Color the "top" bar of the value.
+        bars[end_bar] = if fill { scheme.head } else { scheme.empty };
+    }
+
+    overflow
+}
+
+// Fill the bars to show `halves` half bars, like `fill_look()` with a value for each
+// bar, lighting a trailing half bar filled by the palette, otherwise with the fill of
+// the scheme in the zone of the whole bars. Returns whether `halves` overflowed the bars.
+pub(crate) fn fill_half_steps(bars: &mut [LedColor], halves: u8, look: &Look) -> bool {
+    let range = bars.len() as u8;
+    let whole = halves / 2;
+
+    let overflow = fill_look(bars, whole, range, look);
+
+    if halves % 2 == 1 && whole < range {
+        bars[whole as usize] = match look.palette {
+            Some(palette) => palette.color_for(whole, true, range),
+            None => look.scheme(whole, range).fill,
+        };
+    }
+
+    overflow || u16::from(halves) > u16::from(range) * 2
+}
+
+// Fill each half of the bars to show `range` total values like `fill_look()`, up
+// from the center for positive values & down for negative values, returning whether
+// `value` overflowed the range. The middle bar of an odd number of bars is off.
+pub(crate) fn fill_signed(bars: &mut [LedColor], value: i8, range: u8, look: &Look) -> bool {
+    let half = bars.len() / 2;
+    let (below, above) = bars.split_at_mut(bars.len() - half);
+    let below = &mut below[..half];
+
+    let magnitude = value.unsigned_abs();
+    let (up, down) = if value < 0 {
+        (0, magnitude)
+    } else {
+        (magnitude, 0)
+    };
+
+    let overflow = fill_look(above, up, range, look);
+    fill_look(below, down, range, look);
+
+    // The values below the center grow down the display.
+    below.reverse();
+
+    overflow || down > range
+}
+
+// Fill the bars like `fill_values()`, coloring each filled value by where it starts
+// as a percentage of the range.
+pub(crate) fn fill_colored(bars: &mut [LedColor], value: u8, range: u8, colors: &ColorMap) -> bool {
+    let mut filled = [false; BARGRAPH_RESOLUTION as usize];
+    let filled = &mut filled[..bars.len()];
+
+    let overflow = fill_channel(filled, value, range);
+
+    let value_size = bars.len() / range as usize;
+
+    for (bar, (color, &filled)) in bars.iter_mut().zip(filled.iter()).enumerate() {
+        *color = if filled {
+            let start = (bar / value_size) * 100 / range as usize;
+            colors.color_at(start as u8)
+        } else {
+            LedColor::Off
+        };
+    }
+
+    overflow
+}
+
+// Light only the bars of the value, clamped to the range, returning whether `value`
+// overflowed the range.
+fn dot_channel(leds: &mut [bool], value: u8, range: u8) -> bool {
+    let overflow = fill_channel(leds, value, range);
+
+    // Only keep the top value lit.
+    let value_size = leds.len() / range as usize;
+    let start = (value.min(range) as usize).saturating_sub(1) * value_size;
+    for led in &mut leds[..start] {
+        *led = false;
+    }
+
+    overflow
+}
+
+// Light only the value, like `fill_values()` draws a filled value, returning whether
+// `value` overflowed the range.
+pub(crate) fn fill_dot(bars: &mut [LedColor], value: u8, range: u8, scheme: &ColorScheme) -> bool {
+    let mut lit = [false; BARGRAPH_RESOLUTION as usize];
+    let lit = &mut lit[..bars.len()];
+
+    let overflow = dot_channel(lit, value, range);
+
+    let value_size = bars.len() / range as usize;
+
+    for (bar, (color, &lit)) in bars.iter_mut().zip(lit.iter()).enumerate() {
+        *color = match lit {
+            true if bar % value_size == value_size - 1 => scheme.head,
+            true => scheme.fill,
+            false => LedColor::Off,
+        };
+    }
+
+    overflow
+}
+
+// How the values of `update()` & the other single value updates are drawn, gathered
+// from the Bargraph or a Pipeline so that every update honours the same settings.
+#[derive(Clone, Copy)]
+pub(crate) struct Look<'a> {
+    pub(crate) scheme: &'a ColorScheme,
+    pub(crate) thresholds: Option<&'a Thresholds>,
+    pub(crate) style: Style,
+    pub(crate) palette: Option<&'a (dyn Palette + Send + Sync)>,
+}
+
+impl<'a> Look<'a> {
+    // Draw bars with the scheme alone.
+    pub(crate) fn new(scheme: &'a ColorScheme) -> Self {
+        Look {
+            scheme,
+            thresholds: None,
+            style: Style::Bar,
+            palette: None,
+        }
+    }
+
+    // The scheme drawing the value, in its zone of the thresholds if any.
+    pub(crate) fn scheme(&self, value: u8, range: u8) -> ColorScheme {
+        match self.thresholds {
+            Some(thresholds) => thresholds.scheme(value, range, self.scheme),
+            None => *self.scheme,
+        }
+    }
+}
+
+// Fill the bars in the style of the look, coloring them with its palette if any,
+// otherwise with its scheme in the value's zone.
+pub(crate) fn fill_look(bars: &mut [LedColor], value: u8, range: u8, look: &Look) -> bool {
+    match (look.palette, look.style) {
+        (Some(palette), style) => fill_palette(bars, value, range, style, palette),
+        (None, Style::Bar) => fill_values(bars, value, range, &look.scheme(value, range)),
+        (None, Style::Dot) => fill_dot(bars, value, range, &look.scheme(value, range)),
+    }
+}
+
+// Fill the bars like `fill_values()` or `fill_dot()`, coloring each bar with the
+// palette.
+pub(crate) fn fill_palette<P>(
+    bars: &mut [LedColor],
+    value: u8,
+    range: u8,
+    style: Style,
+    palette: &P,
+) -> bool
+where
+    P: Palette + ?Sized,
+{
+    let mut filled = [false; BARGRAPH_RESOLUTION as usize];
+    let filled = &mut filled[..bars.len()];
+
+    let overflow = match style {
+        Style::Bar => fill_channel(filled, value, range),
+        Style::Dot => dot_channel(filled, value, range),
+    };
+
+    for (bar, (color, &filled)) in bars.iter_mut().zip(filled.iter()).enumerate() {
+        *color = palette.color_for(bar as u8, filled, range);
+    }
+
+    overflow
+}
+
+// Split the bars equally between the segments & fill each like `fill_look()`,
+// returning whether any segment's value overflowed its range.
+pub(crate) fn fill_segments(bars: &mut [LedColor], segments: &[(u8, u8)], look: &Look) -> bool {
+    assert!(
+        !segments.is_empty() && segments.len() <= bars.len(),
+        "Segments [{}] must be between [1] and [{}]",
+        segments.len(),
+        bars.len()
+    );
+
+    let segment_size = bars.len() / segments.len();
+
+    let mut overflow = false;
+    for (bars, &(value, range)) in bars.chunks_mut(segment_size).zip(segments) {
+        if fill_look(bars, value, range, look) {
+            overflow = true;
+        }
+    }
+
+    overflow
+}
+
+// Fill the red & green LEDs of the bars with separate values, returning whether
+// either value overflowed the range.
+pub(crate) fn fill_channels(
+    bars: &mut [LedColor],
+    red_value: u8,
+    green_value: u8,
+    range: u8,
+) -> bool {
+    let mut red = [false; BARGRAPH_RESOLUTION as usize];
+    let mut green = [false; BARGRAPH_RESOLUTION as usize];
+    let red = &mut red[..bars.len()];
+    let green = &mut green[..bars.len()];
+
+    let red_overflow = fill_channel(red, red_value, range);
+    let green_overflow = fill_channel(green, green_value, range);
+
+    for (bar, (&red, &green)) in bars.iter_mut().zip(red.iter().zip(green.iter())) {
+        *bar = match (red, green) {
+            (true, true) => LedColor::Yellow,
+            (true, false) => LedColor::Red,
+            (false, true) => LedColor::Green,
+            (false, false) => LedColor::Off,
+        };
+    }
+
+    red_overflow || green_overflow
+}
+
+// Fill the bars of the values from `low` to `high` in green & the bars of the
+// `marker` value in red, returning whether any value overflowed the range.
+pub(crate) fn fill_band(bars: &mut [LedColor], low: u8, high: u8, marker: u8, range: u8) -> bool {
+    assert!(
+        range > 0 && range as usize <= bars.len(),
+        "Range [{}] must be between [1] and [{}]",
+        range,
+        bars.len()
+    );
+
+    let top = range - 1;
+    let overflow = low > top || high > top || marker > top;
+
+    // Calculate the size of each value.
+    let value_size = bars.len() / range as usize;
+    let value_bars = |value: u8| {
+        let start_bar = value.min(top) as usize * value_size;
+        start_bar..start_bar + value_size
+    };
+
+    for bar in bars.iter_mut() {
+        *bar = LedColor::Off;
+    }
+
+    let (low, high) = (low.min(high), low.max(high));
+    for value in low.min(top)..=high.min(top) {
+        for bar in &mut bars[value_bars(value)] {
+            *bar = LedColor::Green;
+        }
+    }
+
+    for bar in &mut bars[value_bars(marker)] {
+        *bar = LedColor::Red;
+    }
+
+    overflow
+}
+
+// The frame drawing the sample against the range, within the bars in use, with the
+// colors if any, otherwise like `update()` with the look. Overflowing values fill one
+// more than the range, which blinks.
+pub(crate) fn sample_frame(
+    sample: &Sample,
+    range: u8,
+    resolution: u8,
+    colors: Option<&ColorMap>,
+    look: &Look,
+    reversed: bool,
+) -> Frame {
+    let value = (sample.value.max(0.0) * f32::from(range))
+        .round()
+        .min(f32::from(range) + 1.0) as u8;
+
+    let mut frame = Frame::within(resolution, |bars| match colors {
+        Some(colors) => fill_colored(bars, value, range, colors),
+        None => fill_look(bars, value, range, look),
+    });
+
+    frame.blink |= sample.status == Some(Status::Alert) || sample.status == Some(Status::Failed);
+
+    if reversed {
+        frame.bars[..resolution as usize].reverse();
+    }
+
+    frame
+}
+
+/// A destination for frames, e.g. a device or a recorder.
+pub trait Render {
+    /// The error returned when a frame can't be rendered.
+    type Error;
+
+    /// Display the frame, replacing whatever was displayed before.
+    fn render(&mut self, frame: &Frame) -> Result<(), Self::Error>;
+}
+
+impl<R> Render for &mut R
+where
+    R: Render + ?Sized,
+{
+    type Error = R::Error;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+        (**self).render(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        let frame = Frame::from_value_with(3, 8, &ColorScheme::default());
+        assert_eq!(Ok(frame), frame.to_string().parse());
+
+        for invalid in ["", "YYY", "YYYRYYYR...G...G.......g"].iter() {
+            assert_eq!(
+                Err(ParseFrameError {
+                    frame: invalid.to_string(),
+                }),
+                invalid.parse::<Frame>()
+            );
+        }
+    }
+
+    #[test]
+    fn default() {
+        let frame = Frame::default();
+
+        assert!(frame.bars.iter().all(|bar| *bar == LedColor::Off));
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn from_value_empty() {
+        let frame = Frame::from_value(0, 24);
+
+        assert!(frame.bars.iter().all(|bar| *bar == LedColor::Green));
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn from_value() {
+        let frame = Frame::from_value(2, 6);
+
+        let mut expected = [LedColor::Off; BARGRAPH_RESOLUTION as usize];
+        for value in 0..6 {
+            let top = value * 4 + 3;
+            if value < 2 {
+                expected[value * 4..top].copy_from_slice(&[LedColor::Yellow; 3]);
+                expected[top] = LedColor::Red;
+            } else {
+                expected[top] = LedColor::Green;
+            }
+        }
+
+        assert_eq!(expected, frame.bars);
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn from_value_with() {
+        let scheme = ColorScheme {
+            fill: LedColor::Red,
+            head: LedColor::Yellow,
+            empty: LedColor::Off,
+        };
+
+        let frame = Frame::from_value_with(1, 6, &scheme);
+
+        assert_eq!([LedColor::Red; 3], frame.bars[..3]);
+        assert_eq!(LedColor::Yellow, frame.bars[3]);
+        assert!(frame.bars[4..].iter().all(|bar| *bar == LedColor::Off));
+    }
+
+    #[test]
+    fn from_segments_with() {
+        let scheme = ColorScheme {
+            fill: LedColor::Green,
+            head: LedColor::Green,
+            empty: LedColor::Red,
+        };
+
+        let frame = Frame::from_segments_with(&[(12, 12), (0, 12)], &scheme);
+
+        assert!(frame.bars[..12].iter().all(|bar| *bar == LedColor::Green));
+        assert!(frame.bars[12..].iter().all(|bar| *bar == LedColor::Red));
+    }
+
+    #[test]
+    fn from_value_uneven_range() {
+        // 24 bars don't divide into 5 values, the remaining bars are unused.
+        let frame = Frame::from_value(5, 5);
+
+        assert_eq!(LedColor::Red, frame.bars[19]);
+        assert!(frame.bars[20..].iter().all(|bar| *bar == LedColor::Off));
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_value_range_too_large() {
+        Frame::from_value(0, BARGRAPH_RESOLUTION + 1);
+    }
+
+    #[test]
+    fn from_segments() {
+        use LedColor::{Green as G, Off as O, Red as R, Yellow as Y};
+
+        // Three segments of 8 bars each.
+        let frame = Frame::from_segments(&[(1, 4), (2, 4), (0, 2)]);
+
+        let expected = [
+            Y, R, O, G, O, G, O, G, // 1 of 4
+            Y, R, Y, R, O, G, O, G, // 2 of 4
+            O, O, O, G, O, O, O, G, // 0 of 2
+        ];
+        assert_eq!(expected, frame.bars);
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn from_segments_single_is_from_value() {
+        assert_eq!(Frame::from_value(7, 12), Frame::from_segments(&[(7, 12)]));
+    }
+
+    #[test]
+    fn from_segments_overflow() {
+        let frame = Frame::from_segments(&[(1, 12), (13, 12)]);
+
+        assert!(frame.bars[12..].iter().all(|bar| *bar == LedColor::Red));
+        assert!(frame.blink);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_segments_empty() {
+        Frame::from_segments(&[]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_segments_too_many() {
+        Frame::from_segments(&[(0, 1); BARGRAPH_RESOLUTION as usize + 1]);
+    }
+
+    #[test]
+    fn fill_half_steps_values() {
+        let half_steps = |halves| {
+            Frame::within(6, |bars| {
+                fill_half_steps(bars, halves, &Look::new(&ColorScheme::default()))
+            })
+        };
+
+        assert_eq!("GGGGGG..................", half_steps(0).to_string());
+        assert_eq!("YGGGGG..................", half_steps(1).to_string());
+        assert_eq!("RRYGGG..................", half_steps(5).to_string());
+        assert_eq!("RRRRRR..................", half_steps(12).to_string());
+
+        let frame = half_steps(13);
+        assert_eq!("RRRRRR..................", frame.to_string());
+        assert!(frame.blink);
+    }
+
+    #[test]
+    fn from_dot() {
+        assert_eq!(
+            "....YYYR................",
+            Frame::from_dot(2, 6).to_string()
+        );
+        assert_eq!(
+            "........................",
+            Frame::from_dot(0, 6).to_string()
+        );
+
+        let frame = Frame::from_dot(7, 6);
+        assert_eq!("....................YYYR", frame.to_string());
+        assert!(frame.blink);
+    }
+
+    #[test]
+    fn from_signed_value() {
+        assert_eq!(
+            "G.G.G.G.G.G.YRYRYR.G.G.G",
+            Frame::from_signed_value(3, 6).to_string()
+        );
+        assert_eq!(
+            "G.G.G.G.RYRY.G.G.G.G.G.G",
+            Frame::from_signed_value(-2, 6).to_string()
+        );
+
+        let frame = Frame::from_signed_value(-7, 6);
+        assert_eq!("RYRYRYRYRYRY.G.G.G.G.G.G", frame.to_string());
+        assert!(frame.blink);
+    }
+
+    #[test]
+    fn from_signed_value_odd() {
+        let frame = Frame::within(5, |bars| {
+            fill_signed(bars, 1, 2, &Look::new(&ColorScheme::default()))
+        });
+
+        assert_eq!("GG.RG...................", frame.to_string());
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_signed_value_range_too_large() {
+        Frame::from_signed_value(1, 13);
+    }
+
+    #[test]
+    fn from_colored_value() {
+        let colors = ColorMap::new(vec![
+            (0, LedColor::Green),
+            (70, LedColor::Yellow),
+            (90, LedColor::Red),
+        ]);
+
+        // 10 values of 2 bars each, the last 4 bars are unused.
+        let frame = Frame::from_colored_value(10, 10, &colors);
+
+        assert!(frame.bars[..14].iter().all(|bar| *bar == LedColor::Green));
+        assert!(frame.bars[14..18]
+            .iter()
+            .all(|bar| *bar == LedColor::Yellow));
+        assert!(frame.bars[18..20].iter().all(|bar| *bar == LedColor::Red));
+        assert!(frame.bars[20..].iter().all(|bar| *bar == LedColor::Off));
+
+        let frame = Frame::from_colored_value(5, 10, &colors);
+        assert!(frame.bars[10..].iter().all(|bar| *bar == LedColor::Off));
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn from_colored_value_overflow() {
+        let colors = ColorMap::new(vec![(0, LedColor::Green)]);
+
+        let frame = Frame::from_colored_value(25, 24, &colors);
+
+        assert!(frame.bars.iter().all(|bar| *bar == LedColor::Green));
+        assert!(frame.blink);
+    }
+
+    #[test]
+    fn from_channels() {
+        use LedColor::{Green as G, Off as O, Yellow as Y};
+
+        let frame = Frame::from_channels(1, 3, 6);
+
+        let expected = [
+            Y, Y, Y, Y, G, G, G, G, G, G, G, G, // Both, then green only
+            O, O, O, O, O, O, O, O, O, O, O, O,
+        ];
+        assert_eq!(expected, frame.bars);
+        assert!(!frame.blink);
+
+        let frame = Frame::from_channels(2, 0, 3);
+        assert!(frame.bars[..16].iter().all(|bar| *bar == LedColor::Red));
+        assert!(frame.bars[16..].iter().all(|bar| *bar == LedColor::Off));
+    }
+
+    #[test]
+    fn from_channels_overflow() {
+        let frame = Frame::from_channels(0, 13, 12);
+
+        assert!(frame.bars.iter().all(|bar| *bar == LedColor::Green));
+        assert!(frame.blink);
+    }
+
+    #[test]
+    fn from_band() {
+        let frame = Frame::from_band(1, 3, 2, 6);
+        assert_eq!("....GGGGRRRRGGGG........", frame.to_string());
+        assert!(!frame.blink);
+
+        // The marker can be outside the band.
+        let frame = Frame::from_band(3, 3, 0, 6);
+        assert_eq!("RRRR........GGGG........", frame.to_string());
+    }
+
+    #[test]
+    fn from_band_overflow() {
+        let frame = Frame::from_band(10, 12, 12, 12);
+
+        assert_eq!("....................GGRR", frame.to_string());
+        assert!(frame.blink);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_channels_range_zero() {
+        Frame::from_channels(0, 0, 0);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut frame = Frame::from_segments(&[(12, 12), (0, 12)]);
+        frame.reverse();
+
+        assert!(frame.bars[..12].iter().all(|bar| *bar == LedColor::Green));
+        assert!(frame.bars[12..].iter().all(|bar| *bar == LedColor::Red));
+
+        frame.reverse();
+        assert_eq!(Frame::from_segments(&[(12, 12), (0, 12)]), frame);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("........................", Frame::default().to_string());
+        assert_eq!(
+            "RRYYGG..................",
+            Frame::from(
+                &[
+                    LedColor::Red,
+                    LedColor::Red,
+                    LedColor::Yellow,
+                    LedColor::Yellow,
+                    LedColor::Green,
+                    LedColor::Green,
+                ][..]
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn from_iter() {
+        let frame: Frame = vec![LedColor::Red, LedColor::Yellow].into_iter().collect();
+
+        assert_eq!(LedColor::Red, frame.bars[0]);
+        assert_eq!(LedColor::Yellow, frame.bars[1]);
+        assert!(frame.bars[2..].iter().all(|bar| *bar == LedColor::Off));
+        assert!(!frame.blink);
+    }
+
+    #[test]
+    fn from_iter_too_many() {
+        let frame: Frame = std::iter::repeat(LedColor::Green).collect();
+
+        assert!(frame.bars.iter().all(|bar| *bar == LedColor::Green));
+    }
+
+    #[test]
+    fn from_slice() {
+        let frame = Frame::from_value(3, 8);
+
+        assert_eq!(frame, Frame::from(&frame.bars[..]));
+    }
+
+    #[test]
+    fn from_value_overflow() {
+        let frame = Frame::from_value(25, 24);
+
+        assert!(frame.bars.iter().all(|bar| *bar == LedColor::Red));
+        assert!(frame.blink);
+    }
+}
@@ -0,0 +1,392 @@
+//! # Scheduler
+//!
+//! Drive a [Bargraph](struct.Bargraph.html) at a fixed frame rate from a background
+//! thread, decoupled from how often the target value changes.
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::fmt::Debug;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::{Bargraph, BARGRAPH_RESOLUTION};
+
+/// How the frame scheduler of
+/// [Bargraph::spawn_scheduler()](struct.Bargraph.html#method.spawn_scheduler) renders
+/// the target value.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::SchedulerConfig;
+/// use std::time::Duration;
+/// # fn main() {
+///
+/// // Glide between targets & show the fractions of a value by dithering.
+/// let config = SchedulerConfig {
+///     range: 12,
+///     transition: Duration::from_millis(500),
+///     dither: true,
+///     ..SchedulerConfig::default()
+/// };
+///
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SchedulerConfig {
+    /// The range the values are displayed against, `24` by default.
+    pub range: u8,
+    /// How many frames are rendered each second, `30` by default.
+    pub fps: u32,
+    /// How long the display takes to move to a new target, a little each frame, none
+    /// (jumping to the target) by default.
+    pub transition: Duration,
+    /// Show a value between two whole values by alternating between them over the
+    /// frames, in proportion, rather than rounding it. Off by default.
+    pub dither: bool,
+    /// Move to each new target over the time since the previous target arrived,
+    /// instead of over `transition`, so that targets arriving slower than the frame
+    /// rate, e.g. every 15 seconds, animate smoothly rather than jump. The display
+    /// reaches each target as the next one is expected, a target behind. Off by
+    /// default.
+    pub interpolate: bool,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            range: BARGRAPH_RESOLUTION,
+            fps: 30,
+            transition: Duration::from_secs(0),
+            dither: false,
+            interpolate: false,
+        }
+    }
+}
+
+impl<I2C, E> Bargraph<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E> + Send + 'static,
+    E: Debug,
+{
+    /// Move the Bargraph to a background thread that renders the latest target sent
+    /// to it at a fixed frame rate, see [SchedulerConfig](struct.SchedulerConfig.html).
+    ///
+    /// Targets are in units of the range, e.g. `3.5` of `6`, & can be sent at any
+    /// rate, only the latest is rendered. The display is only written when a frame
+    /// changes it. Errors are logged by the background thread rather than returned.
+    ///
+    /// The background thread displays the last target & stops once the sender is
+    /// dropped, returning the Bargraph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is `0` or greater than the
+    /// [resolution()](#method.resolution), or the frame rate is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, SchedulerConfig};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let bargraph = Bargraph::new(i2c, address, None);
+    /// let (sender, scheduler) = bargraph.spawn_scheduler(SchedulerConfig::default());
+    ///
+    /// for target in [3.2, 7.9, 12.4].iter() {
+    ///     sender.send(*target).unwrap();
+    /// }
+    ///
+    /// drop(sender);
+    /// let bargraph = scheduler.join().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn spawn_scheduler(mut self, config: SchedulerConfig) -> (Sender<f32>, JoinHandle<Self>) {
+        assert!(
+            config.range > 0 && config.range <= self.resolution(),
+            "Range [{}] must be between [1] and [{}]",
+            config.range,
+            self.resolution()
+        );
+        assert!(config.fps > 0, "Frame rate must be at least [1]");
+
+        let (sender, receiver) = mpsc::channel::<f32>();
+
+        let thread = thread::spawn(move || {
+            let period = Duration::from_secs(1) / config.fps;
+            let mut schedule = Schedule::new(&config);
+            let mut next_frame = Instant::now();
+
+            loop {
+                let timeout = next_frame.saturating_duration_since(Instant::now());
+
+                match receiver.recv_timeout(timeout) {
+                    Ok(value) if value.is_nan() => {
+                        warn!(self.logger, "Ignoring a value that isn't a number");
+                    }
+                    Ok(value) => schedule.set_target(value, Instant::now()),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if let Some(value) = schedule.finish() {
+                            self.display_frame(value, config.range);
+                        }
+
+                        break;
+                    }
+                }
+
+                // Keep taking targets until the frame is due.
+                let now = Instant::now();
+                if now < next_frame {
+                    continue;
+                }
+
+                if let Some(value) = schedule.frame(now) {
+                    self.display_frame(value, config.range);
+                }
+
+                // Frames missed, e.g. by a slow display, are skipped rather than
+                // rendered back to back to catch up.
+                next_frame = (next_frame + period).max(Instant::now());
+            }
+
+            self
+        });
+
+        (sender, thread)
+    }
+
+    // Display a frame of the scheduler, logging any error.
+    fn display_frame(&mut self, value: u8, range: u8) {
+        if let Err(e) = self.update(value, range, false) {
+            error!(self.logger, "Failed to display a frame";
+                   "value" => value, "range" => range, "error" => %e);
+        }
+    }
+}
+
+// The value of each frame, moving towards the target.
+struct Schedule {
+    transition: Duration,
+    dither: bool,
+    interpolate: bool,
+    // The value being moved from, the target, when the target was set & how long it
+    // takes to reach.
+    target: Option<(f32, f32, Instant, Duration)>,
+    // The difference between the values wanted & shown so far, carried into the next
+    // frame when dithering.
+    error: f32,
+    // The value last shown, to only show changes.
+    shown: Option<u8>,
+}
+
+impl Schedule {
+    fn new(config: &SchedulerConfig) -> Self {
+        Schedule {
+            transition: config.transition,
+            dither: config.dither,
+            interpolate: config.interpolate,
+            target: None,
+            error: 0.0,
+            shown: None,
+        }
+    }
+
+    // Move to the value from wherever the display is now.
+    fn set_target(&mut self, value: f32, now: Instant) {
+        let from = self.value(now).unwrap_or(value);
+        let transition = match self.target {
+            Some((_, _, started, _)) if self.interpolate => now.saturating_duration_since(started),
+            Some(_) | None => self.transition,
+        };
+
+        self.target = Some((from, value, now, transition));
+    }
+
+    // The value wanted at the time, if there's a target yet.
+    fn value(&self, now: Instant) -> Option<f32> {
+        self.target.map(|(from, to, started, transition)| {
+            if transition == Duration::from_secs(0) {
+                return to;
+            }
+
+            let progress =
+                now.saturating_duration_since(started).as_secs_f32() / transition.as_secs_f32();
+
+            from + (to - from) * progress.min(1.0)
+        })
+    }
+
+    // The value to show in the frame at the time, if it changes the display.
+    fn frame(&mut self, now: Instant) -> Option<u8> {
+        let value = self.value(now)?;
+
+        let shown = if self.dither {
+            let wanted = value + self.error;
+            let shown = wanted.round();
+            self.error = wanted - shown;
+            shown
+        } else {
+            value.round()
+        };
+
+        self.show(shown)
+    }
+
+    // The target, rounded, if it changes the display.
+    fn finish(&mut self) -> Option<u8> {
+        let (_, to, _, _) = self.target?;
+        self.show(to.round())
+    }
+
+    fn show(&mut self, value: f32) -> Option<u8> {
+        let value = value.clamp(0.0, f32::from(u8::MAX)) as u8;
+
+        if self.shown == Some(value) {
+            return None;
+        }
+
+        self.shown = Some(value);
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use Frame;
+
+    const ADDRESS: u8 = 0;
+
+    fn spawn(config: SchedulerConfig) -> (Sender<f32>, JoinHandle<Bargraph<I2cMock>>) {
+        Bargraph::new(I2cMock::new(None), ADDRESS, None).spawn_scheduler(config)
+    }
+
+    #[test]
+    fn spawn_scheduler() {
+        let (sender, scheduler) = spawn(SchedulerConfig {
+            range: 6,
+            ..SchedulerConfig::default()
+        });
+
+        sender.send(1.0).unwrap();
+        sender.send(2.6).unwrap();
+        drop(sender);
+
+        let bargraph = scheduler.join().unwrap();
+        assert_eq!(Some(Frame::from_value(3, 6)), bargraph.last_frame);
+    }
+
+    #[test]
+    fn spawn_scheduler_frame_rate() {
+        let (sender, scheduler) = spawn(SchedulerConfig {
+            range: 6,
+            fps: 1000,
+            dither: true,
+            ..SchedulerConfig::default()
+        });
+
+        sender.send(2.5).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        drop(sender);
+
+        // Dithering alternates between the values either side of the target.
+        let bargraph = scheduler.join().unwrap();
+        assert!(bargraph.metrics().frames > 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn spawn_scheduler_no_frame_rate() {
+        spawn(SchedulerConfig {
+            fps: 0,
+            ..SchedulerConfig::default()
+        });
+    }
+
+    #[test]
+    fn transition() {
+        let start = Instant::now();
+        let mut schedule = Schedule::new(&SchedulerConfig {
+            transition: Duration::from_secs(1),
+            ..SchedulerConfig::default()
+        });
+
+        assert_eq!(None, schedule.frame(start));
+
+        // The first target is shown straight away.
+        schedule.set_target(2.0, start);
+        assert_eq!(Some(2), schedule.frame(start));
+
+        schedule.set_target(6.0, start);
+        assert_eq!(None, schedule.frame(start));
+        assert_eq!(Some(4), schedule.frame(start + Duration::from_millis(500)));
+        assert_eq!(Some(6), schedule.frame(start + Duration::from_secs(1)));
+        assert_eq!(None, schedule.frame(start + Duration::from_secs(2)));
+
+        // A new target moves from wherever the display is.
+        schedule.set_target(0.0, start + Duration::from_millis(1500));
+        schedule.set_target(2.0, start + Duration::from_secs(2));
+        assert_eq!(Some(3), schedule.frame(start + Duration::from_secs(2)));
+        assert_eq!(Some(2), schedule.finish());
+    }
+
+    #[test]
+    fn interpolate() {
+        let start = Instant::now();
+        let mut schedule = Schedule::new(&SchedulerConfig {
+            interpolate: true,
+            ..SchedulerConfig::default()
+        });
+
+        // The first target is shown straight away.
+        schedule.set_target(2.0, start);
+        assert_eq!(Some(2), schedule.frame(start));
+
+        // Each target takes as long to reach as the time since the last target.
+        let second = start + Duration::from_secs(10);
+        schedule.set_target(6.0, second);
+        assert_eq!(Some(4), schedule.frame(second + Duration::from_secs(5)));
+        assert_eq!(Some(6), schedule.frame(second + Duration::from_secs(10)));
+
+        let third = second + Duration::from_secs(20);
+        schedule.set_target(2.0, third);
+        assert_eq!(Some(5), schedule.frame(third + Duration::from_secs(5)));
+        assert_eq!(Some(2), schedule.frame(third + Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn dither() {
+        let start = Instant::now();
+        let mut schedule = Schedule::new(&SchedulerConfig {
+            dither: true,
+            ..SchedulerConfig::default()
+        });
+
+        schedule.set_target(2.25, start);
+
+        let frames: Vec<Option<u8>> = (0..8).map(|_| schedule.frame(start)).collect();
+        assert_eq!(
+            vec![
+                Some(2),
+                Some(3),
+                Some(2),
+                None,
+                None,
+                Some(3),
+                Some(2),
+                None
+            ],
+            frames
+        );
+    }
+}
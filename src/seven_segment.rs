@@ -0,0 +1,541 @@
+//! # Seven Segment
+//!
+//! A driver for the [Adafruit 0.56" 4-Digit 7-Segment Display w/I2C Backpack](https://www.adafruit.com/product/878).
+use hal::blocking::i2c::{Write, WriteRead};
+
+use ht16k33::{Dimming, Display, DisplayDataAddress, LedLocation, Oscillator, HT16K33};
+
+use slog::Drain;
+
+use super::{Error, Ht16k33Display, Operation, BRIGHTNESS_MAX};
+
+/// The number of digits on the display.
+pub const SEVEN_SEGMENT_DIGITS: u8 = 4;
+
+// The segments to enable for each hexadecimal digit, bits 0-6 are segments A-G.
+const DIGIT_SEGMENTS: [u8; 16] = [
+    0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, // 0-7
+    0x7F, 0x6F, 0x77, 0x7C, 0x39, 0x5E, 0x79, 0x71, // 8-F
+];
+
+// Bit 7 is the decimal point of each digit.
+const DECIMAL_POINT_SEGMENT: u8 = 7;
+
+// The colon sits between the 2nd & 3rd digits, and has its own row.
+const COLON_ROW: u8 = 4;
+const COLON_SEGMENT: u8 = 1;
+
+/// The seven segment display state.
+pub struct SevenSegment<I2C> {
+    device: HT16K33<I2C>,
+    logger: slog::Logger,
+}
+
+impl<I2C, E> SevenSegment<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Create a SevenSegment for display.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C device to communicate with the `HT16K33` chip.
+    /// * `i2c_address` - The I2C address of the `HT16K33` chip.
+    /// * `logger` - A logging instance.
+    ///
+    /// # Notes
+    ///
+    /// `logger = None` will log to the `slog-stdlog` drain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate ht16k33;
+    /// extern crate led_bargraph;
+    ///
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use led_bargraph::SevenSegment;
+    /// # fn main() {
+    ///
+    /// let mut i2c = I2cMock::new(None);
+    /// let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    ///
+    /// # }
+    /// ```
+    pub fn new<L>(i2c: I2C, i2c_address: u8, logger: L) -> Self
+    where
+        L: Into<Option<slog::Logger>>,
+    {
+        let logger = logger
+            .into()
+            .unwrap_or_else(|| slog::Logger::root(slog_stdlog::StdLog.fuse(), o!()));
+
+        trace!(logger, "Constructing SevenSegment");
+
+        let ht16k33_logger = logger.new(o!("mod" => "HT16K33"));
+        let ht16k33 = HT16K33::new(i2c, i2c_address, ht16k33_logger);
+
+        SevenSegment {
+            device: ht16k33,
+            logger,
+        }
+    }
+
+    /// Initialize the display & the connected `HT16K33` device, leaving the display
+    /// cleared and turned on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.initialize().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn initialize(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "initialize");
+
+        self.device.initialize().map_err(Error::i2c(
+            Operation::Initialize,
+            Oscillator::COMMAND.bits(),
+        ))?;
+        self.device
+            .set_display(Display::ON)
+            .map_err(Error::i2c(Operation::Initialize, Display::COMMAND.bits()))
+    }
+
+    /// Clear the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.clear().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn clear(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "clear");
+
+        self.device.clear_display_buffer();
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Set a digit to a hexadecimal value (`0x0` to `0xF`), optionally with its
+    /// decimal point.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Which digit to set, from `0` (left) to `3` (right).
+    /// * `value` - The value to display.
+    /// * `decimal_point` - Whether to enable the decimal point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` or `value` are out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.set_digit(0, 0xA, false);
+    /// seven_segment.set_digit(1, 5, true);
+    /// seven_segment.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_digit(&mut self, position: u8, value: u8, decimal_point: bool) {
+        trace!(self.logger, "set_digit"; "position" => position, "value" => value,
+               "decimal_point" => decimal_point);
+
+        assert!(
+            (value as usize) < DIGIT_SEGMENTS.len(),
+            "Digit value [{}] must be less than [{}]",
+            value,
+            DIGIT_SEGMENTS.len()
+        );
+
+        let mut segments = DIGIT_SEGMENTS[value as usize];
+        if decimal_point {
+            segments |= 1 << DECIMAL_POINT_SEGMENT;
+        }
+
+        self.set_segments(position, segments);
+    }
+
+    /// Set which segments of a digit are enabled, for displaying custom characters.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Which digit to set, from `0` (left) to `3` (right).
+    /// * `segments` - Bits `0` to `6` enable segments `A` to `G`, bit `7` enables the
+    ///   decimal point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    ///
+    /// // Display a minus sign.
+    /// seven_segment.set_segments(0, 0b0100_0000);
+    /// seven_segment.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_segments(&mut self, position: u8, segments: u8) {
+        trace!(self.logger, "set_segments"; "position" => position,
+               "segments" => format!("{:#010b}", segments));
+
+        assert!(
+            position < SEVEN_SEGMENT_DIGITS,
+            "Digit position [{}] must be less than [{}]",
+            position,
+            SEVEN_SEGMENT_DIGITS
+        );
+
+        let row = Self::digit_row(position);
+
+        for segment in 0..8 {
+            let location = LedLocation::new(row, segment).unwrap();
+            self.device
+                .update_display_buffer(location, segments & (1 << segment) != 0);
+        }
+    }
+
+    /// Enable/Disable the colon between the 2nd & 3rd digits.
+    ///
+    /// The display isn't changed until [flush()](#method.flush) is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.set_colon(true);
+    /// seven_segment.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_colon(&mut self, enabled: bool) {
+        trace!(self.logger, "set_colon"; "enabled" => enabled);
+
+        let location = LedLocation::new(COLON_ROW, COLON_SEGMENT).unwrap();
+        self.device.update_display_buffer(location, enabled);
+    }
+
+    /// Write the digits & colon to the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.flush().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Result<(), Error<E>> {
+        trace!(self.logger, "flush");
+
+        self.device.write_display_buffer().map_err(Error::i2c(
+            Operation::WriteDisplayBuffer,
+            DisplayDataAddress::ROW_0.bits(),
+        ))
+    }
+
+    /// Enable/Disable continuous blinking of the display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.set_blink(true).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_blink"; "enabled" => enabled);
+
+        let display = if enabled {
+            Display::ONE_HZ
+        } else {
+            Display::ON
+        };
+
+        self.device
+            .set_display(display)
+            .map_err(Error::i2c(Operation::SetBlink, Display::COMMAND.bits()))
+    }
+
+    /// Set the brightness of the display, from `0` (dimmest) to
+    /// [BRIGHTNESS_MAX](constant.BRIGHTNESS_MAX.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `brightness` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::SevenSegment;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut seven_segment = SevenSegment::new(i2c, address, None);
+    /// seven_segment.set_brightness(8).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        trace!(self.logger, "set_brightness"; "brightness" => brightness);
+
+        assert!(
+            brightness <= BRIGHTNESS_MAX,
+            "Brightness [{}] must be less than or equal to [{}]",
+            brightness,
+            BRIGHTNESS_MAX
+        );
+
+        self.device
+            .set_dimming(Dimming::from_bits_truncate(brightness))
+            .map_err(Error::i2c(
+                Operation::SetBrightness,
+                Dimming::COMMAND.bits(),
+            ))
+    }
+
+    // The digits skip over the colon's row.
+    //
+    // This transform follows the layout of the Adafruit 7-segment backpack.
+    fn digit_row(position: u8) -> u8 {
+        if position < 2 {
+            position * 2
+        } else {
+            position * 2 + 2
+        }
+    }
+}
+
+impl<I2C, E> Ht16k33Display for SevenSegment<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn initialize(&mut self) -> Result<(), Error<E>> {
+        SevenSegment::initialize(self)
+    }
+
+    fn clear(&mut self) -> Result<(), Error<E>> {
+        SevenSegment::clear(self)
+    }
+
+    fn flush(&mut self) -> Result<(), Error<E>> {
+        SevenSegment::flush(self)
+    }
+
+    fn set_blink(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        SevenSegment::set_blink(self, enabled)
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<(), Error<E>> {
+        SevenSegment::set_brightness(self, brightness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    #[test]
+    fn new() {
+        let i2c = I2cMock::new(None);
+        let _seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+    }
+
+    #[test]
+    fn initialize() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+        seven_segment.initialize().unwrap();
+
+        assert_eq!(Display::ON, *seven_segment.device.display());
+    }
+
+    #[test]
+    fn clear() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+        seven_segment.set_digit(0, 8, true);
+
+        seven_segment.clear().unwrap();
+
+        for row in seven_segment.device.display_buffer().iter() {
+            assert_eq!(0, row.bits());
+        }
+    }
+
+    #[test]
+    fn set_digit() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_digit(0, 1, false);
+        seven_segment.set_digit(1, 2, true);
+        seven_segment.set_digit(2, 0xE, false);
+        seven_segment.set_digit(3, 0xF, false);
+
+        let buffer = seven_segment.device.display_buffer();
+        assert_eq!(0x06, buffer[0].bits());
+        assert_eq!(0xDB, buffer[2].bits());
+        assert_eq!(0x00, buffer[4].bits());
+        assert_eq!(0x79, buffer[6].bits());
+        assert_eq!(0x71, buffer[8].bits());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_digit_value_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_digit(0, 16, false);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_segments_position_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_segments(4, 0xFF);
+    }
+
+    #[test]
+    fn set_colon() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_colon(true);
+        assert_eq!(0x02, seven_segment.device.display_buffer()[4].bits());
+
+        seven_segment.set_colon(false);
+        assert_eq!(0x00, seven_segment.device.display_buffer()[4].bits());
+    }
+
+    #[test]
+    fn flush() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+        seven_segment.set_digit(0, 7, false);
+
+        seven_segment.flush().unwrap();
+    }
+
+    #[test]
+    fn set_blink() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_blink(true).unwrap();
+        assert_eq!(Display::ONE_HZ, *seven_segment.device.display());
+
+        seven_segment.set_blink(false).unwrap();
+        assert_eq!(Display::ON, *seven_segment.device.display());
+    }
+
+    #[test]
+    fn set_brightness() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_brightness(0).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MIN, *seven_segment.device.dimming());
+
+        seven_segment.set_brightness(BRIGHTNESS_MAX).unwrap();
+        assert_eq!(Dimming::BRIGHTNESS_MAX, *seven_segment.device.dimming());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_brightness_too_large() {
+        let i2c = I2cMock::new(None);
+        let mut seven_segment = SevenSegment::new(i2c, ADDRESS, None);
+
+        seven_segment.set_brightness(BRIGHTNESS_MAX + 1).unwrap();
+    }
+}
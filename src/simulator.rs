@@ -0,0 +1,137 @@
+//! # Simulator
+//!
+//! A headless [Render](trait.Render.html) backend that records frames instead of
+//! displaying them, for testing without any I2C device.
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+use super::{Frame, Render};
+
+/// A rendered frame & when it was rendered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CapturedFrame {
+    /// The time since the simulator was created.
+    pub elapsed: Duration,
+    /// The rendered frame.
+    pub frame: Frame,
+}
+
+/// Records every rendered frame.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{Frame, LedColor, Render, SimulatorBackend};
+/// # fn main() {
+///
+/// let mut simulator = SimulatorBackend::new();
+///
+/// simulator.render(&Frame::from_value(0, 24)).unwrap();
+/// simulator.render(&Frame::from_value(25, 24)).unwrap();
+///
+/// assert_eq!(2, simulator.frames().len());
+///
+/// let last = simulator.last_frame().unwrap();
+/// assert_eq!(LedColor::Red, last.bars[23]);
+/// assert!(last.blink);
+///
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SimulatorBackend {
+    start: Instant,
+    frames: Vec<CapturedFrame>,
+}
+
+impl Default for SimulatorBackend {
+    fn default() -> Self {
+        SimulatorBackend::new()
+    }
+}
+
+impl SimulatorBackend {
+    /// Create a SimulatorBackend with no frames.
+    pub fn new() -> Self {
+        SimulatorBackend {
+            start: Instant::now(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// The rendered frames, oldest first.
+    pub fn frames(&self) -> &[CapturedFrame] {
+        &self.frames
+    }
+
+    /// The most recently rendered frame, i.e. what a device would be displaying.
+    pub fn last_frame(&self) -> Option<&Frame> {
+        self.frames.last().map(|captured| &captured.frame)
+    }
+
+    /// Remove & return the rendered frames.
+    pub fn take_frames(&mut self) -> Vec<CapturedFrame> {
+        self.frames.split_off(0)
+    }
+}
+
+impl Render for SimulatorBackend {
+    type Error = Infallible;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), Infallible> {
+        self.frames.push(CapturedFrame {
+            elapsed: self.start.elapsed(),
+            frame: *frame,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LedColor;
+
+    #[test]
+    fn new() {
+        let simulator = SimulatorBackend::new();
+
+        assert!(simulator.frames().is_empty());
+        assert_eq!(None, simulator.last_frame());
+    }
+
+    #[test]
+    fn render() {
+        let mut simulator = SimulatorBackend::new();
+
+        let frames = [
+            Frame::from_value(0, 6),
+            Frame::from_value(3, 6),
+            Frame::from_value(7, 6),
+        ];
+        for frame in frames.iter() {
+            simulator.render(frame).unwrap();
+        }
+
+        let captured: Vec<Frame> = simulator.frames().iter().map(|c| c.frame).collect();
+        assert_eq!(frames.to_vec(), captured);
+        assert_eq!(Some(&frames[2]), simulator.last_frame());
+
+        let elapsed: Vec<Duration> = simulator.frames().iter().map(|c| c.elapsed).collect();
+        assert!(elapsed.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn take_frames() {
+        let mut simulator = SimulatorBackend::new();
+        simulator.render(&Frame::default()).unwrap();
+
+        let frames = simulator.take_frames();
+
+        assert_eq!(1, frames.len());
+        assert_eq!(LedColor::Off, frames[0].frame.bars[0]);
+        assert!(simulator.frames().is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+//! # Sources
+//!
+//! Values polled from outside the display, e.g. a sensor on the same bus or a CI
+//! server, behind one trait so every source can be followed the same way.
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::thread;
+use std::time::Duration;
+
+use super::render::sample_frame;
+use super::{Bargraph, ColorMap, Error};
+
+/// What a [Sample](struct.Sample.html) means beyond its value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    /// The value needs attention, e.g. a meeting is about to start. The display blinks.
+    Alert,
+    /// The source is complete & won't change again, e.g. a finished copy.
+    Complete,
+    /// The source failed & won't change again, e.g. a failed build. The display blinks.
+    Failed,
+}
+
+/// A value polled from a [ValueSource](trait.ValueSource.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample {
+    /// The value as a fraction of a full display, from `0.0` (empty) to `1.0` (full).
+    /// Greater values overflow the display, which blinks.
+    pub value: f32,
+    /// The status of the source, if it has one.
+    pub status: Option<Status>,
+}
+
+impl Sample {
+    /// Create a Sample without a status.
+    pub fn new(value: f32) -> Self {
+        Sample {
+            value,
+            status: None,
+        }
+    }
+
+    /// Create a Sample with a status.
+    pub fn with_status(value: f32, status: Status) -> Self {
+        Sample {
+            value,
+            status: Some(status),
+        }
+    }
+
+    /// Whether the source won't change again.
+    pub fn is_finished(&self) -> bool {
+        match self.status {
+            Some(Status::Complete) | Some(Status::Failed) => true,
+            Some(Status::Alert) | None => false,
+        }
+    }
+}
+
+/// A source of values to display, polled for its latest [Sample](struct.Sample.html).
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::{Sample, Status, ValueSource};
+/// # fn main() {
+///
+/// // Counts up to a full display.
+/// struct Counter(u8);
+///
+/// impl ValueSource for Counter {
+///     type Error = String;
+///
+///     fn poll(&mut self) -> Result<Option<Sample>, String> {
+///         self.0 += 1;
+///
+///         Ok(Some(match self.0 {
+///             10 => Sample::with_status(1.0, Status::Complete),
+///             count => Sample::new(f32::from(count) / 10.0),
+///         }))
+///     }
+/// }
+///
+/// let mut counter = Counter(0);
+/// assert_eq!(Ok(Some(Sample::new(0.1))), counter.poll());
+///
+/// # }
+/// ```
+pub trait ValueSource {
+    /// The error returned when the source can't be polled.
+    type Error;
+
+    /// Poll the source, returning `None` if there isn't a new value yet.
+    fn poll(&mut self) -> Result<Option<Sample>, Self::Error>;
+}
+
+impl<S> ValueSource for Box<S>
+where
+    S: ValueSource + ?Sized,
+{
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Result<Option<Sample>, Self::Error> {
+        (**self).poll()
+    }
+}
+
+impl<I2C, E> Bargraph<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Update the Bargraph display with the sample, filling its fraction of the range
+    /// rounded to the nearest value, see [update()](#method.update).
+    ///
+    /// The display blinks if the value overflows, or the status is
+    /// [Alert](enum.Status.html#variant.Alert) or [Failed](enum.Status.html#variant.Failed).
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - The sample to display.
+    /// * `range` - Total number of values to display.
+    /// * `colors` - The color of each percentage of the range, otherwise the values
+    ///   are drawn with the color scheme.
+    /// * `show` - Whether to also show the display on-screen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is `0` or greater than the [resolution()](#method.resolution).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, Sample, Status};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    /// bargraph.update_sample(&Sample::with_status(0.5, Status::Alert), 24, None, false).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn update_sample(
+        &mut self,
+        sample: &Sample,
+        range: u8,
+        colors: Option<&ColorMap>,
+        show: bool,
+    ) -> Result<(), Error<E>> {
+        span!("update_sample", value = sample.value, range);
+        trace!(self.logger, "update_sample"; "sample" => ?sample, "range" => range);
+
+        let frame = sample_frame(
+            sample,
+            range,
+            self.resolution,
+            colors,
+            &self.look(),
+            self.reversed,
+        );
+
+        if (sample.value * f32::from(range)).round() > f32::from(range) {
+            log_warn!(self.logger, "Sample is greater than the display, setting display to blink";
+                      value => sample.value);
+        }
+
+        self.submit(frame)?;
+
+        if show {
+            self.show()?;
+        }
+
+        Ok(())
+    }
+
+    /// Display each value in turn, waiting `delay` after each, e.g. for a demo or a
+    /// test script. Returns once the last value has been displayed for the delay, or
+    /// at the first error.
+    ///
+    /// Each value is a fraction of the display, like a [Sample](struct.Sample.html),
+    /// drawn against the [resolution()](#method.resolution), see
+    /// [update_sample()](#method.update_sample).
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values to display, e.g. a range or an iterator that never ends.
+    /// * `delay` - How long to display each value for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::Bargraph;
+    /// # use std::time::Duration;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// // Fill the display in tenths.
+    /// let tenths = (0..=10).map(|tenth| tenth as f32 / 10.0);
+    /// bargraph.drive_from_iter(tenths, Duration::from_millis(10)).unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn drive_from_iter<I>(&mut self, values: I, delay: Duration) -> Result<(), Error<E>>
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        trace!(self.logger, "drive_from_iter"; "delay" => ?delay);
+
+        let range = self.resolution;
+
+        for value in values {
+            self.update_sample(&Sample::new(value), range, None, false)?;
+            thread::sleep(delay);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use {Frame, LedColor};
+
+    #[test]
+    fn is_finished() {
+        assert!(!Sample::new(0.5).is_finished());
+        assert!(!Sample::with_status(0.5, Status::Alert).is_finished());
+        assert!(Sample::with_status(1.0, Status::Complete).is_finished());
+        assert!(Sample::with_status(0.5, Status::Failed).is_finished());
+    }
+
+    #[test]
+    fn update_sample() {
+        let mut bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+
+        bargraph
+            .update_sample(&Sample::new(0.5), 24, None, false)
+            .unwrap();
+        let frame = bargraph.last_frame().unwrap();
+        assert_eq!(Frame::from_value(12, 24), frame);
+
+        bargraph
+            .update_sample(&Sample::with_status(0.5, Status::Alert), 24, None, false)
+            .unwrap();
+        assert!(bargraph.last_frame().unwrap().blink);
+
+        bargraph
+            .update_sample(&Sample::new(1.5), 24, None, false)
+            .unwrap();
+        let frame = bargraph.last_frame().unwrap();
+        assert!(frame.blink);
+        assert_eq!(LedColor::Red, frame.bars[23]);
+    }
+
+    #[test]
+    fn drive_from_iter() {
+        let mut bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+        bargraph.set_resolution(12);
+
+        bargraph
+            .drive_from_iter(vec![0.25, 0.5, 1.0], Duration::from_millis(1))
+            .unwrap();
+
+        assert_eq!(3, bargraph.metrics().frames);
+        // Each value is a single bar, its top bar.
+        assert_eq!(
+            "RRRRRRRRRRRR............",
+            bargraph.last_frame().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn update_sample_colored() {
+        let colors = ColorMap::new(vec![(0, LedColor::Green)]);
+        let mut bargraph = Bargraph::new(I2cMock::new(None), 0, None);
+
+        bargraph
+            .update_sample(&Sample::new(0.25), 12, Some(&colors), false)
+            .unwrap();
+        assert_eq!(
+            Frame::from_colored_value(3, 12, &colors),
+            bargraph.last_frame().unwrap()
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! # Tracing I2C
+//!
+//! An I2C device wrapper that logs every transaction, for debugging the protocol
+//! between a display and its `HT16K33` backpack.
+use hal::blocking::i2c::{Read, Write, WriteRead};
+
+use slog::Drain;
+
+use std::time::Instant;
+
+/// Wraps an I2C device, logging & counting every transaction.
+///
+/// Transactions are logged at the `debug` level with the address, register (the
+/// first byte written), the bytes written & read, how long it took and whether it
+/// succeeded.
+pub struct TracingI2c<T> {
+    i2c: T,
+    transactions: u64,
+    logger: slog::Logger,
+}
+
+impl<T> TracingI2c<T> {
+    /// Create a TracingI2c wrapping an I2C device.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C device to trace.
+    /// * `logger` - A logging instance.
+    ///
+    /// # Notes
+    ///
+    /// `logger = None` will log to the `slog-stdlog` drain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate ht16k33;
+    /// extern crate led_bargraph;
+    ///
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use led_bargraph::{Bargraph, TracingI2c};
+    /// # fn main() {
+    ///
+    /// let i2c = TracingI2c::new(I2cMock::new(None), None);
+    /// let address: u8 = 0;
+    ///
+    /// let mut bargraph = Bargraph::new(i2c, address, None);
+    ///
+    /// # }
+    /// ```
+    pub fn new<L>(i2c: T, logger: L) -> Self
+    where
+        L: Into<Option<slog::Logger>>,
+    {
+        let logger = logger
+            .into()
+            .unwrap_or_else(|| slog::Logger::root(slog_stdlog::StdLog.fuse(), o!()));
+
+        TracingI2c {
+            i2c,
+            transactions: 0,
+            logger,
+        }
+    }
+
+    /// The number of transactions, successful or not, made with the I2C device.
+    pub fn transactions(&self) -> u64 {
+        self.transactions
+    }
+
+    /// Destroy the TracingI2c, returning the wrapped I2C device.
+    pub fn into_inner(self) -> T {
+        self.i2c
+    }
+}
+
+impl<T> Write for TracingI2c<T>
+where
+    T: Write,
+{
+    type Error = T::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let start = Instant::now();
+        let result = self.i2c.write(address, bytes);
+        let elapsed = start.elapsed();
+
+        self.transactions += 1;
+
+        debug!(self.logger, "write";
+               "transaction" => self.transactions,
+               "address" => format!("{:#04x}", address),
+               "register" => bytes.first().map(|register| format!("{:#04x}", register)),
+               "bytes" => format!("{:02x?}", bytes),
+               "duration" => format!("{:?}", elapsed),
+               "ok" => result.is_ok());
+
+        result
+    }
+}
+
+impl<T> WriteRead for TracingI2c<T>
+where
+    T: WriteRead,
+{
+    type Error = T::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let start = Instant::now();
+        let result = self.i2c.write_read(address, bytes, buffer);
+        let elapsed = start.elapsed();
+
+        self.transactions += 1;
+
+        debug!(self.logger, "write_read";
+               "transaction" => self.transactions,
+               "address" => format!("{:#04x}", address),
+               "register" => bytes.first().map(|register| format!("{:#04x}", register)),
+               "bytes" => format!("{:02x?}", bytes),
+               "read" => format!("{:02x?}", buffer),
+               "duration" => format!("{:?}", elapsed),
+               "ok" => result.is_ok());
+
+        result
+    }
+}
+
+impl<T> Read for TracingI2c<T>
+where
+    T: Read,
+{
+    type Error = T::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let start = Instant::now();
+        let result = self.i2c.read(address, buffer);
+        let elapsed = start.elapsed();
+
+        self.transactions += 1;
+
+        debug!(self.logger, "read";
+               "transaction" => self.transactions,
+               "address" => format!("{:#04x}", address),
+               "read" => format!("{:02x?}", buffer),
+               "duration" => format!("{:?}", elapsed),
+               "ok" => result.is_ok());
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+
+    const ADDRESS: u8 = 0;
+
+    #[test]
+    fn new() {
+        let i2c = TracingI2c::new(I2cMock::new(None), None);
+
+        assert_eq!(0, i2c.transactions());
+    }
+
+    #[test]
+    fn write() {
+        let mut i2c = TracingI2c::new(I2cMock::new(None), None);
+
+        i2c.write(ADDRESS, &[0x00, 0xFF]).unwrap();
+        i2c.write(ADDRESS, &[0x81]).unwrap();
+
+        assert_eq!(2, i2c.transactions());
+        assert_eq!(0xFF, i2c.into_inner().data_values[0]);
+    }
+
+    #[test]
+    fn write_read() {
+        let mut i2c = TracingI2c::new(I2cMock::new(None), None);
+        i2c.write(ADDRESS, &[0x00, 0xAB]).unwrap();
+
+        let mut buffer = [0u8; 1];
+        i2c.write_read(ADDRESS, &[0x00], &mut buffer).unwrap();
+
+        assert_eq!(2, i2c.transactions());
+        assert_eq!([0xAB], buffer);
+    }
+}
@@ -0,0 +1,301 @@
+//! # Updater
+//!
+//! Drive a [Bargraph](struct.Bargraph.html) from a background thread fed by a channel
+//! of values, smoothing them, limiting how often the display is written & clearing it
+//! when the values stop.
+use hal::blocking::i2c::{Write, WriteRead};
+
+use std::fmt::Debug;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::{Bargraph, BARGRAPH_RESOLUTION};
+
+/// How the background updater of
+/// [Bargraph::spawn_updater()](struct.Bargraph.html#method.spawn_updater) displays
+/// the values it receives.
+///
+/// # Examples
+///
+/// ```
+/// extern crate led_bargraph;
+///
+/// use led_bargraph::UpdaterConfig;
+/// use std::time::Duration;
+/// # fn main() {
+///
+/// // Smooth noisy samples & clear the display if the sampler dies.
+/// let config = UpdaterConfig {
+///     range: 12,
+///     smoothing: 0.8,
+///     stale_after: Some(Duration::from_secs(60)),
+///     ..UpdaterConfig::default()
+/// };
+///
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdaterConfig {
+    /// The range the values are displayed against, `24` by default.
+    pub range: u8,
+    /// How much of the previous value is kept when a value is received, from `0.0`
+    /// (no smoothing, the default) to just below `1.0`.
+    pub smoothing: f32,
+    /// The shortest time between writes to the display, `50ms` by default. Values
+    /// received in between are combined & displayed once the time has passed.
+    pub min_interval: Duration,
+    /// Clear the display when no value is received for this long, never by default.
+    pub stale_after: Option<Duration>,
+}
+
+impl Default for UpdaterConfig {
+    fn default() -> Self {
+        UpdaterConfig {
+            range: BARGRAPH_RESOLUTION,
+            smoothing: 0.0,
+            min_interval: Duration::from_millis(50),
+            stale_after: None,
+        }
+    }
+}
+
+impl<I2C, E> Bargraph<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E> + Send + 'static,
+    E: Debug,
+{
+    /// Move the Bargraph to a background thread that displays the values sent to it,
+    /// see [UpdaterConfig](struct.UpdaterConfig.html).
+    ///
+    /// Values are in units of the range, e.g. `3.0` of `6`, & rounded to the nearest
+    /// value for display. Errors are logged by the background thread rather than
+    /// returned.
+    ///
+    /// The background thread displays any value still waiting & stops once the sender
+    /// is dropped, returning the Bargraph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is `0` or greater than the
+    /// [resolution()](#method.resolution), or the smoothing isn't from `0.0` to just
+    /// below `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate ht16k33;
+    /// # extern crate led_bargraph;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use led_bargraph::{Bargraph, UpdaterConfig};
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new(None);
+    /// # let address: u8 = 0;
+    ///
+    /// let bargraph = Bargraph::new(i2c, address, None);
+    /// let (sender, updater) = bargraph.spawn_updater(UpdaterConfig::default());
+    ///
+    /// for sample in [3.2, 7.9, 12.4].iter() {
+    ///     sender.send(*sample).unwrap();
+    /// }
+    ///
+    /// drop(sender);
+    /// let bargraph = updater.join().unwrap();
+    ///
+    /// # }
+    /// ```
+    pub fn spawn_updater(mut self, config: UpdaterConfig) -> (Sender<f32>, JoinHandle<Self>) {
+        assert!(
+            config.range > 0 && config.range <= self.resolution(),
+            "Range [{}] must be between [1] and [{}]",
+            config.range,
+            self.resolution()
+        );
+        assert!(
+            config.smoothing >= 0.0 && config.smoothing < 1.0,
+            "Smoothing [{}] must be from [0.0] to below [1.0]",
+            config.smoothing
+        );
+
+        let (sender, receiver) = mpsc::channel::<f32>();
+
+        let thread = thread::spawn(move || {
+            // The smoothed value, & whether it's waiting to be displayed.
+            let mut smoothed: Option<f32> = None;
+            let mut pending = false;
+            let mut last_received: Option<Instant> = None;
+            let mut last_written: Option<Instant> = None;
+
+            loop {
+                // Wake up when the pending value can be written, or the values go stale.
+                let write_in = last_written
+                    .filter(|_| pending)
+                    .map(|written| config.min_interval.saturating_sub(written.elapsed()));
+                let stale_in = match (last_received, config.stale_after, smoothed) {
+                    (Some(received), Some(stale_after), Some(_)) => {
+                        Some(stale_after.saturating_sub(received.elapsed()))
+                    }
+                    _ => None,
+                };
+
+                let received = match write_in.into_iter().chain(stale_in).min() {
+                    Some(timeout) => receiver.recv_timeout(timeout),
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+
+                match received {
+                    Ok(value) if value.is_nan() => {
+                        warn!(self.logger, "Ignoring a value that isn't a number");
+                    }
+                    Ok(value) => {
+                        smoothed = Some(match smoothed {
+                            Some(previous) => {
+                                previous * config.smoothing + value * (1.0 - config.smoothing)
+                            }
+                            None => value,
+                        });
+                        pending = true;
+                        last_received = Some(Instant::now());
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if let (true, Some(value)) = (pending, smoothed) {
+                            self.display_value(value, config.range);
+                        }
+
+                        break;
+                    }
+                }
+
+                let can_write =
+                    last_written.is_none_or(|written| written.elapsed() >= config.min_interval);
+
+                if let (true, true, Some(value)) = (pending, can_write, smoothed) {
+                    self.display_value(value, config.range);
+                    pending = false;
+                    last_written = Some(Instant::now());
+                }
+
+                let stale = match (last_received, config.stale_after) {
+                    (Some(received), Some(stale_after)) => received.elapsed() >= stale_after,
+                    _ => false,
+                };
+
+                if stale && smoothed.is_some() {
+                    warn!(self.logger, "No value received, clearing the display";
+                          "stale_after" => ?config.stale_after);
+
+                    smoothed = None;
+                    pending = false;
+
+                    if let Err(e) = self.clear() {
+                        error!(self.logger, "Failed to clear the display"; "error" => %e);
+                    }
+                }
+            }
+
+            self
+        });
+
+        (sender, thread)
+    }
+
+    // Display the value rounded to the nearest whole value, logging any error.
+    fn display_value(&mut self, value: f32, range: u8) {
+        let rounded = value.round().clamp(0.0, f32::from(u8::MAX)) as u8;
+
+        if let Err(e) = self.update(rounded, range, false) {
+            error!(self.logger, "Failed to display a value";
+                   "value" => value, "range" => range, "error" => %e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ht16k33::i2c_mock::I2cMock;
+    use Frame;
+
+    const ADDRESS: u8 = 0;
+
+    fn spawn(config: UpdaterConfig) -> (Sender<f32>, JoinHandle<Bargraph<I2cMock>>) {
+        Bargraph::new(I2cMock::new(None), ADDRESS, None).spawn_updater(config)
+    }
+
+    #[test]
+    fn spawn_updater() {
+        let (sender, updater) = spawn(UpdaterConfig {
+            range: 6,
+            ..UpdaterConfig::default()
+        });
+
+        sender.send(2.6).unwrap();
+        drop(sender);
+
+        let bargraph = updater.join().unwrap();
+        assert_eq!(Some(Frame::from_value(3, 6)), bargraph.last_frame);
+    }
+
+    #[test]
+    fn spawn_updater_smoothing() {
+        let (sender, updater) = spawn(UpdaterConfig {
+            range: 6,
+            smoothing: 0.5,
+            ..UpdaterConfig::default()
+        });
+
+        sender.send(0.0).unwrap();
+        sender.send(4.0).unwrap();
+        drop(sender);
+
+        let bargraph = updater.join().unwrap();
+        assert_eq!(Some(Frame::from_value(2, 6)), bargraph.last_frame);
+    }
+
+    #[test]
+    fn spawn_updater_rate_limited() {
+        let (sender, updater) = spawn(UpdaterConfig {
+            range: 6,
+            min_interval: Duration::from_secs(3600),
+            ..UpdaterConfig::default()
+        });
+
+        for value in 1..=5 {
+            sender.send(value as f32).unwrap();
+        }
+        drop(sender);
+
+        // The first value, then the last value when the sender is dropped.
+        let bargraph = updater.join().unwrap();
+        assert_eq!(2, bargraph.metrics().frames);
+        assert_eq!(Some(Frame::from_value(5, 6)), bargraph.last_frame);
+    }
+
+    #[test]
+    fn spawn_updater_stale() {
+        let (sender, updater) = spawn(UpdaterConfig {
+            range: 6,
+            stale_after: Some(Duration::from_millis(10)),
+            ..UpdaterConfig::default()
+        });
+
+        sender.send(3.0).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        drop(sender);
+
+        // The display was cleared.
+        let bargraph = updater.join().unwrap();
+        assert_eq!(1, bargraph.metrics().frames);
+        assert_eq!(None, bargraph.last_frame);
+    }
+
+    #[test]
+    #[should_panic]
+    fn spawn_updater_range_too_large() {
+        spawn(UpdaterConfig {
+            range: BARGRAPH_RESOLUTION + 1,
+            ..UpdaterConfig::default()
+        });
+    }
+}
@@ -0,0 +1,311 @@
+//! # WASM
+//!
+//! A simulated display for the browser, enabled with the `wasm` feature. The
+//! [Frame](../struct.Frame.html) of each update is calculated by the same rendering
+//! layer as the [Bargraph](../struct.Bargraph.html), and the page in `www/` draws its
+//! bars, so integrations & animations can be developed without the backpack.
+//!
+//! Build the module with:
+//!
+//! ```text
+//! cargo rustc --release --lib --target wasm32-unknown-unknown --no-default-features \
+//!     --features wasm --crate-type cdylib
+//! cp target/wasm32-unknown-unknown/release/led_bargraph.wasm www/
+//! ```
+//!
+//! Only the rendering layer is used, the `Bargraph` itself can't run in the browser as
+//! it measures time with `std::time::Instant`.
+use std::convert::Infallible;
+use std::os::raw::c_int;
+
+use super::{Frame, LedColor, Render, BARGRAPH_RESOLUTION};
+
+/// Returned by the `simulator_*` functions when they succeed.
+pub const SIMULATOR_OK: c_int = 0;
+/// Returned by the `simulator_*` functions when they fail.
+pub const SIMULATOR_ERROR: c_int = -1;
+
+/// An opaque handle to a simulated display, created with `simulator_new()` and
+/// destroyed with `simulator_free()`.
+#[derive(Debug, Default)]
+pub struct WasmSimulator {
+    frame: Frame,
+}
+
+impl Render for WasmSimulator {
+    type Error = Infallible;
+
+    fn render(&mut self, frame: &Frame) -> Result<(), Infallible> {
+        self.frame = *frame;
+
+        Ok(())
+    }
+}
+
+// Render the frame calculated for a `range` from `1` to `max_range`, the frame
+// constructors panic otherwise, which would abort the module.
+unsafe fn render_with<F>(simulator: *mut WasmSimulator, range: u8, max_range: u8, frame: F) -> c_int
+where
+    F: FnOnce() -> Frame,
+{
+    let simulator = match simulator.as_mut() {
+        Some(simulator) => simulator,
+        None => return SIMULATOR_ERROR,
+    };
+
+    if range == 0 || range > max_range {
+        return SIMULATOR_ERROR;
+    }
+
+    match simulator.render(&frame()) {
+        Ok(()) => SIMULATOR_OK,
+        Err(never) => match never {},
+    }
+}
+
+/// Create a simulated display with all bars off.
+#[no_mangle]
+pub extern "C" fn simulator_new() -> *mut WasmSimulator {
+    Box::into_raw(Box::default())
+}
+
+/// Show `range` total values with all values from `0` to `value` filled, see
+/// [Frame::from_value()](../struct.Frame.html#method.from_value).
+///
+/// Returns `SIMULATOR_OK` on success, otherwise `SIMULATOR_ERROR`, including when
+/// `range` is `0` or greater than the display's resolution.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_update(
+    simulator: *mut WasmSimulator,
+    value: u8,
+    range: u8,
+) -> c_int {
+    render_with(simulator, range, BARGRAPH_RESOLUTION, || {
+        Frame::from_value(value, range)
+    })
+}
+
+/// Show a value that can be negative, see
+/// [Frame::from_signed_value()](../struct.Frame.html#method.from_signed_value).
+///
+/// Returns `SIMULATOR_OK` on success, otherwise `SIMULATOR_ERROR`, including when
+/// `range` is `0` or greater than half of the display's resolution.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_update_signed(
+    simulator: *mut WasmSimulator,
+    value: i8,
+    range: u8,
+) -> c_int {
+    render_with(simulator, range, BARGRAPH_RESOLUTION / 2, || {
+        Frame::from_signed_value(value, range)
+    })
+}
+
+/// Show only the bar of the value, see [Frame::from_dot()](../struct.Frame.html#method.from_dot).
+///
+/// Returns `SIMULATOR_OK` on success, otherwise `SIMULATOR_ERROR`.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_update_dot(
+    simulator: *mut WasmSimulator,
+    value: u8,
+    range: u8,
+) -> c_int {
+    render_with(simulator, range, BARGRAPH_RESOLUTION, || {
+        Frame::from_dot(value, range)
+    })
+}
+
+/// Show two values on the red & green LEDs of the same bars, see
+/// [Frame::from_channels()](../struct.Frame.html#method.from_channels).
+///
+/// Returns `SIMULATOR_OK` on success, otherwise `SIMULATOR_ERROR`.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_update_channels(
+    simulator: *mut WasmSimulator,
+    red_value: u8,
+    green_value: u8,
+    range: u8,
+) -> c_int {
+    render_with(simulator, range, BARGRAPH_RESOLUTION, || {
+        Frame::from_channels(red_value, green_value, range)
+    })
+}
+
+/// Turn off every bar & stop blinking.
+///
+/// Returns `SIMULATOR_OK` on success, otherwise `SIMULATOR_ERROR`.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_clear(simulator: *mut WasmSimulator) -> c_int {
+    render_with(
+        simulator,
+        BARGRAPH_RESOLUTION,
+        BARGRAPH_RESOLUTION,
+        Frame::default,
+    )
+}
+
+/// The color of a bar, from the bottom (`0`) to the top of the display: `0` is off,
+/// `1` green, `2` red & `3` yellow.
+///
+/// Returns `SIMULATOR_ERROR` if the bar is beyond the display.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_bar(simulator: *const WasmSimulator, bar: u8) -> c_int {
+    let color = simulator
+        .as_ref()
+        .and_then(|simulator| simulator.frame.bars.get(bar as usize));
+
+    match color {
+        Some(LedColor::Off) => 0,
+        Some(LedColor::Green) => 1,
+        Some(LedColor::Red) => 2,
+        Some(LedColor::Yellow) => 3,
+        None => SIMULATOR_ERROR,
+    }
+}
+
+/// Whether the display is blinking, `1` if it is, otherwise `0`.
+///
+/// Returns `SIMULATOR_ERROR` for a `NULL` handle.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_blink(simulator: *const WasmSimulator) -> c_int {
+    match simulator.as_ref() {
+        Some(simulator) => c_int::from(simulator.frame.blink),
+        None => SIMULATOR_ERROR,
+    }
+}
+
+/// Destroy a simulated display.
+///
+/// # Safety
+///
+/// `simulator` must be `NULL` or a handle returned by `simulator_new()`, and must not
+/// be used after it's freed.
+#[no_mangle]
+pub unsafe extern "C" fn simulator_free(simulator: *mut WasmSimulator) {
+    if !simulator.is_null() {
+        drop(Box::from_raw(simulator));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    // The bars of the simulated display, as the page reads them.
+    unsafe fn bars(simulator: *const WasmSimulator) -> Vec<c_int> {
+        (0..BARGRAPH_RESOLUTION)
+            .map(|bar| simulator_bar(simulator, bar))
+            .collect()
+    }
+
+    #[test]
+    fn new_and_free() {
+        unsafe {
+            let simulator = simulator_new();
+            assert!(!simulator.is_null());
+            assert_eq!(vec![0; BARGRAPH_RESOLUTION as usize], bars(simulator));
+            assert_eq!(0, simulator_blink(simulator));
+
+            simulator_free(simulator);
+        }
+    }
+
+    #[test]
+    fn update() {
+        unsafe {
+            let simulator = simulator_new();
+            assert_eq!(SIMULATOR_OK, simulator_update(simulator, 1, 12));
+
+            let frame = Frame::from_value(1, 12);
+            assert_eq!(3, simulator_bar(simulator, 0));
+            assert_eq!(2, simulator_bar(simulator, 1));
+            assert_eq!(0, simulator_bar(simulator, 2));
+            assert_eq!(1, simulator_bar(simulator, 3));
+            assert_eq!(frame, (*simulator).frame);
+
+            assert_eq!(SIMULATOR_OK, simulator_update(simulator, 25, 24));
+            assert_eq!(1, simulator_blink(simulator));
+
+            assert_eq!(SIMULATOR_OK, simulator_clear(simulator));
+            assert_eq!(Frame::default(), (*simulator).frame);
+
+            simulator_free(simulator);
+        }
+    }
+
+    #[test]
+    fn update_styles() {
+        unsafe {
+            let simulator = simulator_new();
+
+            assert_eq!(SIMULATOR_OK, simulator_update_signed(simulator, -3, 6));
+            assert_eq!(Frame::from_signed_value(-3, 6), (*simulator).frame);
+
+            assert_eq!(SIMULATOR_OK, simulator_update_dot(simulator, 3, 6));
+            assert_eq!(Frame::from_dot(3, 6), (*simulator).frame);
+
+            assert_eq!(SIMULATOR_OK, simulator_update_channels(simulator, 2, 4, 6));
+            assert_eq!(Frame::from_channels(2, 4, 6), (*simulator).frame);
+
+            simulator_free(simulator);
+        }
+    }
+
+    #[test]
+    fn update_invalid() {
+        unsafe {
+            let simulator = simulator_new();
+            assert_eq!(SIMULATOR_OK, simulator_update(simulator, 5, 6));
+
+            assert_eq!(SIMULATOR_ERROR, simulator_update(simulator, 0, 0));
+            assert_eq!(SIMULATOR_ERROR, simulator_update(simulator, 5, 25));
+            assert_eq!(SIMULATOR_ERROR, simulator_update_dot(simulator, 5, 0));
+            assert_eq!(SIMULATOR_ERROR, simulator_update_signed(simulator, -5, 13));
+            assert_eq!(Frame::from_value(5, 6), (*simulator).frame);
+
+            assert_eq!(SIMULATOR_ERROR, simulator_bar(simulator, 24));
+
+            simulator_free(simulator);
+        }
+    }
+
+    #[test]
+    fn null_handle() {
+        unsafe {
+            assert_eq!(SIMULATOR_ERROR, simulator_update(ptr::null_mut(), 5, 6));
+            assert_eq!(SIMULATOR_ERROR, simulator_clear(ptr::null_mut()));
+            assert_eq!(SIMULATOR_ERROR, simulator_bar(ptr::null(), 0));
+            assert_eq!(SIMULATOR_ERROR, simulator_blink(ptr::null()));
+            simulator_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Assert the exact I2C transactions produced by a Bargraph, using the
+//! transaction-expectation mock from `embedded-hal-mock`.
+//!
+//! The same approach works for testing code that drives a Bargraph: build the list
+//! of expected transactions, hand a clone of the mock to the Bargraph, then call
+//! `done()` to check that every expected transaction happened.
+extern crate embedded_hal_mock;
+extern crate led_bargraph;
+extern crate shared_bus;
+
+use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+use embedded_hal_mock::pin::{Mock as PinMock, State as PinState, Transaction as PinTransaction};
+use embedded_hal_mock::MockError;
+
+use led_bargraph::{Bargraph, Error, Operation};
+
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const ADDRESS: u8 = 0x70;
+
+// HT16K33 commands.
+const OSCILLATOR_ON: u8 = 0x21;
+const DISPLAY_OFF: u8 = 0x80;
+const DISPLAY_ON: u8 = 0x81;
+const DISPLAY_ONE_HZ: u8 = 0x85;
+const DIMMING_MAX: u8 = 0xEF;
+const DISPLAY_DATA_ADDRESS: u8 = 0x00;
+
+// Writing the display buffer sends the starting address followed by all 16 rows.
+fn write_rows(rows: [u8; 6]) -> I2cTransaction {
+    let mut bytes = vec![DISPLAY_DATA_ADDRESS];
+    bytes.extend_from_slice(&rows);
+    bytes.extend_from_slice(&[0; 10]);
+
+    I2cTransaction::write(ADDRESS, bytes)
+}
+
+#[test]
+fn initialize() {
+    let expectations = [
+        I2cTransaction::write(ADDRESS, vec![OSCILLATOR_ON]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_OFF]),
+        I2cTransaction::write(ADDRESS, vec![DIMMING_MAX]),
+        write_rows([0; 6]),
+    ];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.initialize().unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn clear() {
+    let expectations = [write_rows([0; 6])];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.clear().unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn power_cycle() {
+    let expectations = [
+        I2cTransaction::write(ADDRESS, vec![OSCILLATOR_ON]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_OFF]),
+        I2cTransaction::write(ADDRESS, vec![DIMMING_MAX]),
+        write_rows([0; 6]),
+    ];
+    let pin_expectations = [
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::High),
+    ];
+    let mut pin = PinMock::new(&pin_expectations);
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.set_power_pin(pin.clone());
+    bargraph.power_cycle().unwrap();
+
+    bargraph.destroy().done();
+    pin.done();
+}
+
+#[test]
+fn power_off_error() {
+    let pin_expectations =
+        [PinTransaction::set(PinState::Low).with_error(MockError::Io(ErrorKind::Other))];
+    let mut pin = PinMock::new(&pin_expectations);
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&[]), ADDRESS, None);
+    bargraph.set_power_pin(pin.clone());
+
+    assert_eq!(
+        Err(Error::Power {
+            on: false,
+            source: "Io(Other)".to_string(),
+        }),
+        bargraph.power_off()
+    );
+
+    bargraph.destroy().done();
+    pin.done();
+}
+
+#[test]
+fn clear_with_fade() {
+    // The brightness steps down from the maximum, then the display is cleared &
+    // the brightness restored.
+    let mut expectations: Vec<I2cTransaction> = (0..DIMMING_MAX & 0x0F)
+        .rev()
+        .map(|brightness| I2cTransaction::write(ADDRESS, vec![DIMMING_MAX & 0xF0 | brightness]))
+        .collect();
+    expectations.push(write_rows([0; 6]));
+    expectations.push(I2cTransaction::write(ADDRESS, vec![DIMMING_MAX]));
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.clear_with_fade(Duration::from_millis(1)).unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_half() {
+    // The filled bars are red, the unfilled bars are green.
+    let expectations = [
+        write_rows([0x0F, 0xF0, 0x0F, 0xF0, 0x0F, 0xF0]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ON]),
+    ];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.update(12, 24, false).unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_full() {
+    let expectations = [
+        write_rows([0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ON]),
+    ];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.update(24, 24, false).unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_overflow_blinks() {
+    let expectations = [
+        write_rows([0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ONE_HZ]),
+    ];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.update(25, 24, false).unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_error() {
+    let expectations = [write_rows([0x0F, 0xF0, 0x0F, 0xF0, 0x0F, 0xF0])
+        .with_error(MockError::Io(ErrorKind::Other))];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+
+    assert_eq!(
+        Err(Error::I2c {
+            operation: Operation::WriteDisplayBuffer,
+            register: DISPLAY_DATA_ADDRESS,
+            attempts: 1,
+            source: MockError::Io(ErrorKind::Other),
+        }),
+        bargraph.update(12, 24, false)
+    );
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_error_recovers_bus() {
+    let failed = || {
+        write_rows([0x0F, 0xF0, 0x0F, 0xF0, 0x0F, 0xF0]).with_error(MockError::Io(ErrorKind::Other))
+    };
+    let expectations = [
+        failed(),
+        failed(),
+        // The bus is recovered & the display re-initialized.
+        I2cTransaction::write(ADDRESS, vec![OSCILLATOR_ON]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_OFF]),
+        I2cTransaction::write(ADDRESS, vec![DIMMING_MAX]),
+        write_rows([0; 6]),
+        write_rows([0x0F, 0xF0, 0x0F, 0xF0, 0x0F, 0xF0]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ON]),
+    ];
+
+    let recoveries = Arc::new(AtomicUsize::new(0));
+    let counter = recoveries.clone();
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.set_bus_recovery(2, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    assert!(bargraph.update(12, 24, false).is_err());
+    assert_eq!(0, recoveries.load(Ordering::SeqCst));
+    assert!(bargraph.update(12, 24, false).is_err());
+    assert_eq!(1, recoveries.load(Ordering::SeqCst));
+    bargraph.update(12, 24, false).unwrap();
+
+    assert_eq!(1, bargraph.metrics().bus_recoveries);
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_unchanged_blink_is_not_rewritten() {
+    let expectations = [
+        write_rows([0x0F, 0xF0, 0x0F, 0xF0, 0x0F, 0xF0]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ON]),
+        write_rows([0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00]),
+        write_rows([0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ONE_HZ]),
+    ];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.update(12, 24, false).unwrap();
+    bargraph.update(24, 24, false).unwrap();
+    bargraph.update(25, 24, false).unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+fn update_unchanged_is_skipped() {
+    let expectations = [
+        write_rows([0x0F, 0xF0, 0x0F, 0xF0, 0x0F, 0xF0]),
+        I2cTransaction::write(ADDRESS, vec![DISPLAY_ON]),
+        write_rows([0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00]),
+    ];
+
+    let mut bargraph = Bargraph::new(I2cMock::new(&expectations), ADDRESS, None);
+    bargraph.set_skip_unchanged(true);
+    bargraph.update(12, 24, false).unwrap();
+    bargraph.update(12, 24, false).unwrap();
+    bargraph.update(24, 24, false).unwrap();
+
+    bargraph.destroy().done();
+}
+
+#[test]
+#[cfg(feature = "sensors")]
+fn shared_bus_interleaves_devices() {
+    use led_bargraph::{Co2Sensor, Sample, Scd, ValueSource};
+    use shared_bus::BusManagerStd;
+
+    const SENSOR_ADDRESS: u8 = 0x62;
+
+    let expectations = [
+        write_rows([0; 6]),
+        I2cTransaction::write(SENSOR_ADDRESS, vec![0xE4, 0xB8]),
+        I2cTransaction::read(SENSOR_ADDRESS, vec![0x80, 0x06, 0x04]),
+        I2cTransaction::write(SENSOR_ADDRESS, vec![0xEC, 0x05]),
+        I2cTransaction::read(
+            SENSOR_ADDRESS,
+            vec![0x01, 0xF4, 0x33, 0x66, 0x67, 0xA2, 0x5E, 0xB9, 0x3C],
+        ),
+        write_rows([0; 6]),
+    ];
+
+    let i2c = I2cMock::new(&expectations);
+    let bus = BusManagerStd::new(i2c.clone());
+    let mut bargraph = Bargraph::new(bus.acquire_i2c(), ADDRESS, None);
+    let mut sensor = Co2Sensor::new(bus.acquire_i2c(), Scd::Scd41, 2000);
+
+    bargraph.clear().unwrap();
+
+    assert_eq!(Ok(Some(Sample::new(0.25))), sensor.poll());
+
+    bargraph.clear().unwrap();
+
+    i2c.clone().done();
+}